@@ -116,6 +116,7 @@ pub fn parse_aisle_config(input: String) -> Arc<AisleConf> {
 }
 
 #[uniffi::export]
+#[allow(clippy::ptr_arg)] // uniffi needs the Vec
 pub fn combine_ingredients(ingredients: &Vec<Ingredient>) -> IngredientList {
     let indices = (0..ingredients.len()).map(|i| i as u32).collect();
     combine_ingredients_selected(ingredients, &indices)
@@ -199,6 +199,7 @@ fn extract_quantity(value: &OriginalScalableValue) -> Value {
     match value {
         OriginalScalableValue::Fixed(value) => extract_value(value),
         OriginalScalableValue::Linear(value) => extract_value(value),
+        OriginalScalableValue::NonLinear { value, .. } => extract_value(value),
         OriginalScalableValue::ByServings(values) => extract_value(values.first().unwrap()),
     }
 }
@@ -210,7 +211,7 @@ fn extract_value(value: &OriginalValue) -> Value {
             start: start.value(),
             end: end.value(),
         },
-        OriginalValue::Text(value) => Value::Text {
+        OriginalValue::Text(value) | OriginalValue::Indefinite(value) => Value::Text {
             value: value.to_string(),
         },
     }
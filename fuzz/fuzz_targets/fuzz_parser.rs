@@ -6,5 +6,5 @@ use cooklang::{CooklangParser, Extensions, Converter};
 
 fuzz_target!(|contents: &str| {
     let parser = CooklangParser::new(Extensions::all(), Converter::default());
-    let _ = parser.parse(&contents);
+    let _ = parser.parse(contents);
 });
@@ -17,6 +17,12 @@ pub struct State {
     extensions: Extensions,
 }
 
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen]
 impl State {
     #[wasm_bindgen(constructor)]
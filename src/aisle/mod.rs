@@ -80,7 +80,7 @@ impl AisleConf<'_> {
 
     /// Returns a reversed configuration, where each ingredient has a
     /// corresponding [`IngredientInfo`]
-    pub fn ingredients_info(&self) -> HashMap<&str, IngredientInfo> {
+    pub fn ingredients_info(&self) -> HashMap<&str, IngredientInfo<'_>> {
         let mut map = HashMap::with_capacity(self.len.get());
         for cat in &self.categories {
             for igr in &cat.ingredients {
@@ -90,7 +90,7 @@ impl AisleConf<'_> {
 
                 for name in &igr.names {
                     let info = IngredientInfo {
-                        name: *name,
+                        name,
                         common_name,
                         category: cat.name,
                     };
@@ -104,7 +104,7 @@ impl AisleConf<'_> {
 }
 
 /// Parse an [`AisleConf`] with the cooklang shopping list format
-pub fn parse(input: &str) -> Result<AisleConf, AisleConfError> {
+pub fn parse(input: &str) -> Result<AisleConf<'_>, AisleConfError> {
     let pairs =
         AisleConfParser::parse(Rule::shopping_list, input).map_err(|e| AisleConfError::Parse {
             span: e.location.into(),
@@ -206,7 +206,7 @@ pub enum AisleConfError {
 }
 
 impl RichError for AisleConfError {
-    fn labels(&self) -> Cow<[Label]> {
+    fn labels(&self) -> Cow<'_, [Label]> {
         use crate::error::label;
         match self {
             AisleConfError::Parse { span, .. } => vec![label!(span)],
@@ -230,7 +230,7 @@ impl RichError for AisleConfError {
         .into()
     }
 
-    fn hints(&self) -> Cow<[CowStr]> {
+    fn hints(&self) -> Cow<'_, [CowStr]> {
         match self {
             AisleConfError::DuplicateCategory { .. } => {
                 vec!["Remove the duplicate category".into()]
@@ -1,17 +1,19 @@
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use regex::Regex;
 
-use crate::convert::{Converter, PhysicalQuantity};
+use crate::convert::{convert_f64, Converter, Duration, DurationUnit, PhysicalQuantity, Unit};
 use crate::error::{label, CowStr, PassResult, SourceDiag, SourceReport};
 use crate::located::Located;
-use crate::metadata::{check_std_entry, StdKey};
+use crate::metadata::{check_std_entry, StdKey, StdValue};
 use crate::parser::{
     self, BlockKind, Event, IntermediateData, IntermediateRefMode, IntermediateTargetKind,
     Modifiers,
 };
 use crate::quantity::{Quantity, QuantityValue, ScalableValue, UnitInfo, Value};
+use crate::scale::ScalingLaw;
 use crate::span::Span;
 use crate::text::Text;
 use crate::{model::*, Extensions, ParseOptions};
@@ -83,6 +85,7 @@ pub fn parse_events<'i, 'c>(
             cookware: Default::default(),
             timers: Default::default(),
             inline_quantities: Default::default(),
+            source_map: Default::default(),
             data: crate::scale::Servings(None),
         },
         current_section: Section::default(),
@@ -90,6 +93,8 @@ pub fn parse_events<'i, 'c>(
         define_mode: DefineMode::All,
         duplicate_mode: DuplicateMode::New,
         auto_scale_ingredients: false,
+        timer_scaling: ScalingLaw::Fixed,
+        cookware_scaling: ScalingLaw::Fixed,
         old_style_metadata: true,
         old_style_metadata_used: vec![],
         ctx,
@@ -113,6 +118,8 @@ struct RecipeCollector<'i, 'c> {
     define_mode: DefineMode,
     duplicate_mode: DuplicateMode,
     auto_scale_ingredients: bool,
+    timer_scaling: ScalingLaw,
+    cookware_scaling: ScalingLaw,
     old_style_metadata: bool,
     old_style_metadata_used: Vec<Span>,
     ctx: SourceReport,
@@ -198,7 +205,8 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
                 item @ (Event::Text(_)
                 | Event::Ingredient(_)
                 | Event::Cookware(_)
-                | Event::Timer(_)) => match &mut current_block {
+                | Event::Timer(_)
+                | Event::InlineQuantity(_)) => match &mut current_block {
                     Some(BlockBuffer::Step(items)) => self.in_step(item, items),
                     Some(BlockBuffer::Text(text)) => self.in_text(item, text),
                     None => panic!("Content outside block"),
@@ -247,8 +255,11 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
                 let mut to_remove = Vec::new();
                 for (key, value) in self.content.metadata.map.iter() {
                     if let Some(sk) = key.as_str().and_then(|s| StdKey::from_str(s).ok()) {
-                        match check_std_entry(sk, value, &self.converter) {
-                            Ok(Some(servings)) => self.content.data = servings,
+                        match check_std_entry(sk, value, self.extensions, self.converter) {
+                            Ok(Some(StdValue::Servings(servings))) => self.content.data = servings,
+                            Ok(Some(StdValue::Yield(q))) => {
+                                self.content.metadata.recipe_yield = Some(q)
+                            }
                             Ok(None) => {}
                             Err(err) => {
                                 // TODO can we get the position of the key value pair inside yaml_text?
@@ -330,6 +341,21 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
                     "false" | "default" => self.auto_scale_ingredients = false,
                     _ => self.ctx.error(invalid_value(vec!["true", "false"])),
                 },
+                "timer scaling" | "timer_scaling" | "cookware scaling" | "cookware_scaling"
+                    if self.extensions.contains(Extensions::SCALING_LAWS) =>
+                {
+                    let law = match value_t.as_ref() {
+                        "default" => Some(ScalingLaw::Fixed),
+                        other => ScalingLaw::from_str(other).ok(),
+                    };
+                    match law {
+                        Some(law) if config_key.starts_with("timer") => self.timer_scaling = law,
+                        Some(law) => self.cookware_scaling = law,
+                        None => self.ctx.error(invalid_value(vec![
+                            "fixed", "linear", "sqrt", "ceil", "whole", "half",
+                        ])),
+                    }
+                }
                 _ => {
                     self.ctx.warn(
                         warning!(
@@ -337,7 +363,7 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
                             label!(key.span())
                         )
                         .hint(
-                            "Possible config keys are '[mode]', '[duplicate]' and '[auto scale]'",
+                            "Possible config keys are '[mode]', '[duplicate]', '[auto scale]', '[timer scaling]' and '[cookware scaling]'",
                         ),
                     );
                     if self.old_style_metadata {
@@ -378,11 +404,13 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
             let check_result = crate::metadata::check_std_entry(
                 sp_key,
                 self.content.metadata.map.get(key_t.as_ref()).unwrap(),
+                self.extensions,
                 self.converter,
             );
 
             match check_result {
-                Ok(Some(servings)) => self.content.data = servings,
+                Ok(Some(StdValue::Servings(servings))) => self.content.data = servings,
+                Ok(Some(StdValue::Yield(q))) => self.content.metadata.recipe_yield = Some(q),
                 Ok(None) => {}
                 Err(err) => {
                     self.ctx.warn(
@@ -481,6 +509,13 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
 
                     let mut haystack = t.as_ref();
                     while let Some((before, temperature, after)) = find_temperature(haystack, re) {
+                        let start = t.len() - haystack.len() + before.len();
+                        let end = t.len() - after.len();
+                        self.content.source_map.inline_quantities.push(Span::new(
+                            text.source_offset(start),
+                            text.source_offset(end),
+                        ));
+
                         if !before.is_empty() {
                             items.push(Item::Text {
                                 value: before.to_string(),
@@ -490,7 +525,10 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
                         items.push(Item::InlineQuantity {
                             index: self.content.inline_quantities.len(),
                         });
-                        self.content.inline_quantities.push(temperature);
+                        self.content.inline_quantities.push(Quantity {
+                            value: ScalableValue::Fixed(temperature.value),
+                            unit: temperature.unit,
+                        });
 
                         haystack = after;
                     }
@@ -515,6 +553,21 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
             Event::Timer(i) => items.push(Item::Timer {
                 index: self.timer(i),
             }),
+            Event::InlineQuantity(q) => {
+                if self.define_mode == DefineMode::Components {
+                    self.ctx.warn(warning!(
+                        "Ignoring inline quantity in define components mode",
+                        label!(q.span())
+                    ));
+                    return;
+                }
+                self.content.source_map.inline_quantities.push(q.span());
+                let quantity = self.quantity(q, false, ScalingLaw::Fixed);
+                items.push(Item::InlineQuantity {
+                    index: self.content.inline_quantities.len(),
+                });
+                self.content.inline_quantities.push(quantity);
+            }
 
             _ => panic!("Unexpected event in step: {item:?}"),
         };
@@ -523,7 +576,10 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
     fn in_text(&mut self, ev: Event<'i>, s: &mut String) {
         match ev {
             Event::Text(t) => s.push_str(t.text().as_ref()),
-            Event::Ingredient(_) | Event::Cookware(_) | Event::Timer(_) => {
+            Event::Ingredient(_)
+            | Event::Cookware(_)
+            | Event::Timer(_)
+            | Event::InlineQuantity(_) => {
                 assert_eq!(
                     self.define_mode,
                     DefineMode::Text,
@@ -535,6 +591,7 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
                     Event::Ingredient(i) => ("ingredient", i.span()),
                     Event::Cookware(c) => ("cookware", c.span()),
                     Event::Timer(t) => ("timer", t.span()),
+                    Event::InlineQuantity(q) => ("inline quantity", q.span()),
                     _ => unreachable!(),
                 };
                 self.ctx
@@ -554,7 +611,10 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
         let mut new_igr = Ingredient {
             name: name.into_owned(),
             alias: ingredient.alias.map(|t| t.text_trimmed().into_owned()),
-            quantity: ingredient.quantity.clone().map(|q| self.quantity(q, true)),
+            quantity: ingredient
+                .quantity
+                .clone()
+                .map(|q| self.quantity(q, true, ScalingLaw::Fixed)),
             note: ingredient.note.map(|n| n.text_trimmed().into_owned()),
             modifiers: ingredient.modifiers.into_inner(),
             relation: IngredientRelation::definition(
@@ -726,6 +786,12 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
             }
         }
 
+        self.content.source_map.ingredients.push(
+            located_ingredient
+                .quantity
+                .as_ref()
+                .map_or(location, |q| q.span()),
+        );
         self.locations.ingredients.push(located_ingredient);
         self.content.ingredients.push(new_igr);
         self.content.ingredients.len() - 1
@@ -860,7 +926,10 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
         let mut new_cw = Cookware {
             name: cookware.name.text_trimmed().into_owned(),
             alias: cookware.alias.map(|t| t.text_trimmed().into_owned()),
-            quantity: cookware.quantity.map(|q| self.value(q.into_inner(), false)),
+            quantity: cookware
+                .quantity
+                .map(|q| self.value(q.into_inner(), false, self.cookware_scaling)),
+            size: cookware.size.and_then(|q| self.pan_size(q)),
             note: cookware.note.map(|n| n.text_trimmed().into_owned()),
             modifiers: cookware.modifiers.into_inner(),
             relation: ComponentRelation::Definition {
@@ -927,6 +996,14 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
             Cookware::set_referenced_from(&mut self.content.cookware, references_to);
         }
 
+        self.content.source_map.cookware.push(
+            located_cookware
+                .quantity
+                .as_ref()
+                .map(|q| q.span())
+                .or(located_cookware.size.as_ref().map(|q| q.span()))
+                .unwrap_or(location),
+        );
         self.locations.cookware.push(located_cookware);
         self.content.cookware.push(new_cw);
         self.content.cookware.len() - 1
@@ -934,9 +1011,21 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
 
     fn timer(&mut self, timer: Located<parser::Timer<'i>>) -> usize {
         let located_timer = timer.clone();
-        let (timer, _span) = timer.take_pair();
+        let (timer, span) = timer.take_pair();
+        self.content
+            .source_map
+            .timers
+            .push(timer.quantity.as_ref().map_or(span, |q| q.span()));
         let quantity = timer.quantity.map(|q| {
-            let quantity = self.quantity(q, false);
+            let duration = if self.extensions.contains(Extensions::DURATIONS) {
+                self.duration_quantity(q.value(), self.timer_scaling)
+            } else {
+                None
+            };
+            let quantity = match duration {
+                Some(quantity) => quantity,
+                None => self.quantity(q, false, self.timer_scaling),
+            };
             if self.extensions.contains(Extensions::ADVANCED_UNITS) {
                 let located_quantity = located_timer.quantity.as_ref().unwrap();
                 if quantity.value.is_text() {
@@ -944,9 +1033,18 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
                         format!("Timer value is text: {}", quantity.value),
                         label!(located_quantity.value.span(), "expected a number here")
                     ));
+                } else if quantity.value.is_indefinite() {
+                    self.ctx.error(error!(
+                        format!("Timer value is indefinite: {}", quantity.value),
+                        label!(located_quantity.value.span(), "expected a number here")
+                    ));
                 }
                 if let Some(unit) = quantity.unit() {
-                    let unit_span = located_quantity.unit.as_ref().unwrap().span();
+                    // durations have the unit in the value
+                    let unit_span = match &located_quantity.unit {
+                        Some(unit) => unit.span(),
+                        None => located_quantity.value.span(),
+                    };
                     match unit.unit_info_or_parse(self.converter) {
                         UnitInfo::Known(unit) => {
                             if unit.physical_quantity != PhysicalQuantity::Time {
@@ -960,10 +1058,18 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
                                 ));
                             }
                         }
-                        UnitInfo::Unknown => self.ctx.error(error!(
-                            format!("Unknown timer unit: {unit}"),
-                            label!(unit_span, "expected time unit")
-                        )),
+                        UnitInfo::Unknown => {
+                            let mut diag = error!(
+                                format!("Unknown timer unit: {unit}"),
+                                label!(unit_span, "expected time unit")
+                            );
+                            if let Some(hint) =
+                                self.unit_suggestion(unit.text(), Some(PhysicalQuantity::Time))
+                            {
+                                diag.add_hint(hint);
+                            }
+                            self.ctx.error(diag);
+                        }
                     }
                 }
             }
@@ -979,24 +1085,279 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
         self.content.timers.len() - 1
     }
 
+    /// Pan size from a cookware quantity with a unit
+    fn pan_size(&mut self, quantity: Located<parser::Quantity<'i>>) -> Option<PanSize> {
+        let span = quantity.span();
+        let parser::Quantity { value, unit, .. } = quantity.into_inner();
+        let unit = unit?;
+        let invalid = |msg: &str| {
+            error!(format!("Invalid pan size: {msg}"), label!(span))
+                .hint("Pan sizes are a diameter like '20%cm' or sides like '23x33%cm'")
+        };
+        let value = match value {
+            parser::QuantityValue::Single { value, .. } => value.into_inner(),
+            parser::QuantityValue::Many(_) => {
+                self.ctx.error(invalid("many values"));
+                return None;
+            }
+        };
+        let unit_text = unit.text_trimmed();
+        let is_length = self
+            .converter
+            .find_unit(&unit_text)
+            .is_some_and(|u| u.physical_quantity == PhysicalQuantity::Length);
+        if !is_length {
+            self.ctx.error(
+                error!(
+                    format!("Invalid pan size: unknown length unit '{unit_text}'"),
+                    label!(unit.span())
+                )
+                .hint("Pan sizes need a length unit, like 'cm' or 'in'"),
+            );
+            return None;
+        }
+        let size = PanSize::from_value(&value, &unit_text);
+        if size.is_none() {
+            self.ctx.error(invalid("expected positive numbers"));
+        }
+        size
+    }
+
     fn quantity(
         &mut self,
         quantity: Located<parser::Quantity<'i>>,
         is_ingredient: bool,
+        default_law: ScalingLaw,
     ) -> Quantity<ScalableValue> {
-        let parser::Quantity { value, unit, .. } = quantity.into_inner();
-        Quantity::new(
-            self.value(value, is_ingredient),
-            unit.map(|t| t.text_trimmed().into_owned()),
-        )
+        let parser::Quantity {
+            mut value,
+            mut unit,
+            composite,
+        } = quantity.into_inner();
+        if !composite.is_empty() {
+            match self.composite_quantity(&value, &unit, &composite) {
+                Ok((total, total_unit)) => {
+                    value = parser::QuantityValue::Single {
+                        value: total,
+                        auto_scale: None,
+                        scaling_law: None,
+                    };
+                    unit = Some(total_unit);
+                }
+                Err(warning) => {
+                    self.ctx.warn(warning);
+                    // not a composite quantity, the unit is all the text after
+                    // the first value, like with advanced units
+                    let first = unit.as_ref().expect("composite quantity without unit");
+                    let start = first.span().start();
+                    let end = composite.last().unwrap().unit.span().end();
+                    unit = Some(Text::from_str(&self.input[start..end], start));
+                }
+            }
+        }
+        let value = self.value(value, is_ingredient, default_law);
+        let Some(unit) = unit else {
+            return Quantity::new(value, None);
+        };
+        let unit_text = unit.text_trimmed().into_owned();
+        let quantity = Quantity::new_and_parse(value, Some(unit_text), self.converter);
+        if !self.extensions.contains(Extensions::ADVANCED_UNITS)
+            || !matches!(
+                quantity.unit().unwrap().unit_info_or_parse(self.converter),
+                UnitInfo::Unknown
+            )
+        {
+            return quantity;
+        }
+
+        let unit_text = quantity.unit_text().unwrap();
+        if self.parse_options.resolve_unit_typos {
+            if let Some(found) = self.converter.fuzzy_find_unit(unit_text) {
+                self.ctx.warn(warning!(
+                    format!("Unknown unit '{unit_text}' used as '{}'", found.key),
+                    label!(unit.span(), "unknown unit")
+                ));
+                return Quantity::new_and_parse(
+                    quantity.value,
+                    Some(found.key.to_string()),
+                    self.converter,
+                );
+            }
+        }
+        // timers already report all unknown units
+        if is_ingredient {
+            if let Some(hint) = self.unit_suggestion(unit_text, None) {
+                self.ctx.warn(
+                    warning!(
+                        format!("Unknown unit: {unit_text}"),
+                        label!(unit.span(), "not a known unit")
+                    )
+                    .hint(hint),
+                );
+            }
+        }
+        quantity
     }
 
-    fn value(&mut self, value: parser::QuantityValue, is_ingredient: bool) -> ScalableValue {
+    /// Quantity from a value written as a duration, like `PT1H30M` or `1:30`
+    ///
+    /// The value is in the smallest unit written, so `PT1H30M` is `90 min`.
+    fn duration_quantity(
+        &self,
+        quantity: &parser::Quantity,
+        default_law: ScalingLaw,
+    ) -> Option<Quantity<ScalableValue>> {
+        let parser::Quantity {
+            value:
+                parser::QuantityValue::Single {
+                    value,
+                    auto_scale: None,
+                    ..
+                },
+            unit: None,
+            ..
+        } = quantity
+        else {
+            return None;
+        };
+        let Value::Text(text) = value.value() else {
+            return None;
+        };
+        let duration = Duration::parse(text)?;
+        let (value, unit) = duration.to_value_unit(self.converter);
+        let value = with_default_law(Value::from(value), default_law);
+        let quantity = match unit {
+            DurationUnit::Known(unit) => Quantity::with_known_unit(value, unit),
+            DurationUnit::Symbol(symbol) => Quantity::new(value, Some(symbol.to_string())),
+        };
+        Some(quantity)
+    }
+
+    /// Hint with the known units similar to an unknown one, optionally only
+    /// of a physical quantity
+    fn unit_suggestion(&self, unit: &str, quantity: Option<PhysicalQuantity>) -> Option<String> {
+        let keys = self
+            .converter
+            .fuzzy_find_units(unit)
+            .into_iter()
+            .filter(|m| quantity.is_none_or(|q| m.unit.physical_quantity == q))
+            .take(3)
+            .map(|m| format!("`{}`", m.key))
+            .collect::<Vec<_>>();
+        match keys.as_slice() {
+            [] => None,
+            [key] => Some(format!("Did you mean {key}?")),
+            [rest @ .., last] => Some(format!("Did you mean {} or {last}?", rest.join(", "))),
+        }
+    }
+
+    /// Adds all the parts of a composite quantity into a single value in the
+    /// smallest of its units. `1 lb 4 oz` will be `20 oz`.
+    ///
+    /// If any unit is unknown, the units are not all of the same physical
+    /// quantity or they can't be added, a warning with the reason is returned.
+    fn composite_quantity(
+        &self,
+        value: &parser::QuantityValue,
+        unit: &Option<Text<'i>>,
+        composite: &[parser::CompositePart<'i>],
+    ) -> Result<(Located<Value>, Text<'i>), SourceDiag> {
+        let parser::QuantityValue::Single { value, .. } = value else {
+            unreachable!("composite quantity with many values")
+        };
+        let unit = unit.as_ref().expect("composite quantity without unit");
+        let parts =
+            std::iter::once((value, unit)).chain(composite.iter().map(|p| (&p.value, &p.unit)));
+
+        let mut resolved: Vec<(&Located<Value>, &Text<'i>, Arc<Unit>)> =
+            Vec::with_capacity(composite.len() + 1);
+        let not_composite = |reason: String, span: Span, label: String| {
+            warning!(
+                format!("Not a composite quantity: {reason}"),
+                label!(span, label)
+            )
+            .hint("The text after the first number is used as the unit")
+        };
+        for (value, unit_text) in parts {
+            let text = unit_text.text_trimmed();
+            let Some(unit) = self.converter.find_unit(&text) else {
+                return Err(not_composite(
+                    format!("unknown unit '{text}'"),
+                    unit_text.span(),
+                    "unknown unit".into(),
+                ));
+            };
+            if unit.difference != 0.0 {
+                return Err(not_composite(
+                    format!("'{text}' can't be added"),
+                    unit_text.span(),
+                    format!("{} units can't be added", unit.physical_quantity),
+                ));
+            }
+            if let Some((_, first_text, first)) = resolved.first() {
+                if first.physical_quantity != unit.physical_quantity {
+                    return Err(not_composite(
+                        format!(
+                            "incompatible units '{}' and '{text}'",
+                            first_text.text_trimmed()
+                        ),
+                        unit_text.span(),
+                        format!(
+                            "expected {}, not {}",
+                            first.physical_quantity, unit.physical_quantity
+                        ),
+                    ));
+                }
+            }
+            resolved.push((value, unit_text, unit));
+        }
+
+        let (_, smallest_text, smallest) = resolved
+            .iter()
+            .min_by(|(_, _, a), (_, _, b)| a.ratio.total_cmp(&b.ratio))
+            .unwrap();
+        let total: f64 = resolved
+            .iter()
+            .map(|(value, _, unit)| {
+                let Value::Number(n) = value.value() else {
+                    unreachable!("composite quantity non numeric value")
+                };
+                convert_f64(n.value(), unit, smallest)
+            })
+            .sum();
+        // remove floating point noise from the conversions
+        let total = (total * 1e6).round() / 1e6;
+
+        let span = Span::new(
+            value.span().start(),
+            composite.last().unwrap().value.span().end(),
+        );
+        Ok((
+            Located::new(Value::from(total), span),
+            (*smallest_text).clone(),
+        ))
+    }
+
+    /// Scalable value from the parser value
+    ///
+    /// Values without the auto scale marker use `default_law`, from the
+    /// `[timer scaling]` and `[cookware scaling]` modes.
+    fn value(
+        &mut self,
+        mut value: parser::QuantityValue,
+        is_ingredient: bool,
+        default_law: ScalingLaw,
+    ) -> ScalableValue {
+        if self.extensions.contains(Extensions::NUMBER_WORDS) {
+            self.number_words(&mut value);
+        }
+
         let mut marker_span = None;
         match &value {
             parser::QuantityValue::Single {
                 value,
                 auto_scale: Some(auto_scale_marker),
+                ..
             } => {
                 marker_span = Some(*auto_scale_marker);
                 if value.is_text() {
@@ -1007,6 +1368,14 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
                         )
                         .hint("Text cannot be scaled"),
                     );
+                } else if let Value::Indefinite(_) = value.value() {
+                    self.ctx.warn(
+                        warning!(
+                            "Indefinite amount with auto scale marker",
+                            label!(auto_scale_marker, "remove this")
+                        )
+                        .hint("Indefinite amounts are not scaled"),
+                    );
                 }
             }
             parser::QuantityValue::Many(v) => {
@@ -1048,7 +1417,10 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
 
         if is_ingredient && self.auto_scale_ingredients {
             match v {
-                ScalableValue::Fixed(value) if !value.is_text() => v = ScalableValue::Linear(value),
+                // an explicit `*fixed` is kept
+                ScalableValue::Fixed(value) if marker_span.is_none() && !value.is_text() => {
+                    v = ScalableValue::Linear(value)
+                }
                 ScalableValue::Linear(_) => {
                     self.ctx.warn(
                         warning!(
@@ -1062,7 +1434,27 @@ impl<'i, 'c> RecipeCollector<'i, 'c> {
             };
         }
 
-        v
+        match v {
+            ScalableValue::Fixed(value) if marker_span.is_none() && !value.is_text() => {
+                with_default_law(value, default_law)
+            }
+            v => v,
+        }
+    }
+
+    /// Replaces text values written with words with their number
+    fn number_words(&self, value: &mut parser::QuantityValue) {
+        let values = match value {
+            parser::QuantityValue::Single { value, .. } => std::slice::from_mut(value),
+            parser::QuantityValue::Many(values) => values.as_mut_slice(),
+        };
+        for value in values {
+            if let Value::Text(text) = &**value {
+                if let Some(new_value) = self.converter.parse_number_words(text) {
+                    **value = new_value;
+                }
+            }
+        }
     }
 
     fn resolve_reference<C: RefComponent>(
@@ -1347,6 +1739,15 @@ fn find_temperature<'a>(text: &'a str, re: &Regex) -> Option<(&'a str, Quantity<
     Some((before, temperature, after))
 }
 
+/// Scalable value of a number without an auto scale marker
+fn with_default_law(value: Value, law: ScalingLaw) -> ScalableValue {
+    match law {
+        ScalingLaw::Fixed => ScalableValue::Fixed(value),
+        ScalingLaw::Linear => ScalableValue::Linear(value),
+        law => ScalableValue::NonLinear { value, law },
+    }
+}
+
 fn note_reference_error(span: Span, implicit: bool, def_span: Span) -> SourceDiag {
     let span = Span::new(span.start().saturating_sub(1), span.end() + 1);
 
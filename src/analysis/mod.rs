@@ -39,6 +39,14 @@ pub struct ParseOptions<'a> {
     /// The boolean returned indicates if the value should be included in the
     /// recipe.
     pub metadata_validator: Option<MetadataValidator<'a>>,
+    /// Use the known unit an unknown unit is a typo of
+    ///
+    /// With the [`ADVANCED_UNITS`](crate::Extensions::ADVANCED_UNITS)
+    /// extension, unknown units similar to known ones have a warning with
+    /// suggestions. If this is enabled and there is only one suggestion, it's
+    /// used instead, like `gramms` as `grams`. See
+    /// [`Converter::fuzzy_find_unit`](crate::convert::Converter::fuzzy_find_unit).
+    pub resolve_unit_typos: bool,
 }
 
 /// Return type for check functions in [`ParseOptions`]
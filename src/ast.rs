@@ -64,6 +64,7 @@ pub fn build_ast<'i>(events: impl Iterator<Item = Event<'i>>) -> PassResult<Ast<
             Event::Ingredient(c) => items.push(Item::Ingredient(Box::new(c))),
             Event::Cookware(c) => items.push(Item::Cookware(Box::new(c))),
             Event::Timer(c) => items.push(Item::Timer(Box::new(c))),
+            Event::InlineQuantity(q) => items.push(Item::InlineQuantity(Box::new(q))),
             Event::Error(e) => ctx.push(e),
             Event::Warning(w) => ctx.push(w),
         }
@@ -0,0 +1,334 @@
+//! Baker's percentages of bread recipes
+//!
+//! In baker's percentages every ingredient is expressed as a percentage of the
+//! total flour weight, so the flour is always `100%`. Only ingredients with a
+//! mass can be expressed like this.
+
+use serde::Serialize;
+use thiserror::Error;
+use unicase::UniCase;
+
+use crate::{
+    convert::{Converter, PhysicalQuantity},
+    ingredient_list::GroupedIngredient,
+    metadata::CooklangValueExt,
+    quantity::{GroupedQuantity, Number, QuantityAddError},
+    scale::{ScaleOutcome, Scaled},
+    ScalableRecipe, ScaledQuantity, ScaledRecipe, UnitInfo, Value,
+};
+
+/// How to find the flour ingredients of a recipe
+#[derive(Debug, Clone, Copy)]
+pub enum Flour<'a> {
+    /// Ingredient definitions with any of these names or aliases, ignoring
+    /// the case
+    Names(&'a [&'a str]),
+    /// Ingredient names listed in a metadata key, comma separated or a YAML
+    /// sequence, like `>> flour: bread flour, rye flour`
+    MetadataKey(&'a str),
+}
+
+/// Baker's percentages of a recipe
+///
+/// Created from [`ScaledRecipe::bakers_percentages`].
+#[derive(Debug, Clone, Serialize)]
+pub struct BakersPercentages {
+    /// Total weight of the flour
+    pub flour: ScaledQuantity,
+    /// Percentage of each ingredient with a mass, in recipe order
+    pub ingredients: Vec<BakersPercentage>,
+}
+
+/// Baker's percentage of an ingredient
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BakersPercentage {
+    /// Index of the ingredient definition in the [`Recipe::ingredients`](crate::model::Recipe::ingredients)
+    pub index: usize,
+    /// If the ingredient is flour
+    pub is_flour: bool,
+    /// Weight of the ingredient and all of its references relative to the
+    /// total flour, where `100.0` is the same weight
+    pub percentage: f64,
+}
+
+/// Errors from baker's percentages
+#[derive(Debug, Error)]
+pub enum BakersPercentagesError {
+    #[error("No flour with a mass found in the recipe")]
+    NoFlour,
+
+    #[error("Ingredient without a mass: {0}")]
+    WithoutMass(String),
+
+    #[error("Ingredient not found: {0}")]
+    NotFound(usize),
+
+    #[error("Invalid flour weight, it has to be greater than 0")]
+    InvalidFlour,
+
+    #[error(transparent)]
+    Quantity(#[from] QuantityAddError),
+}
+
+impl ScaledRecipe {
+    /// Baker's percentages of the ingredients of the recipe
+    ///
+    /// The ingredients are grouped like in [`Self::group_ingredients`] and
+    /// their masses are converted to compare them with the total flour. A
+    /// range, like `@salt{10-12%g}`, uses its lowest value.
+    /// Ingredients without a mass, like `@water{350%ml}` or `@eggs{2}`, are
+    /// not in the list, but it's an error if one of them is flour. It's also
+    /// an error if only part of the flour has a mass.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, Extensions};
+    /// # use cooklang::bakers_percentages::Flour;
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse(">> flour: bread flour, rye flour\n@bread flour{800%g} @rye flour{200%g} @water{700%g} @salt{20%g}")
+    ///     .into_output()
+    ///     .unwrap()
+    ///     .default_scale();
+    /// let percentages = recipe
+    ///     .bakers_percentages(Flour::MetadataKey("flour"), parser.converter())
+    ///     .unwrap();
+    /// assert_eq!(percentages.flour.to_string(), "1 kg");
+    /// let water = &percentages.ingredients[2];
+    /// assert_eq!(recipe.ingredients[water.index].name, "water");
+    /// assert_eq!(water.percentage, 70.0);
+    /// ```
+    pub fn bakers_percentages(
+        &self,
+        flour: Flour,
+        converter: &Converter,
+    ) -> Result<BakersPercentages, BakersPercentagesError> {
+        let names: Vec<UniCase<&str>> = match flour {
+            Flour::Names(names) => names.iter().map(|n| UniCase::new(n.trim())).collect(),
+            Flour::MetadataKey(key) => self
+                .metadata
+                .get(key)
+                .and_then(CooklangValueExt::as_tags)
+                .unwrap_or_default()
+                .into_iter()
+                .map(UniCase::new)
+                .collect(),
+        };
+        let is_flour = |entry: &GroupedIngredient| {
+            let igr = entry.ingredient;
+            names.contains(&UniCase::new(igr.name.as_str()))
+                || igr
+                    .alias
+                    .as_deref()
+                    .is_some_and(|alias| names.contains(&UniCase::new(alias)))
+        };
+
+        let mut masses = Vec::new();
+        let mut total: Option<ScaledQuantity> = None;
+        for entry in self.group_ingredients(converter) {
+            let is_flour = is_flour(&entry);
+            let mass = mass(&entry.quantity, converter);
+            if is_flour && (mass.is_none() || entry.quantity.len() > 1) {
+                let name = entry.ingredient.display_name().into_owned();
+                return Err(BakersPercentagesError::WithoutMass(name));
+            }
+            let Some(mass) = mass else {
+                continue;
+            };
+            if is_flour {
+                total = Some(match total {
+                    Some(total) => total.try_add(&mass, converter)?,
+                    None => mass.clone(),
+                });
+            }
+            masses.push((entry.index, is_flour, mass));
+        }
+        let mut flour = total.ok_or(BakersPercentagesError::NoFlour)?;
+
+        let mut ingredients = Vec::with_capacity(masses.len());
+        for (index, is_flour, mass) in masses {
+            let percentage = match mass.ratio(&flour, converter)? {
+                Value::Number(n) => n.value() * 100.0,
+                _ => unreachable!("masses are numbers"),
+            };
+            ingredients.push(BakersPercentage {
+                index,
+                is_flour,
+                percentage,
+            });
+        }
+
+        let _ = flour.fit(converter);
+        Ok(BakersPercentages { flour, ingredients })
+    }
+}
+
+impl ScalableRecipe {
+    /// Build a scaled recipe from a flour weight and baker's percentages
+    ///
+    /// This is the reverse of [`ScaledRecipe::bakers_percentages`]. The flour
+    /// are the ingredients marked as flour in `percentages`. The recipe is
+    /// scaled by the flour weight divided by the flour in the recipe, like in
+    /// [`Self::scale_by_factor`], and then every ingredient in `percentages`
+    /// is changed to its percentage of the flour weight. The percentages can
+    /// be from this recipe and modified, for example to change the hydration.
+    ///
+    /// Ingredients not in `percentages` are only scaled, so they keep their
+    /// scaling, and the ones in it are changed even if they were fixed.
+    ///
+    /// If the flour weight is a range, the lowest value is used.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, Extensions, Quantity, Value};
+    /// # use cooklang::bakers_percentages::Flour;
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse("@flour{500*%g} @water{350*%g} @salt{10*%g}")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let mut percentages = recipe
+    ///     .clone()
+    ///     .default_scale()
+    ///     .bakers_percentages(Flour::Names(&["flour"]), parser.converter())
+    ///     .unwrap();
+    /// percentages.ingredients[1].percentage = 75.0; // more water
+    ///
+    /// let flour = Quantity::new(Value::from(1.0), Some("kg".to_string()));
+    /// let scaled = recipe
+    ///     .scale_to_bakers_percentages(&flour, &percentages, parser.converter())
+    ///     .unwrap();
+    /// let q = |i: usize| scaled.ingredients[i].quantity.as_ref().unwrap().to_string();
+    /// assert_eq!(q(0), "1 kg");
+    /// assert_eq!(q(1), "750 g");
+    /// assert_eq!(q(2), "20 g");
+    /// ```
+    pub fn scale_to_bakers_percentages(
+        self,
+        flour: &ScaledQuantity,
+        percentages: &BakersPercentages,
+        converter: &Converter,
+    ) -> Result<ScaledRecipe, BakersPercentagesError> {
+        // flour in the recipe before scaling
+        let current = self.default_scale_ref();
+        let mut current_flour: Option<ScaledQuantity> = None;
+        for p in percentages.ingredients.iter().filter(|p| p.is_flour) {
+            let mass = current.ingredient_mass(p.index, converter)?;
+            current_flour = Some(match current_flour {
+                Some(total) => total.try_add(&mass, converter)?,
+                None => mass,
+            });
+        }
+        let current_flour = current_flour.ok_or(BakersPercentagesError::NoFlour)?;
+
+        let value = match flour.value {
+            Value::Number(n) => n,
+            Value::Range { start, .. } => start,
+            _ => return Err(BakersPercentagesError::InvalidFlour),
+        };
+        let flour =
+            ScaledQuantity::new(Value::Number(value), flour.unit_text().map(str::to_string));
+        let factor = match flour.ratio(&current_flour, converter)? {
+            Value::Number(n) => n.value(),
+            _ => unreachable!("ratio is a number"),
+        };
+        let mut scaled = self
+            .scale_by_factor(factor, converter)
+            .map_err(|_| BakersPercentagesError::InvalidFlour)?;
+
+        for p in &percentages.ingredients {
+            let mass = scaled.ingredient_mass(p.index, converter)?;
+            let mut target = flour.clone();
+            if let Value::Number(n) = &mut target.value {
+                *n = (n.value() * p.percentage / 100.0).into();
+            }
+            let change = match target.ratio(&mass, converter) {
+                Ok(Value::Number(n)) => n.value(),
+                // no mass to change
+                Err(QuantityAddError::DivisionByZero) => continue,
+                Ok(_) => unreachable!("masses are numbers"),
+                Err(e) => return Err(e.into()),
+            };
+            scaled.change_mass(p.index, change, converter);
+        }
+
+        Ok(scaled)
+    }
+}
+
+impl ScaledRecipe {
+    /// Mass of an ingredient definition and all of its references
+    fn ingredient_mass(
+        &self,
+        index: usize,
+        converter: &Converter,
+    ) -> Result<ScaledQuantity, BakersPercentagesError> {
+        let igr = self
+            .ingredients
+            .get(index)
+            .ok_or(BakersPercentagesError::NotFound(index))?;
+        let grouped = igr.group_quantities(&self.ingredients, converter);
+        mass(&grouped, converter)
+            .ok_or_else(|| BakersPercentagesError::WithoutMass(igr.display_name().into_owned()))
+    }
+
+    /// Multiplies the mass of an ingredient definition and all of its
+    /// references
+    fn change_mass(&mut self, index: usize, change: f64, converter: &Converter) {
+        let mut outcomes = match &mut self.data {
+            Scaled::Scaled(data) => Some(data.ingredients.as_mut_slice()),
+            Scaled::DefaultScaling => None,
+        };
+        let all = std::iter::once(index).chain(
+            self.ingredients[index]
+                .relation
+                .referenced_from()
+                .iter()
+                .copied(),
+        );
+        for i in all.collect::<Vec<_>>() {
+            let Some(q) = &mut self.ingredients[i].quantity else {
+                continue;
+            };
+            if !is_mass(q, converter) {
+                continue;
+            }
+            let scale = |n: Number| Number::from(n.value() * change);
+            q.value = match q.value {
+                Value::Number(n) => Value::Number(scale(n)),
+                Value::Range { start, end } => Value::Range {
+                    start: scale(start),
+                    end: scale(end),
+                },
+                _ => continue,
+            };
+            let _ = q.fit(converter);
+            if let Some(outcome) = outcomes.as_deref_mut().and_then(|o| o.get_mut(i)) {
+                *outcome = ScaleOutcome::Scaled;
+                outcome.round(q, converter);
+            }
+        }
+    }
+}
+
+/// Mass of a grouped quantity, if it has one
+///
+/// Ranges use the lowest value.
+fn mass(grouped: &GroupedQuantity, converter: &Converter) -> Option<ScaledQuantity> {
+    grouped.iter().find_map(|q| {
+        let value = match q.value {
+            Value::Number(n) => n,
+            Value::Range { start, .. } => start,
+            _ => return None,
+        };
+        is_mass(q, converter)
+            .then(|| ScaledQuantity::new(Value::Number(value), q.unit_text().map(str::to_string)))
+    })
+}
+
+fn is_mass(q: &ScaledQuantity, converter: &Converter) -> bool {
+    q.unit().is_some_and(|unit| {
+        matches!(
+            unit.unit_info_or_parse(converter),
+            UnitInfo::Known(unit) if unit.physical_quantity == PhysicalQuantity::Mass
+        )
+    })
+}
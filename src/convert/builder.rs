@@ -5,7 +5,11 @@ use thiserror::Error;
 
 use super::{
     convert_f64,
-    units_file::{self, BestUnits, Extend, Precedence, SIPrefix, UnitEntry, Units, UnitsFile, SI},
+    rounding::Rounding,
+    units_file::{
+        self, BestUnits, Composite, Extend, Fit, NumberWords, Precedence, SIPrefix, UnitEntry,
+        UnitForms, Units, UnitsFile, SI,
+    },
     BestConversions, BestConversionsStore, Converter, Fractions, PhysicalQuantity, System, Unit,
     UnitIndex, UnknownUnit,
 };
@@ -15,18 +19,23 @@ use super::{
 /// The builder uses [`UnitsFile`] to configure the converter. More than one
 /// file can be layered. Order matters, as one file can extend the units of
 /// another added before, or be overwritten by others after.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct ConverterBuilder {
     all_units: Vec<UnitBuilder>,
     unit_index: UnitIndex,
     extend: Vec<Extend>,
     si: SI,
     fractions: Vec<units_file::Fractions>,
+    rounding: Vec<units_file::Rounding>,
     best_units: EnumMap<PhysicalQuantity, Option<BestUnits>>,
+    composite: EnumMap<PhysicalQuantity, Option<Composite>>,
+    fit: EnumMap<PhysicalQuantity, Option<Fit>>,
+    number_words: NumberWords,
     default_system: System,
+    bundled_units: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct UnitBuilder {
     unit: Unit,
     is_expanded: bool,
@@ -69,6 +78,47 @@ impl ConverterBuilder {
     #[cfg(feature = "bundled_units")]
     pub fn add_bundled_units(&mut self) -> Result<&mut Self, ConverterBuilderError> {
         self.add_units_file(UnitsFile::bundled())?;
+        self.bundled_units = true;
+        Ok(self)
+    }
+
+    /// Add the bundled units and a bundled localized layer to the builder
+    ///
+    /// The bundled units are only added if they are not in the builder yet,
+    /// so more than one locale can be added.
+    ///
+    /// See [`UnitsFile::bundled_locale`]. This is only available with the
+    /// `bundled_units` feature, and each locale with its own feature.
+    #[cfg(feature = "bundled_units")]
+    pub fn with_bundled_locale_units(
+        mut self,
+        locale: &str,
+    ) -> Result<Self, ConverterBuilderError> {
+        self.add_bundled_locale_units(locale)?;
+        Ok(self)
+    }
+
+    /// Add the bundled units and a bundled localized layer to the builder
+    ///
+    /// The bundled units are only added if they are not in the builder yet,
+    /// so more than one locale can be added.
+    ///
+    /// See [`UnitsFile::bundled_locale`]. This is only available with the
+    /// `bundled_units` feature, and each locale with its own feature.
+    #[cfg(feature = "bundled_units")]
+    pub fn add_bundled_locale_units(
+        &mut self,
+        locale: &str,
+    ) -> Result<&mut Self, ConverterBuilderError> {
+        let layer = UnitsFile::bundled_locale(locale).ok_or_else(|| {
+            ConverterBuilderError::LocaleNotBundled {
+                locale: locale.to_string(),
+            }
+        })?;
+        if !self.bundled_units {
+            self.add_bundled_units()?;
+        }
+        self.add_units_file(layer)?;
         Ok(self)
     }
 
@@ -85,15 +135,19 @@ impl ConverterBuilder {
             let mut add_units =
                 |units: Vec<UnitEntry>, system| -> Result<(), ConverterBuilderError> {
                     for entry in units {
-                        let unit = Unit {
+                        let forms = unit_forms(entry.singular, entry.plural, &entry.names);
+                        let mut unit = Unit {
                             names: entry.names,
                             symbols: entry.symbols,
                             aliases: entry.aliases,
+                            forms,
+                            locale_forms: entry.locales,
                             ratio: entry.ratio,
                             difference: entry.difference,
                             physical_quantity: group.quantity,
                             system,
                         };
+                        add_form_names(&mut unit);
                         let _id = self.add_unit(UnitBuilder {
                             unit,
                             is_expanded: false,
@@ -133,6 +187,14 @@ impl ConverterBuilder {
                 }
                 self.best_units[group.quantity] = Some(best_units);
             }
+
+            // same with composite display
+            if let Some(composite) = group.composite {
+                self.composite[group.quantity] = Some(composite);
+            }
+            if let Some(fit) = group.fit {
+                self.fit[group.quantity] = Some(fit);
+            }
         }
 
         // Store the extensions to apply them at the end
@@ -159,6 +221,14 @@ impl ConverterBuilder {
             self.fractions.push(fractions);
         }
 
+        if let Some(rounding) = units.rounding {
+            self.rounding.push(rounding);
+        }
+
+        if let Some(number_words) = units.number_words {
+            self.number_words.merge(number_words);
+        }
+
         Ok(self)
     }
 
@@ -203,6 +273,13 @@ impl ConverterBuilder {
         };
 
         let fractions = build_fractions_config(&self.fractions, &self.unit_index, &self.all_units)?;
+        let rounding = Rounding::new(&self.rounding, &self.unit_index)?;
+
+        let composite = self.composite.map(|_, composite| {
+            enum_map! {
+                system => composite.is_some_and(|c| c.enabled(system))
+            }
+        });
 
         Ok(Converter {
             all_units: self
@@ -214,6 +291,10 @@ impl ConverterBuilder {
             quantity_index,
             best,
             fractions,
+            rounding,
+            composite,
+            fit: self.fit.map(|_, fit| fit.unwrap_or_default()),
+            number_words: self.number_words,
             default_system: self.default_system,
             temperature_regex: Default::default(),
         })
@@ -301,7 +382,10 @@ fn apply_extend_groups(
                 && (entry.ratio.is_some()
                     || entry.difference.is_some()
                     || entry.names.is_some()
-                    || entry.symbols.is_some())
+                    || entry.symbols.is_some()
+                    || entry.singular.is_some()
+                    || entry.plural.is_some()
+                    || entry.locales.is_some())
             {
                 return Err(ConverterBuilderError::InvalidExtendExpanded { key: k });
             }
@@ -330,6 +414,18 @@ fn apply_extend_groups(
             if let Some(aliases) = entry.aliases {
                 join_alias_vec(&mut unit.aliases, aliases, precedence);
             }
+            if entry.singular.is_some() || entry.plural.is_some() {
+                let current = unit.forms.take();
+                let singular = entry
+                    .singular
+                    .or_else(|| current.as_ref().map(|f| Arc::clone(&f.singular)));
+                let plural = entry.plural.or_else(|| current.map(|f| f.plural));
+                unit.forms = unit_forms(singular, plural, &unit.names);
+            }
+            if let Some(locales) = entry.locales {
+                unit.locale_forms.extend(locales);
+            }
+            add_form_names(unit);
 
             // (re)add the new entries to the index
             if all_units[id].expand_si {
@@ -417,6 +513,42 @@ fn build_fractions_config(
     })
 }
 
+/// Resolves the display forms of a unit
+fn unit_forms(
+    singular: Option<Arc<str>>,
+    plural: Option<Arc<str>>,
+    names: &[Arc<str>],
+) -> Option<UnitForms> {
+    if singular.is_none() && plural.is_none() {
+        return None;
+    }
+    let singular = singular
+        .or_else(|| names.first().cloned())
+        .or_else(|| plural.clone())?;
+    let plural = plural.unwrap_or_else(|| Arc::clone(&singular));
+    Some(UnitForms { singular, plural })
+}
+
+/// Adds the display forms to the names, so they can be parsed too
+///
+/// The default forms go first, so the first name is the singular.
+fn add_form_names(unit: &mut Unit) {
+    let mut locales = unit.locale_forms.iter().collect::<Vec<_>>();
+    locales.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    let default = unit.forms.iter().flat_map(|f| [&f.singular, &f.plural]);
+    let locale = locales
+        .into_iter()
+        .flat_map(|(_, f)| [&f.singular, &f.plural]);
+
+    let mut names: Vec<Arc<str>> = Vec::with_capacity(unit.names.len());
+    for name in default.chain(&unit.names).chain(locale) {
+        if !names.contains(name) {
+            names.push(Arc::clone(name));
+        }
+    }
+    unit.names = names;
+}
+
 fn join_alias_vec(target: &mut Vec<Arc<str>>, mut src: Vec<Arc<str>>, src_precedence: Precedence) {
     match src_precedence {
         Precedence::Before => {
@@ -455,6 +587,20 @@ fn join_prefixes(
     }
 }
 
+/// Joins a SI prefix with a name
+///
+/// If the prefix is capitalized, like in German, the name is not, so `Kilo`
+/// and `Gramm` are `Kilogramm`.
+fn prefixed_name(prefix: &str, name: &str) -> Arc<str> {
+    let mut chars = name.chars();
+    match (prefix.chars().next(), chars.next()) {
+        (Some(p), Some(n)) if p.is_uppercase() => {
+            format!("{prefix}{}{}", n.to_lowercase(), chars.as_str()).into()
+        }
+        _ => format!("{prefix}{name}").into(),
+    }
+}
+
 fn expand_si(
     unit: &UnitBuilder,
     si: &SI,
@@ -466,16 +612,35 @@ fn expand_si(
 
     let map = enum_map! {
         prefix => {
-            let names = prefixes[prefix]
+            let mut names: Vec<Arc<str>> = Vec::new();
+            for name in prefixes[prefix]
                 .iter()
-                .flat_map(|p| unit.names.iter().map(move |n| format!("{p}{n}").into()))
-                .collect();
+                .flat_map(|p| unit.names.iter().map(move |n| prefixed_name(p, n)))
+            {
+                // different prefixes and names may join the same
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
 
             let symbols = symbol_prefixes[prefix]
                 .iter()
                 .flat_map(|p| unit.symbols.iter().map(move |n| format!("{p}{n}").into()))
                 .collect();
 
+            let prefix_forms = |f: &UnitForms| {
+                prefixes[prefix].first().map(|p| UnitForms {
+                    singular: prefixed_name(p, &f.singular),
+                    plural: prefixed_name(p, &f.plural),
+                })
+            };
+            let forms = unit.forms.as_ref().and_then(prefix_forms);
+            let locale_forms = unit
+                .locale_forms
+                .iter()
+                .filter_map(|(l, f)| Some((l.clone(), prefix_forms(f)?)))
+                .collect();
+
             UnitBuilder {
                 unit:
 
@@ -483,6 +648,8 @@ fn expand_si(
                 names,
                 symbols,
                 aliases: Vec::new(),
+                forms,
+                locale_forms,
                 ratio: unit.ratio * prefix.ratio(),
                 difference: unit.difference,
                 physical_quantity: unit.physical_quantity,
@@ -567,4 +734,7 @@ pub enum ConverterBuilderError {
 
     #[error("No SI prefixes found when expandind SI on a unit")]
     EmptySIPrefixes,
+
+    #[error("Units for locale '{locale}' are not bundled")]
+    LocaleNotBundled { locale: String },
 }
@@ -0,0 +1,216 @@
+//! Durations written as ISO 8601 (`PT1H30M`) or clock time (`1:30:00`)
+
+use std::sync::Arc;
+
+use super::{Converter, PhysicalQuantity, Unit};
+
+/// Parts of a duration, from the biggest to the smallest
+const PARTS: [Part; 4] = [
+    Part {
+        seconds: 86400.0,
+        keys: &["d", "day", "days"],
+    },
+    Part {
+        seconds: 3600.0,
+        keys: &["h", "hour", "hours"],
+    },
+    Part {
+        seconds: 60.0,
+        keys: &["min", "minute", "minutes", "m"],
+    },
+    Part {
+        seconds: 1.0,
+        keys: &["s", "sec", "second", "seconds"],
+    },
+];
+
+struct Part {
+    seconds: f64,
+    /// Possible keys of the unit in a converter. The first one is used when
+    /// the converter doesn't have it.
+    keys: &'static [&'static str],
+}
+
+/// A parsed duration
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Duration {
+    /// Values of each of the [`PARTS`]
+    values: [f64; 4],
+}
+
+impl Duration {
+    /// Parses an ISO 8601 duration or a clock time
+    ///
+    /// - ISO 8601 durations can have weeks, days, hours, minutes and seconds,
+    ///   like `PT1H30M` or `P1DT12H`. Years and months are not supported
+    ///   because their length varies.
+    /// - Clock times are `h:mm` or `h:mm:ss`, like `1:30` or `0:45:30`.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        parse_iso(s).or_else(|| parse_clock(s))
+    }
+
+    pub(crate) fn seconds(self) -> f64 {
+        self.values
+            .iter()
+            .zip(&PARTS)
+            .map(|(v, p)| v * p.seconds)
+            .sum()
+    }
+
+    pub(crate) fn minutes(self) -> f64 {
+        self.seconds() / 60.0
+    }
+
+    /// Value and unit of the duration as a single quantity
+    ///
+    /// The unit is the smallest part written, so `PT1H30M` is `90 min`. If
+    /// the converter knows the time unit, it is returned, otherwise only the
+    /// symbol.
+    pub(crate) fn to_value_unit(self, converter: &Converter) -> (f64, DurationUnit) {
+        let (_, part) = self
+            .values
+            .iter()
+            .zip(&PARTS)
+            .rev()
+            .find(|(v, _)| **v != 0.0)
+            .unwrap_or((&0.0, &PARTS[2])); // 0 minutes
+        let value = self.seconds() / part.seconds;
+        // remove floating point noise
+        let value = (value * 1e6).round() / 1e6;
+
+        let unit = part
+            .keys
+            .iter()
+            .filter_map(|key| converter.find_unit(key))
+            .find(|u| u.physical_quantity == PhysicalQuantity::Time);
+        let unit = match unit {
+            Some(unit) => DurationUnit::Known(unit),
+            None => DurationUnit::Symbol(part.keys[0]),
+        };
+        (value, unit)
+    }
+}
+
+pub(crate) enum DurationUnit {
+    Known(Arc<Unit>),
+    Symbol(&'static str),
+}
+
+fn parse_iso(s: &str) -> Option<Duration> {
+    let s = s.to_ascii_uppercase();
+    let rest = s.strip_prefix('P')?;
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return None,
+        Some((date, time)) => (date, Some(time)),
+        None => (rest, None),
+    };
+
+    let mut duration = Duration::default();
+    let mut any = false;
+    let mut parse_section = |section: &str, designators: &[(char, usize, f64)]| -> Option<()> {
+        let mut section = section;
+        let mut next = 0;
+        while !section.is_empty() {
+            let end = section.find(|c: char| !c.is_ascii_digit() && c != '.' && c != ',')?;
+            let (number, rest) = section.split_at(end);
+            let designator = rest.chars().next()?;
+            let number = number.replace(',', ".").parse::<f64>().ok()?;
+            // designators must be in order and only once
+            let pos = designators[next..]
+                .iter()
+                .position(|(d, _, _)| *d == designator)?;
+            let (_, part, factor) = designators[next + pos];
+            duration.values[part] += number * factor;
+            next += pos + 1;
+            any = true;
+            section = &rest[designator.len_utf8()..];
+        }
+        Some(())
+    };
+    parse_section(date, &[('W', 0, 7.0), ('D', 0, 1.0)])?;
+    if let Some(time) = time {
+        parse_section(time, &[('H', 1, 1.0), ('M', 2, 1.0), ('S', 3, 1.0)])?;
+    }
+    any.then_some(duration)
+}
+
+fn parse_clock(s: &str) -> Option<Duration> {
+    let mut parts = s.split(':');
+    let hours = parts.next()?;
+    let minutes = parts.next()?;
+    let seconds = parts.next();
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let number = |s: &str, max: Option<u32>| {
+        if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        if max.is_some() && s.len() != 2 {
+            return None;
+        }
+        let n = s.parse::<u32>().ok()?;
+        max.is_none_or(|max| n < max).then_some(f64::from(n))
+    };
+    let mut duration = Duration::default();
+    duration.values[1] = number(hours, None)?;
+    duration.values[2] = number(minutes, Some(60))?;
+    if let Some(seconds) = seconds {
+        duration.values[3] = number(seconds, Some(60))?;
+    }
+    Some(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("PT1H30M" => Some(5400.0))]
+    #[test_case("PT90M" => Some(5400.0))]
+    #[test_case("pt45s" => Some(45.0); "lowercase")]
+    #[test_case("PT1.5H" => Some(5400.0))]
+    #[test_case("PT0,5H" => Some(1800.0); "decimal comma")]
+    #[test_case("P1DT2H" => Some(93600.0))]
+    #[test_case("P1W" => Some(604800.0))]
+    #[test_case("1:30" => Some(5400.0))]
+    #[test_case("1:30:15" => Some(5415.0))]
+    #[test_case("0:00:45" => Some(45.0))]
+    #[test_case(" 12:05 " => Some(43500.0); "trimmed")]
+    #[test_case("P" => None)]
+    #[test_case("PT" => None)]
+    #[test_case("P1M" => None; "months")]
+    #[test_case("PT30M1H" => None; "unordered")]
+    #[test_case("PT1H1H" => None; "repeated")]
+    #[test_case("1:5" => None)]
+    #[test_case("1:75" => None)]
+    #[test_case("1:30:00:00" => None)]
+    #[test_case("10 min" => None)]
+    fn parse(s: &str) -> Option<f64> {
+        Duration::parse(s).map(|d| d.seconds())
+    }
+
+    #[test_case("PT1H30M" => (90.0, "min".to_string()))]
+    #[test_case("PT2H" => (2.0, "h".to_string()))]
+    #[test_case("1:30:15" => (5415.0, "s".to_string()))]
+    #[test_case("1:00" => (1.0, "h".to_string()))]
+    #[test_case("0:00" => (0.0, "min".to_string()))]
+    fn value_unit(s: &str) -> (f64, String) {
+        let converter = Converter::bundled();
+        let (value, unit) = Duration::parse(s).unwrap().to_value_unit(&converter);
+        let DurationUnit::Known(unit) = unit else {
+            panic!("unknown unit")
+        };
+        (value, unit.symbol().to_string())
+    }
+
+    #[test]
+    fn empty_converter() {
+        let duration = Duration::parse("PT1H30M").unwrap();
+        let (value, unit) = duration.to_value_unit(&Converter::empty());
+        assert_eq!(value, 90.0);
+        assert!(matches!(unit, DurationUnit::Symbol("min")));
+    }
+}
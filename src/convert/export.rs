@@ -0,0 +1,204 @@
+//! Export a [`Converter`] back to a [`UnitsFile`]
+
+use std::collections::HashMap;
+
+use enum_map::EnumMap;
+
+use super::{
+    units_file::{
+        BestUnits, Composite, Fractions, FractionsConfigHelper, FractionsConfigWrapper,
+        QuantityGroup, UnitEntry, Units, UnitsFile,
+    },
+    BestConversions, BestConversionsStore, Converter, FractionsConfig, PhysicalQuantity, System,
+    Unit,
+};
+
+impl Converter {
+    /// Export the converter to a single [`UnitsFile`]
+    ///
+    /// The file has the effective configuration of all the layers the
+    /// converter was built with: every unit (with SI units already expanded),
+    /// the best units per system, composite display, fit, fractions, number
+    /// words and the default system.
+    ///
+    /// Building a converter with only this file results in the same converter.
+    ///
+    /// ```
+    /// # use cooklang::convert::{Converter, ConverterBuilder};
+    /// let converter = Converter::bundled();
+    /// let file = converter.to_units_file();
+    /// let toml = toml::to_string(&file).unwrap();
+    ///
+    /// let file = toml::from_str(&toml).unwrap();
+    /// let same = ConverterBuilder::new()
+    ///     .with_units_file(file)
+    ///     .unwrap()
+    ///     .finish()
+    ///     .unwrap();
+    /// assert_eq!(converter, same);
+    /// ```
+    pub fn to_units_file(&self) -> UnitsFile {
+        // Units are exported in the same order, so they keep their ids. Each
+        // run of units of the same quantity and system is a group.
+        let mut quantity: Vec<QuantityGroup> = Vec::new();
+        let mut configured = EnumMap::<PhysicalQuantity, bool>::default();
+        for unit in &self.all_units {
+            let q = unit.physical_quantity;
+            let list = quantity
+                .last_mut()
+                .filter(|g| g.quantity == q)
+                .and_then(|g| g.units.as_mut())
+                .and_then(|units| system_list(units, unit.system));
+            if let Some(list) = list {
+                list.push(export_unit(unit));
+                continue;
+            }
+
+            let mut group = if configured[q] {
+                QuantityGroup {
+                    quantity: q,
+                    best: None,
+                    composite: None,
+                    fit: None,
+                    units: None,
+                }
+            } else {
+                configured[q] = true;
+                self.export_group_config(q)
+            };
+            let mut units = Units::BySystem {
+                metric: Vec::new(),
+                imperial: Vec::new(),
+                unspecified: Vec::new(),
+            };
+            system_list(&mut units, unit.system)
+                .expect("empty units")
+                .push(export_unit(unit));
+            group.units = Some(units);
+            quantity.push(group);
+        }
+        for (q, configured) in configured {
+            if !configured {
+                let group = self.export_group_config(q);
+                if group.best.is_some() || group.composite.is_some() || group.fit.is_some() {
+                    quantity.push(group);
+                }
+            }
+        }
+
+        UnitsFile {
+            default_system: Some(self.default_system),
+            si: None,
+            fractions: Some(self.export_fractions()),
+            rounding: Some(self.rounding.export(self)),
+            number_words: Some(self.number_words.clone()),
+            extend: None,
+            quantity,
+        }
+    }
+
+    /// A group with the best units, composite and fit of a quantity
+    fn export_group_config(&self, quantity: PhysicalQuantity) -> QuantityGroup {
+        let names = |best: &BestConversions| -> Vec<String> {
+            best.0
+                .iter()
+                .map(|&(_, id)| self.all_units[id].symbol().to_string())
+                .collect()
+        };
+        let best = match &self.best[quantity] {
+            BestConversionsStore::Unified(best) if !best.0.is_empty() => {
+                Some(BestUnits::Unified(names(best)))
+            }
+            BestConversionsStore::BySystem { metric, imperial }
+                if !metric.0.is_empty() && !imperial.0.is_empty() =>
+            {
+                Some(BestUnits::BySystem {
+                    metric: names(metric),
+                    imperial: names(imperial),
+                })
+            }
+            _ => None,
+        };
+
+        let composite = self.composite[quantity];
+        let composite = (composite[System::Metric] || composite[System::Imperial]).then_some(
+            Composite::BySystem {
+                metric: composite[System::Metric],
+                imperial: composite[System::Imperial],
+            },
+        );
+        let fit = self.fit[quantity];
+
+        QuantityGroup {
+            quantity,
+            best,
+            composite,
+            fit: (fit != Default::default()).then_some(fit),
+            units: None,
+        }
+    }
+
+    fn export_fractions(&self) -> Fractions {
+        let f = &self.fractions;
+        Fractions {
+            all: f.all.map(export_fractions_config),
+            metric: f.metric.map(export_fractions_config),
+            imperial: f.imperial.map(export_fractions_config),
+            quantity: f
+                .quantity
+                .iter()
+                .map(|(&q, &cfg)| (q, export_fractions_config(cfg)))
+                .collect(),
+            unit: f
+                .unit
+                .iter()
+                .map(|(&id, &cfg)| {
+                    let key = self.all_units[id].symbol().to_string();
+                    (key, export_fractions_config(cfg))
+                })
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+}
+
+/// List of a system where a unit can be pushed keeping the order of the units
+fn system_list(units: &mut Units, system: Option<System>) -> Option<&mut Vec<UnitEntry>> {
+    let Units::BySystem {
+        metric,
+        imperial,
+        unspecified,
+    } = units
+    else {
+        return None;
+    };
+    // the builder adds the units of a group in this order
+    match system {
+        Some(System::Metric) if imperial.is_empty() && unspecified.is_empty() => Some(metric),
+        Some(System::Imperial) if unspecified.is_empty() => Some(imperial),
+        None => Some(unspecified),
+        _ => None,
+    }
+}
+
+fn export_unit(unit: &Unit) -> UnitEntry {
+    UnitEntry {
+        names: unit.names.clone(),
+        singular: unit.forms.as_ref().map(|f| f.singular.clone()),
+        plural: unit.forms.as_ref().map(|f| f.plural.clone()),
+        locales: unit.locale_forms.clone(),
+        symbols: unit.symbols.clone(),
+        aliases: unit.aliases.clone(),
+        ratio: unit.ratio,
+        difference: unit.difference,
+        expand_si: false,
+    }
+}
+
+fn export_fractions_config(cfg: FractionsConfig) -> FractionsConfigWrapper {
+    FractionsConfigWrapper::Custom(FractionsConfigHelper {
+        enabled: Some(cfg.enabled),
+        accuracy: Some(cfg.accuracy),
+        max_denominator: Some(cfg.max_denominator),
+        max_whole: Some(cfg.max_whole),
+    })
+}
@@ -0,0 +1,225 @@
+//! Readable fitting of values to the best units
+//!
+//! Used when the [`FitStrategy::Readable`] is configured. Each possible best
+//! unit gets a score (lower is better) for how easy the value is to read and
+//! measure in a kitchen.
+
+use std::sync::Arc;
+
+use super::{units_file::FitStrategy, ConvertValue, Converter, FractionsConfig, System, Unit};
+use crate::quantity::Number;
+
+/// Penalty for each step the unit is smaller than the one chosen by magnitude
+const SMALLER_UNIT_PENALTY: f64 = 1.0;
+/// Penalty when a value has more whole units than allowed by the fractions config
+const MAX_WHOLE_PENALTY: f64 = 3.0;
+/// Penalty for splitting the value in 2 units
+const SPLIT_PENALTY: f64 = 1.5;
+/// Penalty for decimal values when the unit uses fractions
+const NO_FRACTION_PENALTY: f64 = 1.0;
+/// Relative error allowed when rounding decimals
+const RELATIVE_PRECISION: f64 = 0.005;
+
+/// A value fitted to a unit
+#[derive(Debug, Clone)]
+pub(super) struct Fitted {
+    pub value: Number,
+    pub unit: Arc<Unit>,
+    pub score: f64,
+}
+
+impl Converter {
+    pub(super) fn is_readable_fit(&self, unit: &Unit) -> bool {
+        self.fit[unit.physical_quantity].strategy == FitStrategy::Readable
+    }
+
+    /// Finds the most readable best unit for a value
+    ///
+    /// Returns [`None`] if the value is not positive or there are no best
+    /// units.
+    pub(super) fn readable_fit(&self, value: f64, unit: &Unit, system: System) -> Option<Fitted> {
+        if value <= 0.0 || !value.is_finite() {
+            return None;
+        }
+        let conversions = self.best[unit.physical_quantity].conversions(system);
+        let magnitude_best = conversions.best_unit(self, &ConvertValue::Number(value), unit)?;
+        let magnitude_index = conversions
+            .all_units(self)
+            .position(|u| Arc::ptr_eq(u, &magnitude_best))?;
+
+        conversions
+            .all_units(self)
+            .enumerate()
+            .map(|(index, new_unit)| {
+                let new_value = self.convert_f64(value, unit, new_unit);
+                let (number, mut score) = self.readable_number(new_value, new_unit);
+                score += magnitude_index.saturating_sub(index) as f64 * SMALLER_UNIT_PENALTY;
+                Fitted {
+                    value: number,
+                    unit: Arc::clone(new_unit),
+                    score,
+                }
+            })
+            .min_by(|a, b| a.score.total_cmp(&b.score))
+    }
+
+    /// Splits a value in 2 best units, like `1/4 cup + 1 tbsp`
+    ///
+    /// Returns [`None`] if splitting is not enabled or no split is more
+    /// readable than [`Self::readable_fit`].
+    pub(super) fn readable_split(
+        &self,
+        value: f64,
+        unit: &Unit,
+        system: System,
+    ) -> Option<(Fitted, Fitted)> {
+        let single = self.readable_fit(value, unit, system)?;
+        if !self.fit[unit.physical_quantity].split {
+            return None;
+        }
+
+        // best units are sorted from smallest to biggest
+        let best = self.best[unit.physical_quantity]
+            .conversions(system)
+            .all_units(self)
+            .collect::<Vec<_>>();
+
+        let mut selected: Option<(Fitted, Fitted)> = None;
+        for (index, &big_unit) in best.iter().enumerate().skip(1) {
+            let big_value = self.convert_f64(value, unit, big_unit);
+            let cfg = self.fractions_config(big_unit);
+            let max_den = if cfg.enabled { cfg.max_denominator } else { 1 };
+
+            for den in 1..=max_den as u32 {
+                // small tolerance for conversion errors
+                let parts = (big_value * den as f64 + 1e-9).floor() as u32;
+                let (whole, num) = (parts / den, parts % den);
+                if parts == 0 || (den > 1 && (num == 0 || gcd(num, den) != 1)) {
+                    continue;
+                }
+                let big_number = if num == 0 {
+                    Number::Regular(whole as f64)
+                } else {
+                    Number::Fraction {
+                        whole,
+                        num,
+                        den,
+                        err: 0.0,
+                    }
+                };
+                let rest = big_value - big_number.value();
+                if rest < 1e-6 * big_value {
+                    continue;
+                }
+                let big_score = number_score(big_number, cfg);
+
+                for &small_unit in &best[..index] {
+                    let small_value = self.convert_f64(rest, big_unit, small_unit);
+                    let (small_number, small_score) = self.readable_number(small_value, small_unit);
+                    let big = Fitted {
+                        value: big_number,
+                        unit: Arc::clone(big_unit),
+                        score: big_score + small_score + SPLIT_PENALTY,
+                    };
+                    let small = Fitted {
+                        value: small_number,
+                        unit: Arc::clone(small_unit),
+                        score: small_score,
+                    };
+                    if selected.as_ref().is_none_or(|(s, _)| big.score < s.score) {
+                        selected = Some((big, small));
+                    }
+                }
+            }
+        }
+
+        selected.filter(|(big, _)| big.score < single.score)
+    }
+
+    /// Approximates the value in the unit and scores it
+    fn readable_number(&self, value: f64, unit: &Unit) -> (Number, f64) {
+        let cfg = self.fractions_config(unit);
+        let number = cfg
+            .enabled
+            .then(|| Number::new_approx(value, cfg.accuracy, cfg.max_denominator, cfg.max_whole))
+            .flatten()
+            .unwrap_or(Number::Regular(value));
+        (number, number_score(number, cfg))
+    }
+}
+
+fn number_score(number: Number, cfg: FractionsConfig) -> f64 {
+    let value = number.value();
+    let mut score = match number {
+        Number::Fraction {
+            whole,
+            num,
+            den,
+            err,
+        } => {
+            let den_penalty = match (num, den) {
+                (0, _) => 0.0,
+                (_, 2 | 3) => 1.0,
+                (_, 4) => 1.5,
+                (_, 8) => 2.5,
+                _ => 4.0,
+            };
+            let whole_penalty = if whole > 1 {
+                0.5 * (whole as f64).log10()
+            } else {
+                0.0
+            };
+            den_penalty + whole_penalty + (err / value).abs() * 10.0
+        }
+        Number::Regular(v) => {
+            // decimals needed to show the value with a small relative error
+            let decimals = (0..3)
+                .find(|&d| {
+                    let p = 10f64.powi(d);
+                    ((v * p).round() / p - v).abs() <= RELATIVE_PRECISION * v
+                })
+                .unwrap_or(3) as f64;
+            let size_penalty = if v < 1.0 {
+                (1.0 / v).log2()
+            } else {
+                0.5 * v.log10()
+            };
+            // decimals are hard to measure with units that use fractions
+            let no_fraction_penalty = if cfg.enabled && decimals > 0.0 {
+                NO_FRACTION_PENALTY
+            } else {
+                0.0
+            };
+            decimals + size_penalty + no_fraction_penalty
+        }
+    };
+    if cfg.enabled && value.trunc() > cfg.max_whole as f64 {
+        score += MAX_WHOLE_PENALTY;
+    }
+    score
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Number::Regular(1.0), Number::Regular(1.5))]
+    #[test_case(Number::Regular(1.5), Number::Regular(1.25))]
+    #[test_case(Number::Regular(2.0), Number::Regular(0.5))]
+    #[test_case(Number::Regular(473.176), Number::Regular(0.473))]
+    #[test_case(Number::Fraction { whole: 0, num: 1, den: 2, err: 0.0 }, Number::Fraction { whole: 0, num: 1, den: 8, err: 0.0 })]
+    #[test_case(Number::Fraction { whole: 0, num: 1, den: 3, err: 0.0 }, Number::Regular(0.333))]
+    fn more_readable(a: Number, b: Number) {
+        let cfg = FractionsConfig::default();
+        assert!(number_score(a, cfg) < number_score(b, cfg));
+    }
+}
@@ -0,0 +1,158 @@
+//! Find units with typos
+
+use std::sync::Arc;
+
+use unicase::UniCase;
+
+use super::{Converter, Unit};
+
+/// A unit found with [`Converter::fuzzy_find_units`]
+#[derive(Debug, Clone)]
+pub struct UnitMatch {
+    /// The unit
+    pub unit: Arc<Unit>,
+    /// Name, symbol or alias of the unit that matched
+    pub key: Arc<str>,
+    /// Number of edits to get the key, ignoring the case and a trailing dot
+    ///
+    /// The edits are insertions, deletions, substitutions and transpositions
+    /// of characters.
+    pub distance: usize,
+}
+
+impl Converter {
+    /// Find the units similar to the given text
+    ///
+    /// The text is compared to every name, symbol and alias ignoring the case
+    /// and a trailing dot, and allowing a few typos. The longer the text, the
+    /// more typos are allowed. Text with 3 characters or less can only differ
+    /// in the case or the dot.
+    ///
+    /// The result has one match per unit, ranked from most to least similar.
+    ///
+    /// ```
+    /// # use cooklang::convert::Converter;
+    /// let converter = Converter::bundled();
+    /// let matches = converter.fuzzy_find_units("gramms");
+    /// assert_eq!(&*matches[0].key, "grams");
+    /// let matches = converter.fuzzy_find_units("Ml");
+    /// assert_eq!(&*matches[0].key, "ml");
+    /// ```
+    pub fn fuzzy_find_units(&self, unit: &str) -> Vec<UnitMatch> {
+        let text = normalize(unit);
+        let max_distance = match text.chars().count() {
+            0..=3 => 0,
+            4..=5 => 1,
+            _ => 2,
+        };
+
+        // (match, if the case is different)
+        let mut matches: Vec<(UnitMatch, bool)> = Vec::new();
+        for candidate in &self.all_units {
+            let best = candidate
+                .all_keys()
+                .map(|key| (key, edit_distance(&text, &normalize(key))))
+                .filter(|(_, distance)| *distance <= max_distance)
+                .min_by_key(|(_, distance)| *distance);
+            if let Some((key, distance)) = best {
+                let other_case = trim_dot(key) != trim_dot(unit.trim());
+                let m = UnitMatch {
+                    unit: Arc::clone(candidate),
+                    key: Arc::clone(key),
+                    distance,
+                };
+                matches.push((m, other_case));
+            }
+        }
+        matches.sort_by_key(|(m, other_case)| (m.distance, *other_case));
+        matches.into_iter().map(|(m, _)| m).collect()
+    }
+
+    /// Find the only unit that is the most similar to the given text
+    ///
+    /// Returns [`None`] if no unit is similar or if more than one unit is
+    /// equally similar.
+    pub fn fuzzy_find_unit(&self, unit: &str) -> Option<UnitMatch> {
+        let mut matches = self.fuzzy_find_units(unit).into_iter();
+        let first = matches.next()?;
+        match matches.next() {
+            Some(second) if second.distance == first.distance => None,
+            _ => Some(first),
+        }
+    }
+}
+
+fn trim_dot(s: &str) -> &str {
+    s.strip_suffix('.').unwrap_or(s)
+}
+
+fn normalize(s: &str) -> String {
+    UniCase::new(trim_dot(s.trim())).to_folded_case()
+}
+
+/// Optimal string alignment distance between 2 strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // d[i][j] is the distance between a[..i] and b[..j]
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("gram", "gram" => 0)]
+    #[test_case("grams", "gramms" => 1)]
+    #[test_case("tbsp", "tbps" => 1; "transposition")]
+    #[test_case("cup", "cups" => 1)]
+    #[test_case("kilogram", "kilgoram" => 1)]
+    #[test_case("ml", "" => 2)]
+    #[test_case("litre", "liter" => 1)]
+    fn distance(a: &str, b: &str) -> usize {
+        edit_distance(a, b)
+    }
+
+    #[test_case("g" => Some("g".to_string()))]
+    #[test_case("gramms" => Some("grams".to_string()))]
+    #[test_case("tbps" => Some("tbsp".to_string()))]
+    #[test_case("Ml" => Some("ml".to_string()))]
+    #[test_case("TBSP" => Some("tbsp".to_string()); "uppercase")]
+    #[test_case("oz." => Some("oz".to_string()); "trailing dot")]
+    #[test_case("x" => None)]
+    #[test_case("bag" => None)]
+    #[test_case("cucumber" => None)]
+    fn find(unit: &str) -> Option<String> {
+        let converter = Converter::bundled();
+        converter.fuzzy_find_unit(unit).map(|m| m.key.to_string())
+    }
+
+    #[test]
+    fn ranked() {
+        let converter = Converter::bundled();
+        let matches = converter.fuzzy_find_units("Meters.");
+        assert_eq!(matches[0].distance, 0);
+        assert_eq!(&*matches[0].key, "meters");
+        assert!(matches.len() > 1);
+        assert!(matches.windows(2).all(|w| w[0].distance <= w[1].distance));
+    }
+}
@@ -0,0 +1,565 @@
+//! Load units files reporting errors with their location
+//!
+//! [`ConverterBuilder::add_units_file`] only says what went wrong. The
+//! [`UnitsLoader`] parses the TOML keeping the spans of every key and value, so
+//! problems are reported as a [`SourceReport`] for each file, rendered like the
+//! recipe diagnostics.
+//!
+//! This is only available with the `units_loader` feature.
+
+use std::{collections::HashMap, ops::Range, sync::Arc};
+
+use toml_edit::{ImDocument, Item, Table, Value as TomlValue};
+
+use super::{
+    units_file::{Precedence, UnitEntry, Units, UnitsFile},
+    Converter, ConverterBuilder, ConverterBuilderError, PhysicalQuantity, UnknownUnit,
+};
+use crate::error::{label, Label, Severity, SourceDiag, SourceReport, Stage};
+
+/// Loads units files in TOML into a [`Converter`]
+///
+/// Like a [`ConverterBuilder`], files are layered in the order they are added.
+///
+/// ```
+/// # use cooklang::convert::UnitsLoader;
+/// let mut loader = UnitsLoader::new();
+/// loader.add_bundled_units();
+/// loader.add_toml(
+///     "my_units.toml",
+///     r#"
+///     [extend.units]
+///     unknown = { aliases = ["something"] }
+///     "#,
+/// );
+/// let loaded = loader.finish();
+/// assert!(loaded.converter.is_none());
+/// let report = &loaded.files[1].report;
+/// assert_eq!(report.errors().count(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct UnitsLoader {
+    builder: ConverterBuilder,
+    files: Vec<LoadedFile>,
+    /// Which file set each setting that is replaced by later layers
+    settings: HashMap<Setting, usize>,
+    /// Which file defined each unit name, to warn when a later layer changes
+    /// the unit
+    unit_names: HashMap<Arc<str>, usize>,
+}
+
+#[derive(Debug)]
+struct LoadedFile {
+    report: UnitsFileReport,
+    entries: Vec<Entry>,
+}
+
+/// A key or a value in a TOML file
+#[derive(Debug)]
+struct Entry {
+    /// Path of the table the key is in, or of the value. Arrays of tables
+    /// include the index.
+    path: Vec<String>,
+    is_key: bool,
+    text: String,
+    span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Setting {
+    DefaultSystem,
+    Best(PhysicalQuantity),
+    Composite(PhysicalQuantity),
+    Fit(PhysicalQuantity),
+}
+
+/// How a layer changes a unit of a previous one with `[extend.units]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnitChange {
+    /// The ratio or difference
+    Redefined,
+    /// The names, symbols or aliases are replaced, so the previous ones are
+    /// no longer units
+    Shadowed,
+}
+
+/// Errors and warnings of a units file loaded with [`UnitsLoader`]
+#[derive(Debug, Clone)]
+pub struct UnitsFileReport {
+    /// Name given to the file
+    pub name: String,
+    /// TOML source of the file
+    pub source: String,
+    /// Errors and warnings
+    pub report: SourceReport,
+}
+
+/// Output of [`UnitsLoader::finish`]
+#[derive(Debug)]
+pub struct LoadedUnits {
+    /// The converter, only if there are no errors in any file
+    pub converter: Option<Converter>,
+    /// Reports of every file in the order they were added
+    pub files: Vec<UnitsFileReport>,
+}
+
+impl LoadedUnits {
+    /// Check if any of the files has errors
+    pub fn has_errors(&self) -> bool {
+        self.files.iter().any(|f| f.report.has_errors())
+    }
+
+    /// Write the reports of all the files
+    pub fn write(&self, color: bool, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        for file in &self.files {
+            file.report.write(&file.name, &file.source, color, w)?;
+        }
+        Ok(())
+    }
+
+    /// Print the reports of all the files to stderr
+    pub fn eprint(&self, color: bool) -> std::io::Result<()> {
+        for file in &self.files {
+            file.report.eprint(&file.name, &file.source, color)?;
+        }
+        Ok(())
+    }
+}
+
+impl UnitsLoader {
+    /// New empty loader
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the bundled units file, named `units.toml`
+    ///
+    /// This is only available with the `bundled_units` feature.
+    #[cfg(feature = "bundled_units")]
+    pub fn add_bundled_units(&mut self) -> &mut Self {
+        self.add_toml("units.toml", super::units_file::BUNDLED)
+    }
+
+    /// Add a units file in TOML
+    ///
+    /// If the file has errors, it's not added to the converter and they are
+    /// reported in [`LoadedUnits::files`].
+    pub fn add_toml(&mut self, name: impl Into<String>, source: impl Into<String>) -> &mut Self {
+        let mut file = LoadedFile {
+            report: UnitsFileReport {
+                name: name.into(),
+                source: source.into(),
+                report: SourceReport::empty(),
+            },
+            entries: Vec::new(),
+        };
+
+        let doc = match ImDocument::parse(file.report.source.as_str()) {
+            Ok(doc) => doc,
+            Err(e) => {
+                file.report.report.error(toml_error(e.message(), e.span()));
+                self.files.push(file);
+                return self;
+            }
+        };
+        collect_table(doc.as_table(), &mut Vec::new(), &mut file.entries);
+
+        let units: UnitsFile = match toml::from_str(&file.report.source) {
+            Ok(units) => units,
+            Err(e) => {
+                file.report.report.error(toml_error(e.message(), e.span()));
+                self.files.push(file);
+                return self;
+            }
+        };
+
+        let settings = settings(&units, &file.entries);
+        let defined = defined_units(&units);
+        let changed = changed_units(&units);
+        let mut builder = self.builder.clone();
+        match builder.add_units_file(units) {
+            Ok(_) => {
+                self.builder = builder;
+                self.warn_replaced(&mut file, settings);
+                self.warn_changed_units(&mut file, changed);
+                let index = self.files.len();
+                for name in defined {
+                    self.unit_names.entry(name).or_insert(index);
+                }
+            }
+            Err(err) => {
+                let diag = self.builder_error(err, &file.entries);
+                file.report.report.error(diag);
+            }
+        }
+        self.files.push(file);
+        self
+    }
+
+    /// Add a units file in TOML
+    pub fn with_toml(mut self, name: impl Into<String>, source: impl Into<String>) -> Self {
+        self.add_toml(name, source);
+        self
+    }
+
+    /// Consume the loader and build the [`Converter`]
+    ///
+    /// Errors that are only detected when all the layers are joined are
+    /// reported in the last file that could have caused them.
+    pub fn finish(mut self) -> LoadedUnits {
+        let has_errors = self.files.iter().any(|f| f.report.report.has_errors());
+        let converter = if self.files.is_empty() {
+            Some(Converter::empty())
+        } else if has_errors {
+            None
+        } else {
+            match self.builder.finish() {
+                Ok(converter) => Some(converter),
+                Err(err) => {
+                    let index = self
+                        .files
+                        .iter()
+                        .rposition(|f| locate(&err, &f.entries).is_some())
+                        .unwrap_or(self.files.len() - 1);
+                    let file = &mut self.files[index];
+                    let diag = builder_diag(err, &file.entries);
+                    file.report.report.error(diag);
+                    None
+                }
+            }
+        };
+        LoadedUnits {
+            converter,
+            files: self.files.into_iter().map(|f| f.report).collect(),
+        }
+    }
+
+    fn builder_error(&self, err: ConverterBuilderError, entries: &[Entry]) -> SourceDiag {
+        let previous = match &err {
+            ConverterBuilderError::DuplicateUnit { name } => self
+                .files
+                .iter()
+                .rfind(|f| {
+                    f.entries
+                        .iter()
+                        .any(|e| !e.is_key && e.in_units() && e.text == *name)
+                })
+                .map(|f| f.report.name.clone()),
+            _ => None,
+        };
+        let mut diag = builder_diag(err, entries);
+        if let Some(previous) = previous {
+            diag.add_hint(format!("It's already defined in '{previous}'"));
+        }
+        diag
+    }
+
+    fn warn_replaced(
+        &mut self,
+        file: &mut LoadedFile,
+        settings: Vec<(Setting, Option<Range<usize>>)>,
+    ) {
+        let index = self.files.len();
+        for (setting, span) in settings {
+            if let Some(previous) = self.settings.insert(setting, index) {
+                let Some(span) = span else { continue };
+                let previous = &self.files[previous].report.name;
+                let diag = SourceDiag::warning(
+                    format!("This replaces the {setting} set in '{previous}'"),
+                    label!(span),
+                    Stage::Analysis,
+                );
+                file.report.report.warn(diag);
+            }
+        }
+    }
+
+    fn warn_changed_units(&self, file: &mut LoadedFile, changed: Vec<(String, UnitChange)>) {
+        for (key, change) in changed {
+            let Some(&previous) = self.unit_names.get(key.as_str()) else {
+                continue;
+            };
+            let Some(span) = file
+                .entries
+                .iter()
+                .find(|e| e.is_key && e.path == ["extend", "units"] && e.text == key)
+                .map(|e| e.span.clone())
+            else {
+                continue;
+            };
+            let previous = &self.files[previous].report.name;
+            let message = match change {
+                UnitChange::Redefined => {
+                    format!("This redefines the unit '{key}' from '{previous}'")
+                }
+                UnitChange::Shadowed => {
+                    format!("This replaces the names of the unit '{key}' from '{previous}'")
+                }
+            };
+            let diag = SourceDiag::warning(message, label!(span), Stage::Analysis);
+            file.report.report.warn(diag);
+        }
+    }
+}
+
+impl std::fmt::Display for Setting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Setting::DefaultSystem => write!(f, "default system"),
+            Setting::Best(q) => write!(f, "best units of {q}"),
+            Setting::Composite(q) => write!(f, "composite display of {q}"),
+            Setting::Fit(q) => write!(f, "fit of {q}"),
+        }
+    }
+}
+
+impl Entry {
+    fn under(&self, prefix: &[&str]) -> bool {
+        self.path.len() >= prefix.len() && self.path.iter().zip(prefix).all(|(a, b)| a == b)
+    }
+
+    /// The `quantity` value of a `[[quantity]]` group
+    fn is_group_quantity(&self) -> bool {
+        self.path.len() == 3 && self.path[0] == "quantity" && self.path[2] == "quantity"
+    }
+
+    /// A value in the definition of a unit
+    fn in_units(&self) -> bool {
+        self.path.iter().any(|p| p == "units")
+    }
+}
+
+/// Settings the file replaces and where
+fn settings(units: &UnitsFile, entries: &[Entry]) -> Vec<(Setting, Option<Range<usize>>)> {
+    let key_span = |path: &[&str], key: &str| {
+        entries
+            .iter()
+            .find(|e| e.is_key && e.path == path && e.text == key)
+            .map(|e| e.span.clone())
+    };
+    let mut settings = Vec::new();
+    if units.default_system.is_some() {
+        settings.push((Setting::DefaultSystem, key_span(&[], "default_system")));
+    }
+    for (index, group) in units.quantity.iter().enumerate() {
+        let q = group.quantity;
+        let index = index.to_string();
+        let path = ["quantity", index.as_str()];
+        if group.best.is_some() {
+            settings.push((Setting::Best(q), key_span(&path, "best")));
+        }
+        if group.composite.is_some() {
+            settings.push((Setting::Composite(q), key_span(&path, "composite")));
+        }
+        if group.fit.is_some() {
+            settings.push((Setting::Fit(q), key_span(&path, "fit")));
+        }
+    }
+    settings
+}
+
+/// All the names of the units defined in the file
+fn defined_units(units: &UnitsFile) -> Vec<Arc<str>> {
+    let mut names = Vec::new();
+    for group in &units.quantity {
+        let entries: Vec<&UnitEntry> = match &group.units {
+            Some(Units::Unified(v)) => v.iter().collect(),
+            Some(Units::BySystem {
+                metric,
+                imperial,
+                unspecified,
+            }) => metric.iter().chain(imperial).chain(unspecified).collect(),
+            None => continue,
+        };
+        for entry in entries {
+            names.extend(
+                entry
+                    .names
+                    .iter()
+                    .chain(&entry.symbols)
+                    .chain(&entry.aliases)
+                    .chain(&entry.singular)
+                    .chain(&entry.plural)
+                    .cloned(),
+            );
+        }
+    }
+    names
+}
+
+/// Units of any layer the file changes in `[extend.units]`
+fn changed_units(units: &UnitsFile) -> Vec<(String, UnitChange)> {
+    let Some(extend) = &units.extend else {
+        return Vec::new();
+    };
+    let mut changed = extend
+        .units
+        .iter()
+        .filter_map(|(key, entry)| {
+            let change = if entry.ratio.is_some() || entry.difference.is_some() {
+                UnitChange::Redefined
+            } else if extend.precedence == Precedence::Override
+                && (entry.names.is_some() || entry.symbols.is_some() || entry.aliases.is_some())
+            {
+                UnitChange::Shadowed
+            } else {
+                return None;
+            };
+            Some((key.clone(), change))
+        })
+        .collect::<Vec<_>>();
+    // the extend table is a hash map
+    changed.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    changed
+}
+
+fn toml_error(message: &str, span: Option<Range<usize>>) -> SourceDiag {
+    let message = format!("Invalid units file: {}", message.trim_end());
+    match span {
+        Some(span) => SourceDiag::error(message, label!(span), Stage::Parse),
+        None => SourceDiag::unlabeled(message, Severity::Error, Stage::Parse),
+    }
+}
+
+fn builder_diag(err: ConverterBuilderError, entries: &[Entry]) -> SourceDiag {
+    let message = err.to_string();
+    let diag = match locate(&err, entries) {
+        Some(label) => SourceDiag::error(message, label, Stage::Analysis),
+        None => SourceDiag::unlabeled(message, Severity::Error, Stage::Analysis),
+    };
+    let diag = match &err {
+        ConverterBuilderError::EmptySIPrefixes => {
+            diag.hint("Add the SI prefixes in the 'si' table of this or a previous file")
+        }
+        ConverterBuilderError::EmptyBest { .. } => {
+            diag.hint("Every quantity needs best units in at least one file")
+        }
+        _ => diag,
+    };
+    diag.set_source(err)
+}
+
+/// Find where a builder error is in a file
+fn locate(err: &ConverterBuilderError, entries: &[Entry]) -> Option<Label> {
+    use ConverterBuilderError as E;
+
+    let find = |pred: &dyn Fn(&Entry) -> bool| {
+        entries
+            .iter()
+            .rev()
+            .find(|e| pred(e))
+            .map(|e| e.span.clone())
+    };
+
+    match err {
+        E::DuplicateUnit { name } => find(&|e| !e.is_key && e.in_units() && e.text == *name)
+            .map(|span| label!(span, "duplicated here")),
+        E::DuplicateExtendUnit { key } | E::InvalidExtendExpanded { key } => {
+            find(&|e| e.is_key && e.under(&["extend", "units"]) && e.text == *key)
+                .map(|span| label!(span))
+        }
+        E::UnknownUnit(UnknownUnit(unit)) => find(&|e| {
+            e.text == *unit
+                && (!e.is_key
+                    || e.under(&["extend", "units"])
+                    || e.under(&["fractions", "unit"])
+                    || e.under(&["rounding", "unit"]))
+        })
+        .map(|span| label!(span, "unknown unit")),
+        E::EmptyUnitKey { .. } => {
+            find(&|e| !e.is_key && e.in_units() && e.text.is_empty()).map(|span| label!(span))
+        }
+        E::EmptyBest { quantity, .. } => group_key(entries, *quantity, "best")
+            .or_else(|| {
+                let quantity = quantity.to_string();
+                find(&|e| !e.is_key && e.is_group_quantity() && e.text == quantity)
+            })
+            .map(|span| label!(span)),
+        E::EmptySIPrefixes => {
+            find(&|e| e.is_key && e.text == "expand_si").map(|span| label!(span, "expanded here"))
+        }
+        E::EmptyUnit { .. } | E::LocaleNotBundled { .. } => None,
+    }
+}
+
+/// Span of a key in the last `[[quantity]]` group of a physical quantity
+fn group_key(entries: &[Entry], quantity: PhysicalQuantity, key: &str) -> Option<Range<usize>> {
+    let quantity = quantity.to_string();
+    entries
+        .iter()
+        .rev()
+        .filter(|e| !e.is_key && e.is_group_quantity() && e.text == quantity)
+        .find_map(|group| {
+            entries
+                .iter()
+                .find(|e| {
+                    e.is_key && e.path.len() == 2 && e.path[..] == group.path[..2] && e.text == key
+                })
+                .map(|e| e.span.clone())
+        })
+}
+
+fn collect_table(table: &Table, path: &mut Vec<String>, entries: &mut Vec<Entry>) {
+    for (key, item) in table.iter() {
+        if let Some(span) = table.key(key).and_then(|k| k.span()) {
+            entries.push(Entry {
+                path: path.clone(),
+                is_key: true,
+                text: key.to_string(),
+                span,
+            });
+        }
+        path.push(key.to_string());
+        match item {
+            Item::None => {}
+            Item::Value(value) => collect_value(value, path, entries),
+            Item::Table(table) => collect_table(table, path, entries),
+            Item::ArrayOfTables(array) => {
+                for (index, table) in array.iter().enumerate() {
+                    path.push(index.to_string());
+                    collect_table(table, path, entries);
+                    path.pop();
+                }
+            }
+        }
+        path.pop();
+    }
+}
+
+fn collect_value(value: &TomlValue, path: &mut Vec<String>, entries: &mut Vec<Entry>) {
+    match value {
+        TomlValue::Array(array) => {
+            for value in array.iter() {
+                collect_value(value, path, entries);
+            }
+        }
+        TomlValue::InlineTable(table) => {
+            for (key, value) in table.iter() {
+                if let Some(span) = table.key(key).and_then(|k| k.span()) {
+                    entries.push(Entry {
+                        path: path.clone(),
+                        is_key: true,
+                        text: key.to_string(),
+                        span,
+                    });
+                }
+                path.push(key.to_string());
+                collect_value(value, path, entries);
+                path.pop();
+            }
+        }
+        value => {
+            let Some(span) = value.span() else { return };
+            let text = match value {
+                TomlValue::String(s) => s.value().clone(),
+                other => other.to_string().trim().to_string(),
+            };
+            entries.push(Entry {
+                path: path.clone(),
+                is_key: false,
+                text,
+                span,
+            });
+        }
+    }
+}
@@ -15,14 +15,32 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    quantity::{Number, Quantity, ScaledQuantity, Value},
-    ScaledRecipe, UnitInfo,
+    error::{label, Severity, SourceDiag, SourceReport, Stage},
+    quantity::{
+        CompositeQuantity, Number, Quantity, QuantityValue, Rational, ScaledQuantity, Value,
+    },
+    scale::{ScaleOutcome, Scaled},
+    ScaledRecipe, Span, UnitInfo,
 };
 
 pub use builder::{ConverterBuilder, ConverterBuilderError};
-pub use units_file::UnitsFile;
+pub(crate) use duration::{Duration, DurationUnit};
+pub use fuzzy::UnitMatch;
+#[cfg(feature = "units_loader")]
+pub use loader::{LoadedUnits, UnitsFileReport, UnitsLoader};
+pub use profile::{ConversionProfile, ProfileTarget};
+pub use units_file::{UnitForms, UnitsFile};
 
 mod builder;
+mod duration;
+mod export;
+mod fit;
+mod fuzzy;
+#[cfg(feature = "units_loader")]
+mod loader;
+mod number_words;
+mod profile;
+mod rounding;
 pub mod units_file;
 
 /// Main struct to perform conversions
@@ -41,6 +59,10 @@ pub struct Converter {
     quantity_index: UnitQuantityIndex,
     best: EnumMap<PhysicalQuantity, BestConversionsStore>,
     fractions: Fractions,
+    rounding: rounding::Rounding,
+    composite: EnumMap<PhysicalQuantity, EnumMap<System, bool>>,
+    fit: EnumMap<PhysicalQuantity, units_file::Fit>,
+    number_words: units_file::NumberWords,
     default_system: System,
 
     temperature_regex: OnceCell<Regex>,
@@ -69,6 +91,10 @@ impl Converter {
             default_system: Default::default(),
             temperature_regex: Default::default(),
             fractions: Default::default(),
+            rounding: Default::default(),
+            composite: Default::default(),
+            fit: Default::default(),
+            number_words: Default::default(),
         }
     }
 
@@ -193,6 +219,10 @@ impl PartialEq for Converter {
             && self.unit_index == other.unit_index
             && self.quantity_index == other.quantity_index
             && self.best == other.best
+            && self.composite == other.composite
+            && self.fit == other.fit
+            && self.rounding == other.rounding
+            && self.number_words == other.number_words
             && self.default_system == other.default_system
         // temperature_regex ignored, it should be the same if the rest is the
         // the same
@@ -277,6 +307,12 @@ pub struct Unit {
     pub symbols: Vec<Arc<str>>,
     /// Custom aliases to parse the unit from a different string
     pub aliases: Vec<Arc<str>>,
+    /// Singular and plural names to display the unit
+    ///
+    /// If [`None`], the first name is used for both.
+    pub forms: Option<UnitForms>,
+    /// Singular and plural names in other locales
+    pub locale_forms: HashMap<String, UnitForms>,
     /// Conversion ratio
     pub ratio: f64,
     /// Difference offset to the conversion ratio
@@ -304,6 +340,52 @@ impl Unit {
             .or_else(|| self.aliases.first())
             .expect("symbol, name or alias in unit")
     }
+
+    /// Get the name to display the unit with a value
+    ///
+    /// Chooses the singular or plural form with [`QuantityValue::is_singular`].
+    /// If a locale is given, like `es` or `pt-BR`, and the unit has forms for
+    /// it, or for its language, they are used.
+    ///
+    /// If the unit has no forms, it's the first name and, if it has no names,
+    /// the [`symbol`](Self::symbol).
+    pub fn name_for(&self, value: &impl QuantityValue, locale: Option<&str>) -> &str {
+        let locale_forms = locale.and_then(|locale| {
+            self.locale_forms.get(locale).or_else(|| {
+                let language = locale.split(['-', '_']).next()?;
+                self.locale_forms.get(language)
+            })
+        });
+        match locale_forms.or(self.forms.as_ref()) {
+            Some(forms) => forms.for_value(value),
+            None => self
+                .names
+                .first()
+                .map(|n| n.as_ref())
+                .unwrap_or_else(|| self.symbol()),
+        }
+    }
+
+    /// Finds the forms a name belongs to
+    pub(crate) fn forms_of(&self, name: &str) -> Option<&UnitForms> {
+        self.forms
+            .iter()
+            .chain(self.locale_forms.values())
+            .find(|f| &*f.singular == name || &*f.plural == name)
+    }
+}
+
+impl UnitForms {
+    /// Get the singular or plural form for a value
+    ///
+    /// See [`QuantityValue::is_singular`].
+    pub fn for_value(&self, value: &impl QuantityValue) -> &str {
+        if value.is_singular() {
+            &self.singular
+        } else {
+            &self.plural
+        }
+    }
 }
 
 impl PartialEq for Unit {
@@ -311,6 +393,8 @@ impl PartialEq for Unit {
         self.names == other.names
             && self.symbols == other.symbols
             && self.aliases == other.aliases
+            && self.forms == other.forms
+            && self.locale_forms == other.locale_forms
             && self.ratio == other.ratio
             && self.difference == other.difference
             && self.physical_quantity == other.physical_quantity
@@ -388,7 +472,7 @@ impl BestConversions {
         Some(Arc::clone(&converter.all_units[best_id]))
     }
 
-    fn all_units<'c>(&'c self, converter: &'c Converter) -> impl Iterator<Item = &Arc<Unit>> {
+    fn all_units<'c>(&'c self, converter: &'c Converter) -> impl Iterator<Item = &'c Arc<Unit>> {
         self.0.iter().map(|(_, uid)| &converter.all_units[*uid])
     }
 }
@@ -419,42 +503,102 @@ pub enum PhysicalQuantity {
 }
 
 impl ScaledRecipe {
-    /// Convert a [`ScaledRecipe`] to another [`System`] in place.
+    /// Convert a [`ScaledRecipe`] to another [`System`] or following a
+    /// [`ConversionProfile`] in place.
     ///
     /// When an error occurs, it is stored and the quantity stays the same.
     ///
-    /// Returns all the errors while converting. These usually are missing units,
-    /// unknown units or text values.
-    pub fn convert(&mut self, to: System, converter: &Converter) -> Vec<ConvertError> {
-        let mut errors = Vec::new();
+    /// Converted values are rounded with the converter
+    /// [`Rounding`](units_file::Rounding) configuration. If the recipe was
+    /// scaled, the outcome of a rounded component changes to
+    /// [`ScaleOutcome::Rounded`].
+    ///
+    /// Returns a report with all the errors while converting. These usually
+    /// are missing units, unknown units or text values. The errors are located
+    /// with the [`SourceMap`](crate::SourceMap) of the recipe and the
+    /// [`ConvertError`] is the source of each one.
+    pub fn convert<'a>(
+        &mut self,
+        to: impl Into<ConvertTo<'a>>,
+        converter: &Converter,
+    ) -> SourceReport {
+        let mut report = SourceReport::empty();
 
-        let to = ConvertTo::from(to);
+        let to = to.into();
 
-        let mut conv = |q: &mut ScaledQuantity| {
-            if let Err(e) = q.convert(to, converter) {
-                errors.push(e)
+        let mut conv = |q: &mut ScaledQuantity, span: Option<&Span>, what: String| match q
+            .convert(to, converter)
+        {
+            Ok(()) => true,
+            Err(e) => {
+                let mut diag = SourceDiag::unlabeled(
+                    format!("Can't convert {what}"),
+                    Severity::Error,
+                    Stage::Convert,
+                )
+                .set_source(e);
+                if let Some(span) = span {
+                    diag.add_label(label!(span, "this quantity"));
+                }
+                report.error(diag);
+                false
             }
         };
 
-        for igr in &mut self.ingredients {
+        let (mut ingredient_outcomes, mut timer_outcomes, mut inline_outcomes) =
+            match &mut self.data {
+                Scaled::Scaled(data) => (
+                    Some(data.ingredients.as_mut_slice()),
+                    Some(data.timers.as_mut_slice()),
+                    Some(data.inline_quantities.as_mut_slice()),
+                ),
+                Scaled::DefaultScaling => (None, None, None),
+            };
+        let source_map = &self.source_map;
+
+        // converted values are always rounded, but only scaled ones record it
+        let round =
+            |q: &mut ScaledQuantity, outcomes: Option<&mut [ScaleOutcome]>, index: usize| {
+                match outcomes.and_then(|o| o.get_mut(index)) {
+                    Some(outcome) if outcome.is_scaled() => outcome.round(q, converter),
+                    _ => {
+                        q.round(converter);
+                    }
+                }
+            };
+
+        for (index, igr) in self.ingredients.iter_mut().enumerate() {
+            let name = igr.display_name().into_owned();
             if let Some(q) = &mut igr.quantity {
-                conv(q);
+                let what = format!("'{q}' of {name}");
+                if conv(q, source_map.ingredients.get(index), what) {
+                    round(q, ingredient_outcomes.as_deref_mut(), index);
+                }
             }
         }
 
         // cookware can't have units
 
-        for timer in &mut self.timers {
+        for (index, timer) in self.timers.iter_mut().enumerate() {
             if let Some(q) = &mut timer.quantity {
-                conv(q);
+                let what = match &timer.name {
+                    Some(name) => format!("'{q}' of timer {name}"),
+                    None => format!("timer '{q}'"),
+                };
+                if conv(q, source_map.timers.get(index), what) {
+                    round(q, timer_outcomes.as_deref_mut(), index);
+                }
             }
         }
 
-        for q in &mut self.inline_quantities {
-            conv(q);
+        for (index, q) in self.inline_quantities.iter_mut().enumerate() {
+            let what = format!("inline quantity '{q}'");
+            if conv(q, source_map.inline_quantities.get(index), what) {
+                round(q, inline_outcomes.as_deref_mut(), index);
+            }
         }
 
-        errors
+        report
     }
 }
 
@@ -471,9 +615,16 @@ impl ScaledQuantity {
     fn convert_impl(&mut self, to: ConvertTo, converter: &Converter) -> Result<(), ConvertError> {
         let unit_info = self.unit().map(|u| u.unit_info_or_parse(converter));
         let original_system;
+        let mut to = to;
         let unit = match unit_info {
             Some(UnitInfo::Known(ref u)) => {
                 original_system = u.system;
+                if let ConvertTo::Profile(profile) = to {
+                    match profile.target(u, converter)? {
+                        Some(target) => to = target,
+                        None => return Ok(()), // keep as is
+                    }
+                }
                 ConvertUnit::Unit(u)
             }
             Some(UnitInfo::Unknown) => {
@@ -485,8 +636,12 @@ impl ScaledQuantity {
         };
         let value = ConvertValue::try_from(&self.value)?;
 
+        let from = converter.get_unit(&unit)?;
         let (new_value, new_unit) = converter.convert(value, unit, to)?;
-        *self = Quantity::with_known_unit(new_value.into(), Arc::clone(&new_unit));
+        let new_value = converter
+            .convert_exact(&self.value, from, &new_unit)
+            .unwrap_or_else(|| new_value.into());
+        *self = Quantity::with_known_unit(new_value, Arc::clone(&new_unit));
         match to {
             ConvertTo::Unit(_) => {
                 self.try_fraction(converter);
@@ -497,6 +652,7 @@ impl ScaledQuantity {
             ConvertTo::SameSystem => {
                 self.fit_fraction(&new_unit, original_system, converter)?;
             }
+            ConvertTo::Profile(_) => unreachable!("profile already resolved"),
         }
         Ok(())
     }
@@ -549,7 +705,54 @@ impl ScaledQuantity {
         let value = match self.value {
             Value::Number(n) => n.value(),
             Value::Range { start, .. } => start.value(),
-            Value::Text(ref t) => return Err(ConvertError::TextValue(t.clone())),
+            Value::Text(ref t) | Value::Indefinite(ref t) => {
+                return Err(ConvertError::TextValue(t.clone()))
+            }
+        };
+
+        let selected = if converter.is_readable_fit(unit) {
+            converter
+                .readable_fit(value, unit, system)
+                .map(|fitted| (fitted.value, fitted.unit))
+        } else {
+            Self::select_fraction(value, unit, system, converter)
+        };
+
+        let Some((new_value, new_unit)) = selected else {
+            return Ok(false);
+        };
+
+        let new_value = match self.value {
+            Value::Number(_) => Value::Number(new_value),
+            Value::Range { end, .. } => {
+                let end = converter.convert_f64(end.value(), unit, &new_unit);
+                let end_frac = approx(end, converter.fractions_config(&new_unit))
+                    .unwrap_or(Number::Regular(end));
+                Value::Range {
+                    start: new_value,
+                    end: end_frac,
+                }
+            }
+            Value::Text(_) | Value::Indefinite(_) => unreachable!(),
+        };
+        // keep the exact value if the approximation didn't change it
+        let new_value = match converter.convert_exact(&self.value, unit, &new_unit) {
+            Some(exact) if approx_eq(&exact, &new_value) => exact,
+            _ => new_value,
+        };
+        *self = Quantity::with_known_unit(new_value, new_unit);
+        Ok(true)
+    }
+
+    /// Selects the best unit where the value is closest to a fraction
+    fn select_fraction(
+        value: f64,
+        unit: &Unit,
+        system: System,
+        converter: &Converter,
+    ) -> Option<(Number, Arc<Unit>)> {
+        let approx = |val: f64, cfg: FractionsConfig| {
+            Number::new_approx(val, cfg.accuracy, cfg.max_denominator, cfg.max_whole)
         };
 
         let possible_conversions = converter.best[unit.physical_quantity]
@@ -583,25 +786,109 @@ impl ScaledQuantity {
             a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Less)
         });
 
-        let Some((new_value, new_unit)) = selected else {
-            return Ok(false);
+        selected.map(|(new_value, new_unit)| (new_value, Arc::clone(new_unit)))
+    }
+
+    /// Splits the quantity into the best units of its system
+    ///
+    /// For example, `90 min` would be `1 h 30 min` and `35 oz`, `2 lb 3 oz`.
+    ///
+    /// This is only possible when the units configuration enables composite
+    /// quantities for the physical quantity and system of the unit. If it's
+    /// not enabled, or the value is not a positive number with a known unit,
+    /// returns [`None`].
+    ///
+    /// The last part is tried to be converted to a fraction, respecting the
+    /// converter configuration.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn to_composite(&self, converter: &Converter) -> Option<CompositeQuantity> {
+        let Some(UnitInfo::Known(unit)) = self.unit().map(|u| u.unit_info_or_parse(converter))
+        else {
+            return None;
         };
+        let Value::Number(n) = &self.value else {
+            return None;
+        };
+        let system = unit.system.unwrap_or(converter.default_system);
+        if !converter.composite[unit.physical_quantity][system] {
+            return None;
+        }
 
-        let new_value = match self.value {
-            Value::Number(_) => Value::Number(new_value),
-            Value::Range { end, .. } => {
-                let end = converter.convert_f64(end.value(), unit, new_unit);
-                let end_frac = approx(end, converter.fractions_config(new_unit))
-                    .unwrap_or(Number::Regular(end));
-                Value::Range {
-                    start: new_value,
-                    end: end_frac,
-                }
+        // best units are sorted from smallest to biggest
+        let best = converter.best[unit.physical_quantity]
+            .conversions(system)
+            .all_units(converter)
+            .collect::<Vec<_>>();
+        let (smallest, bigger) = best.split_first()?;
+        if unit.difference != 0.0 || best.iter().any(|u| u.difference != 0.0) {
+            return None;
+        }
+
+        let mut rest = converter.convert_f64(n.value(), &unit, smallest);
+        if rest <= 0.0 || !rest.is_finite() {
+            return None;
+        }
+
+        let mut parts = Vec::with_capacity(best.len());
+        for &part_unit in bigger.iter().rev() {
+            // small tolerance for the conversion errors, so 59.999.. min is 1 h
+            let whole = (converter.convert_f64(rest, smallest, part_unit) + 1e-9).floor();
+            if whole >= 1.0 {
+                parts.push(Quantity::with_known_unit(
+                    Value::from(whole),
+                    Arc::clone(part_unit),
+                ));
+                rest -= converter.convert_f64(whole, part_unit, smallest);
             }
-            Value::Text(_) => unreachable!(),
+        }
+
+        let rest = (rest * 1000.0).round() / 1000.0;
+        if rest > 0.0 || parts.is_empty() {
+            let mut last = Quantity::with_known_unit(Value::from(rest), Arc::clone(smallest));
+            last.try_fraction(converter);
+            parts.push(last);
+        }
+
+        Some(CompositeQuantity::new(parts))
+    }
+
+    /// Fits the quantity, allowing to split it in 2 units
+    ///
+    /// Like [`Self::fit`], but when the units configuration enables `split`
+    /// for a [readable](units_file::FitStrategy::Readable) fit, the value can
+    /// be split in 2 of the best units if that is more readable. For example,
+    /// `5 tbsp` would be `1/4 cup + 1 tbsp`.
+    ///
+    /// If it's not split, the composite quantity only has 1 part.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn fit_split(&self, converter: &Converter) -> Result<CompositeQuantity, ConvertError> {
+        let unit = match self.unit().map(|u| u.unit_info_or_parse(converter)) {
+            Some(UnitInfo::Known(unit)) => unit,
+            Some(UnitInfo::Unknown) => {
+                return Err(ConvertError::UnknownUnit(UnknownUnit(
+                    self.unit_text().unwrap().to_string(),
+                )))
+            }
+            None => return Err(ConvertError::NoUnit(self.clone())),
         };
-        *self = Quantity::with_known_unit(new_value, Arc::clone(new_unit));
-        Ok(true)
+        if let Value::Text(t) | Value::Indefinite(t) = &self.value {
+            return Err(ConvertError::TextValue(t.clone()));
+        }
+
+        // ranges are never split
+        if let (true, Value::Number(n)) = (converter.is_readable_fit(&unit), &self.value) {
+            let system = unit.system.unwrap_or(converter.default_system);
+            if let Some((big, small)) = converter.readable_split(n.value(), &unit, system) {
+                return Ok(CompositeQuantity::new(vec![
+                    Quantity::with_known_unit(Value::Number(big.value), big.unit),
+                    Quantity::with_known_unit(Value::Number(small.value), small.unit),
+                ]));
+            }
+        }
+
+        let mut single = self.clone();
+        single.fit(converter)?;
+        Ok(CompositeQuantity::new(vec![single]))
     }
 
     /// Tries to convert the value to a fraction, keeping the same unit
@@ -626,7 +913,7 @@ impl ScaledQuantity {
                 start.try_approx(cfg.accuracy, cfg.max_denominator, cfg.max_whole)
                     || end.try_approx(cfg.accuracy, cfg.max_denominator, cfg.max_whole)
             }
-            Value::Text(_) => false,
+            Value::Text(_) | Value::Indefinite(_) => false,
         }
     }
 }
@@ -651,6 +938,10 @@ impl Converter {
             ConvertTo::SameSystem => {
                 self.convert_to_best(value, unit, unit.system.unwrap_or(self.default_system))?
             }
+            ConvertTo::Profile(profile) => match profile.target(unit, self)? {
+                Some(to) => return self.convert(value, ConvertUnit::Unit(unit), to),
+                None => (value, Arc::clone(unit)),
+            },
         };
         Ok((value, unit))
     }
@@ -700,6 +991,42 @@ impl Converter {
         }
     }
 
+    /// Converts a value without losing precision
+    ///
+    /// Returns [`None`] if the value is not exact or the ratio between the
+    /// units is not a simple rational number, like `tsp` to `tbsp` (`1/3`) or
+    /// `g` to `kg` (`1/1000`). If the target unit doesn't use fractions, only
+    /// integers are exact.
+    fn convert_exact(&self, value: &Value, from: &Unit, to: &Unit) -> Option<Value> {
+        if from.physical_quantity != to.physical_quantity
+            || from.difference != 0.0
+            || to.difference != 0.0
+        {
+            return None;
+        }
+        let ratio = Rational::approx(from.ratio / to.ratio, 1e-6, |den| {
+            den <= 16 || matches!(den, 100 | 1_000 | 10_000 | 100_000 | 1_000_000)
+        })?;
+        // fractions are only kept when the unit uses them
+        let fractions = self.fractions_config(to).enabled;
+        let convert = |n: Number| {
+            let r = n.to_rational()?.checked_mul(ratio)?;
+            if fractions || r.den() == 1 {
+                Some(Number::from_rational(r))
+            } else {
+                Some(Number::Regular(r.value()))
+            }
+        };
+        match *value {
+            Value::Number(n) => Some(Value::Number(convert(n)?)),
+            Value::Range { start, end } => Some(Value::Range {
+                start: convert(start)?,
+                end: convert(end)?,
+            }),
+            Value::Text(_) | Value::Indefinite(_) => None,
+        }
+    }
+
     fn convert_f64(&self, value: f64, from: &Unit, to: &Unit) -> f64 {
         if std::ptr::eq(from, to) {
             return value;
@@ -729,6 +1056,17 @@ pub(crate) fn convert_f64(value: f64, from: &Unit, to: &Unit) -> f64 {
     (norm / to.ratio) - to.difference
 }
 
+fn approx_eq(a: &Value, b: &Value) -> bool {
+    let eq = |a: &Number, b: &Number| (a.value() - b.value()).abs() <= 1e-9 * a.value().abs();
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => eq(a, b),
+        (Value::Range { start: s1, end: e1 }, Value::Range { start: s2, end: e2 }) => {
+            eq(s1, s2) && eq(e1, e2)
+        }
+        _ => false,
+    }
+}
+
 /// Error when try to convert an unknown unit
 #[derive(Debug, Error)]
 #[error("Unknown unit: '{0}'")]
@@ -762,6 +1100,7 @@ pub enum ConvertTo<'a> {
     SameSystem,
     Best(System),
     Unit(ConvertUnit<'a>),
+    Profile(&'a ConversionProfile),
 }
 
 #[derive(
@@ -811,6 +1150,12 @@ impl From<System> for ConvertTo<'_> {
     }
 }
 
+impl<'a> From<&'a ConversionProfile> for ConvertTo<'a> {
+    fn from(value: &'a ConversionProfile) -> Self {
+        Self::Profile(value)
+    }
+}
+
 impl<'a> From<&'a Arc<Unit>> for ConvertTo<'a> {
     fn from(value: &'a Arc<Unit>) -> Self {
         Self::Unit(value.into())
@@ -835,7 +1180,9 @@ impl TryFrom<&Value> for ConvertValue {
         let value = match value {
             Value::Number(n) => ConvertValue::Number(n.value()),
             Value::Range { start, end } => ConvertValue::Range(start.value()..=end.value()),
-            Value::Text(t) => return Err(ConvertError::TextValue(t.clone())),
+            Value::Text(t) | Value::Indefinite(t) => {
+                return Err(ConvertError::TextValue(t.clone()))
+            }
         };
         Ok(value)
     }
@@ -890,6 +1237,9 @@ pub enum ConvertError {
 
     #[error(transparent)]
     UnknownUnit(#[from] UnknownUnit),
+
+    #[error("Conversion profile units '{0}' and '{1}' are the same unit with different targets")]
+    ProfileConflict(String, String),
 }
 
 impl Converter {
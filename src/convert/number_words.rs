@@ -0,0 +1,165 @@
+//! Numbers and indefinite amounts written with words
+//!
+//! Used with the [`NUMBER_WORDS`](crate::Extensions::NUMBER_WORDS) extension.
+//! The words are configured with [`NumberWords`](super::units_file::NumberWords).
+
+use super::Converter;
+use crate::quantity::{Number, Rational, Value};
+
+/// Max error to keep a word number as an exact fraction
+const EXACT_MAX_ERR: f64 = 1e-9;
+/// Max denominator to keep a word number as an exact fraction
+const EXACT_MAX_DEN: u64 = 16;
+
+impl Converter {
+    /// Parses a text value written with words
+    ///
+    /// Returns a [`Value::Number`] for numbers like `two`, `a dozen` or
+    /// `half a dozen`, a [`Value::Indefinite`] for amounts like `a pinch` or
+    /// `to taste` and [`None`] if the text is not recognized.
+    pub(crate) fn parse_number_words(&self, text: &str) -> Option<Value> {
+        let words = &self.number_words;
+        let text = text.trim();
+        let lower = text.to_lowercase();
+        let tokens = lower.split_whitespace().collect::<Vec<_>>();
+        if tokens.is_empty() {
+            return None;
+        }
+
+        let is_article = |t: &str| words.articles.iter().any(|a| a == t);
+        let without_articles = tokens
+            .iter()
+            .position(|t| !is_article(t))
+            .map(|start| tokens[start..].join(" "))
+            .unwrap_or_default();
+        if words
+            .indefinite
+            .iter()
+            .any(|i| *i == lower || *i == without_articles)
+        {
+            return Some(Value::Indefinite(text.to_string()));
+        }
+
+        let value = self.number_words_value(&tokens)?;
+
+        let number = Rational::approx(value, EXACT_MAX_ERR, |den| den <= EXACT_MAX_DEN)
+            .map(Number::from_rational)
+            .unwrap_or(Number::Regular(value));
+        Some(Value::Number(number))
+    }
+
+    /// Adds the numbers written with words
+    ///
+    /// Consecutive numbers are added like `twenty two`, but only a multiple
+    /// of ten followed by a digit, and a multiplier multiplies the numbers
+    /// before it, like `half a dozen`. After a multiplier there can be more
+    /// numbers, smaller than it, like `one hundred twenty`. Anything else,
+    /// like repeated words (`one one`), is not a number.
+    fn number_words_value(&self, tokens: &[&str]) -> Option<f64> {
+        let words = &self.number_words;
+        let is_article = |t: &str| words.articles.iter().any(|a| a == t);
+
+        let mut total = 0.0;
+        // numbers since the last multiplier
+        let mut current: Option<f64> = None;
+        // value of the last number word, to check the next one
+        let mut last_number: Option<f64> = None;
+        let mut last_multiplier: Option<f64> = None;
+        let mut article = false;
+        for &token in tokens {
+            if let Some(&n) = words.numbers.get(token) {
+                if article {
+                    // "half a two"
+                    if current.is_some() {
+                        return None;
+                    }
+                    article = false;
+                }
+                current = match (current, last_number) {
+                    (None, _) => Some(n),
+                    (Some(c), Some(last))
+                        if last >= 20.0
+                            && last % 10.0 == 0.0
+                            && n.fract() == 0.0
+                            && (1.0..10.0).contains(&n) =>
+                    {
+                        Some(c + n)
+                    }
+                    _ => return None,
+                };
+                last_number = Some(n);
+            } else if let Some(&m) = words.multipliers.get(token) {
+                let group = current.unwrap_or(1.0);
+                if current.is_none() && !article && last_multiplier.is_some() {
+                    // "dozen dozen"
+                    return None;
+                }
+                if let Some(last) = last_multiplier {
+                    if group * m >= last {
+                        return None;
+                    }
+                }
+                total += group * m;
+                current = None;
+                last_number = None;
+                last_multiplier = Some(m);
+                article = false;
+            } else if is_article(token) && !article {
+                article = true;
+            } else {
+                return None;
+            }
+        }
+        // an article has to be followed by a number or multiplier
+        if article {
+            return None;
+        }
+        if let Some(c) = current {
+            if last_multiplier.is_some_and(|m| c >= m) {
+                return None;
+            }
+            total += c;
+        } else if last_multiplier.is_none() {
+            return None;
+        }
+        Some(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("two" => Some(Value::from(2.0)))]
+    #[test_case("Two" => Some(Value::from(2.0)); "uppercase")]
+    #[test_case("twelve" => Some(Value::from(12.0)))]
+    #[test_case("a dozen" => Some(Value::from(12.0)))]
+    #[test_case("two dozen" => Some(Value::from(24.0)))]
+    #[test_case("half a dozen" => Some(Value::from(6.0)))]
+    #[test_case("half" => Some(Value::Number(Number::Fraction { whole: 0, num: 1, den: 2, err: 0.0 })))]
+    #[test_case("a pinch" => Some(Value::Indefinite("a pinch".into())))]
+    #[test_case("pinch" => Some(Value::Indefinite("pinch".into())))]
+    #[test_case("To taste" => Some(Value::Indefinite("To taste".into())); "uppercase indefinite")]
+    #[test_case("a" => None)]
+    #[test_case("a lot of" => None)]
+    #[test_case("two pinches" => None)]
+    #[test_case("twenty two" => Some(Value::from(22.0)))]
+    #[test_case("twenty" => Some(Value::from(20.0)))]
+    #[test_case("a half" => Some(Value::Number(Number::Fraction { whole: 0, num: 1, den: 2, err: 0.0 })); "article before number")]
+    #[test_case("half a two" => None; "article between numbers")]
+    #[test_case("half half" => None)]
+    #[test_case("one one" => None)]
+    #[test_case("twelve one" => None; "not a multiple of ten")]
+    #[test_case("twenty half" => None; "fraction after ten")]
+    #[test_case("two twenty" => None; "bigger after smaller")]
+    #[test_case("dozen" => Some(Value::from(12.0)); "multiplier alone")]
+    #[test_case("two dozen two" => Some(Value::from(26.0)))]
+    #[test_case("a dozen a dozen" => None; "repeated multiplier")]
+    #[test_case("dozen dozen" => None; "consecutive multipliers")]
+    #[test_case("two a" => None; "trailing article")]
+    #[test_case("a a dozen" => None; "repeated article")]
+    fn parse(text: &str) -> Option<Value> {
+        Converter::bundled().parse_number_words(text)
+    }
+}
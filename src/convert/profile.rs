@@ -0,0 +1,186 @@
+//! User preferences for conversions
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::{ConvertError, ConvertTo, ConvertUnit, Converter, PhysicalQuantity, System, Unit};
+
+/// User unit preferences to convert a recipe
+///
+/// This is an alternative to convert everything to a [`System`]. Each
+/// [`PhysicalQuantity`] can have its own target, and specific units can
+/// override it. For example, "mass always in grams, volume in metric except
+/// spoons, temperatures in °F":
+///
+/// ```toml
+/// [quantity]
+/// mass = "g"
+/// volume = "metric"
+/// temperature = "F"
+///
+/// [unit]
+/// tsp = "keep"
+/// tbsp = "keep"
+/// ```
+///
+/// The target for a unit is searched in order:
+/// 1. [`unit`](Self::unit) overrides
+/// 2. [`quantity`](Self::quantity) targets
+/// 3. The default [`system`](Self::system)
+/// 4. If none is found, the unit is fitted in the same system
+///
+/// This structure is designed for deserializing [TOML](https://toml.io/en/),
+/// but you can try other formats supported by serde.
+///
+/// Use it with [`ScaledRecipe::convert`](crate::ScaledRecipe::convert),
+/// [`ScaledQuantity::convert`](crate::quantity::ScaledQuantity::convert) or
+/// [`GroupedQuantity::fit_with_profile`](crate::quantity::GroupedQuantity::fit_with_profile).
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConversionProfile {
+    /// Default system for the quantities without a target
+    pub system: Option<System>,
+    /// Target for each [`PhysicalQuantity`]
+    pub quantity: HashMap<PhysicalQuantity, ProfileTarget>,
+    /// Target for specific units. The keys are any unit name, symbol, or alias.
+    ///
+    /// Keys of the same unit can't have different targets, it's a
+    /// [`ConvertError::ProfileConflict`] when converting that unit.
+    pub unit: HashMap<String, ProfileTarget>,
+}
+
+/// Target of a [`ConversionProfile`] rule
+///
+/// When deserializing, it's a single string:
+/// - `"keep"` to leave the quantity as is.
+/// - `"metric"` or `"imperial"` to convert to the best unit of that system.
+/// - Any other string is a unit name, symbol or alias.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "String")]
+pub enum ProfileTarget {
+    /// Don't convert the quantity
+    Keep,
+    /// Convert to the best unit in a system
+    System(System),
+    /// Convert to a specific unit
+    Unit(String),
+}
+
+impl From<String> for ProfileTarget {
+    fn from(value: String) -> Self {
+        if value == "keep" {
+            return Self::Keep;
+        }
+        match value.parse::<System>() {
+            Ok(system) => Self::System(system),
+            Err(_) => Self::Unit(value),
+        }
+    }
+}
+
+impl ConversionProfile {
+    /// Creates a profile that converts everything to a system
+    pub fn new(system: System) -> Self {
+        Self {
+            system: Some(system),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the target of a physical quantity
+    pub fn with_quantity(mut self, quantity: PhysicalQuantity, target: ProfileTarget) -> Self {
+        self.quantity.insert(quantity, target);
+        self
+    }
+
+    /// Sets the target of a unit
+    pub fn with_unit(mut self, unit: impl Into<String>, target: ProfileTarget) -> Self {
+        self.unit.insert(unit.into(), target);
+        self
+    }
+
+    /// Get the conversion target for a unit
+    ///
+    /// Returns [`None`] if the unit has to be kept as is.
+    pub(crate) fn target<'a>(
+        &'a self,
+        unit: &Unit,
+        converter: &Converter,
+    ) -> Result<Option<ConvertTo<'a>>, ConvertError> {
+        let mut overrides = self
+            .unit
+            .iter()
+            .filter(|(key, _)| converter.find_unit(key).is_some_and(|u| u.as_ref() == unit))
+            .collect::<Vec<_>>();
+        // sorted so the error is always the same
+        overrides.sort_unstable_by_key(|(key, _)| key.as_str());
+
+        let target = match overrides.as_slice() {
+            [] => self.quantity.get(&unit.physical_quantity),
+            [(first_key, first), rest @ ..] => {
+                if let Some((key, _)) = rest.iter().find(|(_, target)| target != first) {
+                    return Err(ConvertError::ProfileConflict(
+                        first_key.to_string(),
+                        key.to_string(),
+                    ));
+                }
+                Some(*first)
+            }
+        };
+
+        let to = match target {
+            Some(ProfileTarget::Keep) => return Ok(None),
+            Some(ProfileTarget::System(system)) => ConvertTo::Best(*system),
+            Some(ProfileTarget::Unit(key)) => ConvertTo::Unit(ConvertUnit::Key(key)),
+            None => match self.system {
+                Some(system) => ConvertTo::Best(system),
+                None => ConvertTo::SameSystem,
+            },
+        };
+        Ok(Some(to))
+    }
+}
+
+impl From<System> for ConversionProfile {
+    fn from(value: System) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("keep" => ProfileTarget::Keep)]
+    #[test_case("metric" => ProfileTarget::System(System::Metric))]
+    #[test_case("imperial" => ProfileTarget::System(System::Imperial))]
+    #[test_case("g" => ProfileTarget::Unit("g".into()))]
+    fn target_from_string(s: &str) -> ProfileTarget {
+        ProfileTarget::from(s.to_string())
+    }
+
+    #[test]
+    fn deserialize() {
+        let profile: ConversionProfile = toml::from_str(
+            r#"
+            system = "metric"
+            [quantity]
+            mass = "g"
+            temperature = "F"
+            [unit]
+            tsp = "keep"
+            "#,
+        )
+        .unwrap();
+        let expected = ConversionProfile::new(System::Metric)
+            .with_quantity(PhysicalQuantity::Mass, ProfileTarget::Unit("g".into()))
+            .with_quantity(
+                PhysicalQuantity::Temperature,
+                ProfileTarget::Unit("F".into()),
+            )
+            .with_unit("tsp", ProfileTarget::Keep);
+        assert_eq!(profile, expected);
+    }
+}
@@ -0,0 +1,219 @@
+//! Rounding of values after scaling and converting
+//!
+//! Configured with [`Rounding`](units_file::Rounding) in the units file.
+
+use std::collections::HashMap;
+
+use super::{
+    units_file::{self, RoundingConfig, RoundingDirection},
+    Converter, PhysicalQuantity, System, Unit, UnitIndex, UnknownUnit,
+};
+use crate::quantity::{Number, ScaledQuantity, UnitInfo, Value};
+
+/// Tolerance for float errors when rounding up or down
+const EPSILON: f64 = 1e-9;
+/// Decimals kept after rounding, to remove float errors like `0.30000000000000004`
+const CLEAN_DECIMALS: i32 = 9;
+
+/// Rounding layers of a [`Converter`] with the unit keys resolved
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Rounding {
+    all: Option<RoundingConfig>,
+    metric: Option<RoundingConfig>,
+    imperial: Option<RoundingConfig>,
+    unitless: Option<RoundingConfig>,
+    quantity: HashMap<PhysicalQuantity, RoundingConfig>,
+    unit: HashMap<usize, RoundingConfig>,
+}
+
+impl Rounding {
+    /// Joins the layers of all the units files. Later layers replace the
+    /// configuration of the same key.
+    pub(crate) fn new(
+        layers: &[units_file::Rounding],
+        unit_index: &UnitIndex,
+    ) -> Result<Self, UnknownUnit> {
+        let mut rounding = Self::default();
+        for layer in layers {
+            rounding.all = layer.all.or(rounding.all);
+            rounding.metric = layer.metric.or(rounding.metric);
+            rounding.imperial = layer.imperial.or(rounding.imperial);
+            rounding.unitless = layer.unitless.or(rounding.unitless);
+            rounding.quantity.extend(&layer.quantity);
+            for (key, cfg) in &layer.unit {
+                let id = unit_index.get_unit_id(key)?;
+                rounding.unit.insert(id, *cfg);
+            }
+        }
+        Ok(rounding)
+    }
+
+    /// Back to a units file configuration
+    pub(crate) fn export(&self, converter: &Converter) -> units_file::Rounding {
+        units_file::Rounding {
+            all: self.all,
+            metric: self.metric,
+            imperial: self.imperial,
+            unitless: self.unitless,
+            quantity: self.quantity.clone(),
+            unit: self
+                .unit
+                .iter()
+                .map(|(&id, &cfg)| (converter.all_units[id].symbol().to_string(), cfg))
+                .collect(),
+        }
+    }
+}
+
+impl Converter {
+    /// Gets the rounding configuration for a unit, or for values without unit
+    ///
+    /// All the layers that apply are merged. Returns [`None`] if none is
+    /// configured.
+    pub fn rounding_config(&self, unit: Option<&Unit>) -> Option<RoundingConfig> {
+        let r = &self.rounding;
+        let layers = match unit {
+            Some(unit) => {
+                let id = self.unit_index.get_unit_id(unit.symbol()).ok();
+                let system = unit.system.and_then(|s| match s {
+                    System::Metric => r.metric.as_ref(),
+                    System::Imperial => r.imperial.as_ref(),
+                });
+                [
+                    id.and_then(|id| r.unit.get(&id)),
+                    r.quantity.get(&unit.physical_quantity),
+                    system,
+                    r.all.as_ref(),
+                ]
+            }
+            None => [r.unitless.as_ref(), r.all.as_ref(), None, None],
+        };
+        layers
+            .into_iter()
+            .flatten()
+            .copied()
+            .reduce(RoundingConfig::merge)
+    }
+}
+
+impl RoundingConfig {
+    /// Rounds a value with this configuration
+    ///
+    /// ```
+    /// # use cooklang::convert::units_file::{RoundingConfig, RoundingDirection};
+    /// let cfg = RoundingConfig {
+    ///     significant_figures: Some(3),
+    ///     step: Some(5.0),
+    ///     direction: None,
+    /// };
+    /// assert_eq!(cfg.round(371.428), 370.0);
+    /// let cfg = RoundingConfig {
+    ///     step: Some(1.0),
+    ///     direction: Some(RoundingDirection::Up),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(cfg.round(3.75), 4.0);
+    /// ```
+    pub fn round(&self, value: f64) -> f64 {
+        if !value.is_finite() {
+            return value;
+        }
+        let direction = self.direction.unwrap_or_default();
+        let round = |x: f64| match direction {
+            RoundingDirection::Nearest => x.round(),
+            RoundingDirection::Up => (x - EPSILON).ceil(),
+            RoundingDirection::Down => (x + EPSILON).floor(),
+        };
+
+        let mut value = value;
+        if let Some(figures) = self.significant_figures.filter(|&f| f > 0) {
+            if value != 0.0 {
+                let magnitude = value.abs().log10().floor() as i32;
+                let exp = i32::from(figures) - 1 - magnitude;
+                value = if exp >= 0 {
+                    let p = 10f64.powi(exp);
+                    round(value * p) / p
+                } else {
+                    let p = 10f64.powi(-exp);
+                    round(value / p) * p
+                };
+            }
+        }
+        if let Some(step) = self.step.filter(|&s| s > 0.0) {
+            value = round(value / step) * step;
+        }
+
+        let p = 10f64.powi(CLEAN_DECIMALS);
+        (value * p).round() / p
+    }
+}
+
+impl ScaledQuantity {
+    /// Rounds the value with the converter rounding configuration
+    ///
+    /// Only regular numbers are rounded, fractions are kept as they are.
+    /// Unknown units only use the configuration for `all` the units.
+    ///
+    /// Returns `true` if the value changed.
+    pub fn round(&mut self, converter: &Converter) -> bool {
+        let config = match self.unit().map(|u| u.unit_info_or_parse(converter)) {
+            Some(UnitInfo::Known(unit)) => converter.rounding_config(Some(&unit)),
+            Some(UnitInfo::Unknown) => converter.rounding.all,
+            None => converter.rounding_config(None),
+        };
+        let Some(config) = config else {
+            return false;
+        };
+
+        let round = |n: &mut Number| match n {
+            Number::Regular(v) => {
+                let rounded = config.round(*v);
+                let changed = rounded != *v;
+                *v = rounded;
+                changed
+            }
+            Number::Fraction { .. } => false,
+        };
+        match &mut self.value {
+            Value::Number(n) => round(n),
+            Value::Range { start, end } => {
+                let start = round(start);
+                let end = round(end);
+                start || end
+            }
+            Value::Text(_) | Value::Indefinite(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Some(3), None, None, 371.428 => 371.0)]
+    #[test_case(Some(2), None, None, 371.428 => 370.0)]
+    #[test_case(Some(2), None, None, 0.01234 => 0.012)]
+    #[test_case(Some(1), None, Some(RoundingDirection::Up), 0.31 => 0.4)]
+    #[test_case(None, Some(5.0), None, 372.6 => 375.0)]
+    #[test_case(None, Some(0.25), None, 1.8 => 1.75)]
+    #[test_case(None, Some(0.1), None, 0.31 => 0.3)]
+    #[test_case(None, Some(1.0), Some(RoundingDirection::Up), 3.75 => 4.0)]
+    #[test_case(None, Some(1.0), Some(RoundingDirection::Up), 3.0000000000000004 => 3.0; "float error up")]
+    #[test_case(None, Some(1.0), Some(RoundingDirection::Down), 3.75 => 3.0)]
+    #[test_case(Some(3), Some(5.0), None, 1234.5 => 1230.0)]
+    #[test_case(Some(2), None, None, 0.0 => 0.0)]
+    fn round(
+        significant_figures: Option<u8>,
+        step: Option<f64>,
+        direction: Option<RoundingDirection>,
+        value: f64,
+    ) -> f64 {
+        RoundingConfig {
+            significant_figures,
+            step,
+            direction,
+        }
+        .round(value)
+    }
+}
@@ -2,7 +2,7 @@
 
 use enum_map::EnumMap;
 use once_cell::sync::Lazy;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use super::{FractionsConfig, PhysicalQuantity, System};
@@ -10,8 +10,10 @@ use super::{FractionsConfig, PhysicalQuantity, System};
 /// Configuration struct for units used in [`ConverterBuilder`](super::ConverterBuilder)
 ///
 /// This structure is designed for deserializing [TOML](https://toml.io/en/),
-/// but you can try other formats supported by serde.
-#[derive(Debug, Deserialize, Clone)]
+/// but you can try other formats supported by serde. It can also be serialized,
+/// and [`Converter::to_units_file`](super::Converter::to_units_file) exports a
+/// converter to one.
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct UnitsFile {
     /// Set the default system
@@ -30,6 +32,13 @@ pub struct UnitsFile {
     ///
     /// If enabled, a decimal value will be converted to a fraction if possible.
     pub fractions: Option<Fractions>,
+    /// Rounding of values after scaling and converting
+    pub rounding: Option<Rounding>,
+    /// Words used as numbers and indefinite amounts
+    ///
+    /// Used with the [`NUMBER_WORDS`](crate::Extensions::NUMBER_WORDS)
+    /// extension. The words of all layers are merged.
+    pub number_words: Option<NumberWords>,
     /// Extend and/or edit units from other layers before
     pub extend: Option<Extend>,
     /// Declare new units
@@ -37,10 +46,42 @@ pub struct UnitsFile {
     pub quantity: Vec<QuantityGroup>,
 }
 
+/// Words used as numbers in [`UnitsFile`]
+///
+/// All the words are case insensitive.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NumberWords {
+    /// Words that are a number, like `two` or `half`
+    pub numbers: HashMap<String, f64>,
+    /// Words that multiply the number before them, like `dozen` in
+    /// `two dozen`
+    pub multipliers: HashMap<String, f64>,
+    /// Words ignored before a multiplier or an indefinite amount, like `a` in
+    /// `half a dozen` or `a pinch`. Alone, they are `1`, like in `a dozen`.
+    pub articles: Vec<String>,
+    /// Amounts without a number, like `pinch` or `to taste`
+    pub indefinite: Vec<String>,
+}
+
+impl NumberWords {
+    /// Merges another layer into this one, overwriting the repeated words
+    pub(crate) fn merge(&mut self, other: NumberWords) {
+        let lower = |(k, v): (String, f64)| (k.to_lowercase(), v);
+        self.numbers.extend(other.numbers.into_iter().map(lower));
+        self.multipliers
+            .extend(other.multipliers.into_iter().map(lower));
+        self.articles
+            .extend(other.articles.iter().map(|w| w.to_lowercase()));
+        self.indefinite
+            .extend(other.indefinite.iter().map(|w| w.to_lowercase()));
+    }
+}
+
 /// [SI] configuration used in [`UnitsFile`]
 ///
 /// [SI]: https://en.wikipedia.org/wiki/International_System_of_Units
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct SI {
     /// Prefixes for the names of the units when expanding
@@ -61,7 +102,9 @@ pub struct SI {
 /// [SI] supported prefixes
 ///
 /// [SI]: https://en.wikipedia.org/wiki/International_System_of_Units
-#[derive(Debug, Deserialize, Clone, Copy, strum::Display, strum::AsRefStr, enum_map::Enum)]
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, strum::Display, strum::AsRefStr, enum_map::Enum,
+)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "camelCase")]
 pub enum SIPrefix {
@@ -99,7 +142,7 @@ impl SIPrefix {
 /// - `metric` / `imperial`
 /// - `quantity`
 /// - `unit`
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(default, deny_unknown_fields)]
 pub struct Fractions {
     /// The base configuration
@@ -114,7 +157,7 @@ pub struct Fractions {
     pub unit: HashMap<String, FractionsConfigWrapper>,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum FractionsConfigWrapper {
     Toggle(bool),
@@ -134,7 +177,7 @@ impl FractionsConfigWrapper {
 }
 
 /// Fractions configuration layer
-#[derive(Debug, Clone, Copy, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default)]
 #[serde(default, deny_unknown_fields)]
 pub struct FractionsConfigHelper {
     /// If fractions are enabled. Defaults to `false`
@@ -178,10 +221,86 @@ impl FractionsConfigHelper {
     }
 }
 
+/// Configuration for rounding
+///
+/// Values are rounded after scaling a recipe and converting its units, so
+/// `371.428 g` can be `370 g`. Only regular numbers are rounded, fractions
+/// are kept.
+///
+/// A unit can have more than one layer, which are merged in the order:
+/// - `all`
+/// - `metric` / `imperial`
+/// - `quantity`
+/// - `unit`
+///
+/// Values without a unit use `all` and `unitless`.
+///
+/// The rounding only depends on the unit, not the ingredient, so `3` eggs and
+/// `3` lemons are rounded the same with `unitless`. To always round up the
+/// eggs, give them a scaling law in the recipe, like `@eggs{3*ceil}`.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rounding {
+    /// The base configuration
+    pub all: Option<RoundingConfig>,
+    /// For metric units
+    pub metric: Option<RoundingConfig>,
+    /// For imperial units
+    pub imperial: Option<RoundingConfig>,
+    /// For values without a unit, like `3` eggs. It applies to all of them,
+    /// there is no per ingredient configuration.
+    pub unitless: Option<RoundingConfig>,
+    /// For each [`PhysicalQuantity`]
+    pub quantity: HashMap<PhysicalQuantity, RoundingConfig>,
+    /// For specific units. The keys are any unit name, symbol, or alias.
+    pub unit: HashMap<String, RoundingConfig>,
+}
+
+/// Rounding configuration layer
+///
+/// When both are set, the value is first rounded to the significant figures
+/// and then to the step.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RoundingConfig {
+    /// Max number of significant figures, like `2` for `370`
+    pub significant_figures: Option<u8>,
+    /// Round to a multiple of this, like `5` for `370` or `0.25` for `1.75`
+    pub step: Option<f64>,
+    /// Direction to round to. Defaults to [`RoundingDirection::Nearest`].
+    pub direction: Option<RoundingDirection>,
+}
+
+/// Direction to round values
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RoundingDirection {
+    /// The closest value
+    #[default]
+    Nearest,
+    /// The closest value that is not lower, like for eggs
+    Up,
+    /// The closest value that is not greater
+    Down,
+}
+
+impl RoundingConfig {
+    /// Merges this layer with another
+    ///
+    /// It keeps the values defined in `self` and falls back to `other`.
+    pub(crate) fn merge(self, other: RoundingConfig) -> Self {
+        Self {
+            significant_figures: self.significant_figures.or(other.significant_figures),
+            step: self.step.or(other.step),
+            direction: self.direction.or(other.direction),
+        }
+    }
+}
+
 /// Extend units from other layers config used in [`UnitsFile`]
 ///
 /// The maps's keys are any name, symbol or alias of the unit you want to extend.
-#[derive(Debug, Default, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct Extend {
     /// Precedence when joining to other layers
@@ -194,7 +313,7 @@ pub struct Extend {
 ///
 /// This is important in, for example, the case of symbols. The first symbol
 /// is the one that will be used for formatting.
-#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum Precedence {
     /// The list will be added before the current ones (*higher priority*)
@@ -210,7 +329,7 @@ pub enum Precedence {
 ///
 /// See [`Unit`](super::Unit). If the unit is automatially generated (expanded) from another
 /// one, only aliases can be set.
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 #[serde(default)]
 pub struct ExtendUnitEntry {
     pub ratio: Option<f64>,
@@ -221,12 +340,26 @@ pub struct ExtendUnitEntry {
     pub symbols: Option<Vec<Arc<str>>>,
     #[serde(alias = "alias")]
     pub aliases: Option<Vec<Arc<str>>>,
+    pub singular: Option<Arc<str>>,
+    pub plural: Option<Arc<str>>,
+    /// Added to the current locales, replacing the repeated ones
+    pub locales: Option<HashMap<String, UnitForms>>,
+}
+
+/// Singular and plural names of a unit
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct UnitForms {
+    /// Name for a single unit, like `1 cup` or `1/2 cup`
+    pub singular: Arc<str>,
+    /// Name for many units, like `2 cups`
+    pub plural: Arc<str>,
 }
 
 /// Configuration of a group of units belonging to a [physical quantity]
 ///
 /// [physical quantity]: https://en.wikipedia.org/wiki/Physical_quantity
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct QuantityGroup {
     /// Quantity of the group
@@ -241,11 +374,94 @@ pub struct QuantityGroup {
     /// **This will always replace the configuration from [`UnitsFile`] before**
     #[serde(default)]
     pub best: Option<BestUnits>,
+    /// Allow displaying quantities as a composite of the best units
+    ///
+    /// For example, `90 min` as `1 h 30 min` or `35 oz` as `2 lb 3 oz`. See
+    /// [`ScaledQuantity::to_composite`](crate::quantity::ScaledQuantity::to_composite).
+    ///
+    /// **This will always replace the configuration from [`UnitsFile`] before**
+    #[serde(default)]
+    pub composite: Option<Composite>,
+    /// How to choose the best unit when fitting a quantity
+    ///
+    /// **This will always replace the configuration from [`UnitsFile`] before**
+    #[serde(default)]
+    pub fit: Option<Fit>,
     /// Definition of units
     #[serde(default)]
     pub units: Option<Units>,
 }
 
+/// Composite display configuration used in [`QuantityGroup`]
+///
+/// Like in [`BestUnits`], it can be the same for every system or set per
+/// system. Not set systems are disabled.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum Composite {
+    /// Same for all the systems
+    Toggle(bool),
+    /// A toggle per system
+    BySystem {
+        #[serde(default)]
+        metric: bool,
+        #[serde(default)]
+        imperial: bool,
+    },
+}
+
+impl Composite {
+    /// Checks if composite quantities are enabled for a system
+    pub fn enabled(self, system: System) -> bool {
+        match self {
+            Composite::Toggle(enabled) => enabled,
+            Composite::BySystem { metric, imperial } => match system {
+                System::Metric => metric,
+                System::Imperial => imperial,
+            },
+        }
+    }
+}
+
+/// Configuration of how to fit quantities to the best units used in
+/// [`QuantityGroup`]
+///
+/// The bundled units use the default for every quantity. To use another
+/// strategy, add a layer like:
+///
+/// ```toml
+/// [[quantity]]
+/// quantity = "volume"
+/// fit = { strategy = "readable", split = true }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Fit {
+    /// How to choose between the best units
+    pub strategy: FitStrategy,
+    /// Allow splitting a quantity in 2 units, like `1/4 cup + 1 tbsp`, when
+    /// that is more readable than any single unit.
+    ///
+    /// This only applies to [`FitStrategy::Readable`] and
+    /// [`ScaledQuantity::fit_split`](crate::quantity::ScaledQuantity::fit_split).
+    pub split: bool,
+}
+
+/// Strategy to choose the best unit for a value
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FitStrategy {
+    /// The biggest unit in which the value is at least 1
+    #[default]
+    Magnitude,
+    /// Score each unit by how readable the value is
+    ///
+    /// Values close to common fractions (with the [`Fractions`]
+    /// configuration) are preferred and tiny decimals or too many whole units
+    /// are avoided. So `0.333 cup` will be `1/3 cup` and `7 tsp`, `2 1/3 tbsp`.
+    Readable,
+}
+
 /// List of best units
 ///
 /// The *best* units are the one elegible for automatic unit convertion to a
@@ -255,7 +471,7 @@ pub struct QuantityGroup {
 /// about the system and the other doesn't. It's the same in [`Units`]. You can
 /// set a unit's system in either, this enum, in [`Units`] or in both (but it
 /// has to match).
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(untagged, deny_unknown_fields)]
 pub enum BestUnits {
     /// List without system information
@@ -273,7 +489,7 @@ pub enum BestUnits {
 /// about the system and the other doesn't. It's the same in [`BestUnits`]. You can
 /// set a unit's system in either, this enum, in [`BestUnits`] or in both (but it
 /// has to match).
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(untagged, deny_unknown_fields)]
 pub enum Units {
     /// List without [`System`] information
@@ -295,14 +511,29 @@ pub enum Units {
 /// [`BestUnits`].
 ///
 /// Conversions will be `val * [Self::ratio] + [Self::difference]`.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct UnitEntry {
     /// Names. For example: `grams`
     ///
     /// This will expand with [`SI`] configuration.
-    #[serde(alias = "name")]
+    #[serde(default, alias = "name")]
     pub names: Vec<Arc<str>>,
+    /// Singular name used to display the unit. For example: `gram`
+    ///
+    /// It's also added to the names. If not given, the first name is used.
+    #[serde(default)]
+    pub singular: Option<Arc<str>>,
+    /// Plural name used to display the unit. For example: `grams`
+    ///
+    /// It's also added to the names. If not given, the singular is used.
+    #[serde(default)]
+    pub plural: Option<Arc<str>>,
+    /// Singular and plural names in other locales, like `es` or `pt-BR`
+    ///
+    /// They are also added to the names.
+    #[serde(default)]
+    pub locales: HashMap<String, UnitForms>,
     /// Symbols. For example: `g`
     ///
     /// This will expand with [`SI`] configuration.
@@ -343,8 +574,48 @@ impl UnitsFile {
     ///
     /// This is only available with the `bundled_units` feature.
     pub fn bundled() -> Self {
-        const TEXT: &str = include_str!("../../units.toml");
-        static FILE: Lazy<UnitsFile> = Lazy::new(|| toml::from_str(TEXT).unwrap());
+        static FILE: Lazy<UnitsFile> = Lazy::new(|| toml::from_str(BUNDLED).unwrap());
         FILE.clone()
     }
+
+    /// Get a bundled localized units layer
+    ///
+    /// The locale is a language tag like `es` or `pt-BR`, only the language
+    /// is used. The layer extends the units of [`Self::bundled`], so it has to
+    /// be added after them. [`ConverterBuilder::add_bundled_locale_units`]
+    /// does that.
+    ///
+    /// Each locale is only available with its feature, like `bundled_units_es`.
+    /// Returns [`None`] if the locale is not available.
+    ///
+    /// [`ConverterBuilder::add_bundled_locale_units`]: super::ConverterBuilder::add_bundled_locale_units
+    pub fn bundled_locale(locale: &str) -> Option<Self> {
+        let language = locale.split(['-', '_']).next()?.to_lowercase();
+        let (_, text) = BUNDLED_LOCALES.iter().find(|(code, _)| *code == language)?;
+        Some(toml::from_str(text).unwrap())
+    }
+
+    /// Locales available with [`Self::bundled_locale`]
+    pub fn bundled_locales() -> impl Iterator<Item = &'static str> {
+        BUNDLED_LOCALES.iter().map(|(code, _)| *code)
+    }
 }
+
+/// Bundled units file source
+#[cfg(feature = "bundled_units")]
+pub(crate) const BUNDLED: &str = include_str!("../../units.toml");
+
+/// Bundled localized layers enabled by features
+#[cfg(feature = "bundled_units")]
+pub(crate) const BUNDLED_LOCALES: &[(&str, &str)] = &[
+    #[cfg(feature = "bundled_units_es")]
+    ("es", include_str!("../../units/spanish.toml")),
+    #[cfg(feature = "bundled_units_de")]
+    ("de", include_str!("../../units/german.toml")),
+    #[cfg(feature = "bundled_units_fr")]
+    ("fr", include_str!("../../units/french.toml")),
+    #[cfg(feature = "bundled_units_it")]
+    ("it", include_str!("../../units/italian.toml")),
+    #[cfg(feature = "bundled_units_ja")]
+    ("ja", include_str!("../../units/japanese.toml")),
+];
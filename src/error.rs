@@ -53,11 +53,11 @@ impl std::fmt::Display for SourceDiag {
 }
 
 impl RichError for SourceDiag {
-    fn labels(&self) -> Cow<[Label]> {
+    fn labels(&self) -> Cow<'_, [Label]> {
         self.labels.as_slice().into()
     }
 
-    fn hints(&self) -> Cow<[CowStr]> {
+    fn hints(&self) -> Cow<'_, [CowStr]> {
         self.hints.as_slice().into()
     }
 
@@ -68,9 +68,8 @@ impl RichError for SourceDiag {
 
 impl std::error::Error for SourceDiag {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        // idk why I can't .as_deref but I can do this
         match &self.source {
-            Some(err) => Some(err),
+            Some(err) => Some(err.as_ref()),
             None => None,
         }
     }
@@ -180,6 +179,10 @@ pub enum Stage {
     Parse,
     /// Analysis stage
     Analysis,
+    /// Scale stage, see [`ScaledRecipe::scale_report`](crate::ScaledRecipe::scale_report)
+    Scale,
+    /// Convert stage, see [`ScaledRecipe::convert`](crate::ScaledRecipe::convert)
+    Convert,
 }
 
 /// Errors and warnings container with fancy formatting
@@ -432,10 +435,10 @@ impl<T> PassResult<T> {
 
 /// Trait to enhace errors with rich metadata
 pub trait RichError: std::error::Error {
-    fn labels(&self) -> Cow<[Label]> {
+    fn labels(&self) -> Cow<'_, [Label]> {
         Cow::Borrowed(&[])
     }
-    fn hints(&self) -> Cow<[CowStr]> {
+    fn hints(&self) -> Cow<'_, [CowStr]> {
         Cow::Borrowed(&[])
     }
     fn severity(&self) -> Severity {
@@ -0,0 +1,260 @@
+//! Locale aware display of quantities and steps
+//!
+//! The [`Display`](std::fmt::Display) implementations of [`Number`],
+//! [`Quantity`](crate::Quantity) and others always use a `.` as decimal
+//! separator, up to 3 decimals and the unit as written in the recipe. A
+//! [`Formatter`] allows to change all of that.
+//!
+//! ```
+//! # use cooklang::{format::{Formatter, UnitStyle}, Converter, Quantity, Value};
+//! let converter = Converter::bundled();
+//! let formatter = Formatter::for_locale("es-ES").with_unit_style(UnitStyle::Name);
+//! let q = Quantity::new(Value::from(1.5), Some("kg".into()));
+//! assert_eq!(formatter.quantity(&q, &converter), "1,5 kilograms");
+//! ```
+
+use crate::{
+    convert::Converter,
+    model::{Item, ScaledRecipe, Step},
+    quantity::{GroupedQuantity, Number, ScaledQuantity, UnitInfo, Value},
+};
+
+/// Languages that use a comma as decimal separator
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "it", "lt", "lv",
+    "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr", "uk", "vi",
+];
+
+/// How to display units
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnitStyle {
+    /// As written in the recipe
+    #[default]
+    Original,
+    /// The symbol of the unit, like `tbsp`
+    Symbol,
+    /// The full name of the unit, like `tablespoons`
+    ///
+    /// The singular or plural form is selected with
+    /// [`Unit::name_for`](crate::convert::Unit::name_for) and the formatter
+    /// locale.
+    Name,
+}
+
+/// Formatter for numbers, quantities and steps
+///
+/// The default formatter is the same as the [`Display`](std::fmt::Display)
+/// implementations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formatter {
+    /// Locale this formatter was created for, like `es` or `de-AT`
+    ///
+    /// It's used to select the unit names when a unit has forms for it.
+    pub locale: Option<String>,
+    /// Separator between the integer and decimal part of a number
+    pub decimal_separator: char,
+    /// Max number of decimals of regular numbers
+    pub max_decimals: u8,
+    /// Display fractions with unicode characters, like `1½`
+    pub fraction_glyphs: bool,
+    /// How to display units
+    pub unit_style: UnitStyle,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self {
+            locale: None,
+            decimal_separator: '.',
+            max_decimals: 3,
+            fraction_glyphs: false,
+            unit_style: UnitStyle::Original,
+        }
+    }
+}
+
+impl Formatter {
+    /// Creates a formatter with the defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a formatter with the conventions of a locale
+    ///
+    /// The locale is a language tag like `es`, `de-AT` or `pt_BR`. Only the
+    /// language is used to select the decimal separator.
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let decimal_separator = if DECIMAL_COMMA_LANGUAGES.contains(&language.as_str()) {
+            ','
+        } else {
+            '.'
+        };
+        Self {
+            locale: Some(locale.to_string()),
+            decimal_separator,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the max number of decimals
+    pub fn with_max_decimals(mut self, max_decimals: u8) -> Self {
+        self.max_decimals = max_decimals;
+        self
+    }
+
+    /// Sets if fractions use unicode characters
+    pub fn with_fraction_glyphs(mut self, fraction_glyphs: bool) -> Self {
+        self.fraction_glyphs = fraction_glyphs;
+        self
+    }
+
+    /// Sets how to display units
+    pub fn with_unit_style(mut self, unit_style: UnitStyle) -> Self {
+        self.unit_style = unit_style;
+        self
+    }
+
+    /// Formats a number
+    pub fn number(&self, number: Number) -> String {
+        match number {
+            Number::Regular(n) => {
+                let p = 10f64.powi(self.max_decimals.into());
+                let s = ((n * p).round() / p).to_string();
+                if self.decimal_separator == '.' {
+                    s
+                } else {
+                    s.replace('.', self.decimal_separator.encode_utf8(&mut [0; 4]))
+                }
+            }
+            Number::Fraction { .. } if self.fraction_glyphs => number.unicode().to_string(),
+            Number::Fraction { .. } => number.to_string(),
+        }
+    }
+
+    /// Formats a value
+    pub fn value(&self, value: &Value) -> String {
+        match value {
+            Value::Number(n) => self.number(*n),
+            Value::Range { start, end } => {
+                format!("{}-{}", self.number(*start), self.number(*end))
+            }
+            Value::Text(t) | Value::Indefinite(t) => t.clone(),
+        }
+    }
+
+    /// Formats a quantity
+    ///
+    /// The converter is used to find the unit symbol or names. If the unit is
+    /// unknown, it's displayed as written.
+    pub fn quantity(&self, quantity: &ScaledQuantity, converter: &Converter) -> String {
+        let mut s = self.value(&quantity.value);
+        if let Some(unit) = quantity.unit() {
+            let text = match (self.unit_style, unit.unit_info_or_parse(converter)) {
+                (UnitStyle::Original, _) | (_, UnitInfo::Unknown) => unit.text(),
+                (UnitStyle::Symbol, UnitInfo::Known(unit)) => {
+                    return format!("{s} {}", unit.symbol())
+                }
+                (UnitStyle::Name, UnitInfo::Known(unit)) => {
+                    let name = unit.name_for(&quantity.value, self.locale.as_deref());
+                    return format!("{s} {name}");
+                }
+            };
+            s.push(' ');
+            s.push_str(text);
+        }
+        s
+    }
+
+    /// Formats a group of quantities as a comma separated list
+    pub fn grouped_quantity(&self, quantity: &GroupedQuantity, converter: &Converter) -> String {
+        quantity
+            .iter()
+            .map(|q| self.quantity(q, converter))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Formats a step as text
+    ///
+    /// Ingredients and cookware are displayed with their name. Timers and
+    /// inline quantities are displayed with their quantity.
+    pub fn step(&self, step: &Step, recipe: &ScaledRecipe, converter: &Converter) -> String {
+        let mut s = String::new();
+        for item in &step.items {
+            match item {
+                Item::Text { value } => s += value,
+                Item::Ingredient { index } => s += &recipe.ingredients[*index].display_name(),
+                Item::Cookware { index } => s += recipe.cookware[*index].display_name(),
+                Item::Timer { index } => {
+                    let timer = &recipe.timers[*index];
+                    match (&timer.quantity, &timer.name) {
+                        (Some(q), _) => s += &self.quantity(q, converter),
+                        (None, Some(name)) => s += name,
+                        (None, None) => {}
+                    }
+                }
+                Item::InlineQuantity { index } => {
+                    s += &self.quantity(&recipe.inline_quantities[*index], converter)
+                }
+            }
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("en", Number::Regular(1.5) => "1.5")]
+    #[test_case("es", Number::Regular(1.5) => "1,5")]
+    #[test_case("de-AT", Number::Regular(1.5) => "1,5")]
+    #[test_case("pt_BR", Number::Regular(0.25) => "0,25")]
+    #[test_case("de", Number::Regular(2.0) => "2")]
+    #[test_case("es", Number::Regular(1.23456) => "1,235")]
+    #[test_case("es", Number::Fraction { whole: 1, num: 1, den: 2, err: 0.0 } => "1 1/2")]
+    fn locale_number(locale: &str, number: Number) -> String {
+        Formatter::for_locale(locale).number(number)
+    }
+
+    #[test_case(0, Number::Regular(1.5) => "2")]
+    #[test_case(1, Number::Regular(1.25) => "1.3")]
+    #[test_case(3, Number::Regular(0.3333) => "0.333")]
+    fn max_decimals(max_decimals: u8, number: Number) -> String {
+        Formatter::new()
+            .with_max_decimals(max_decimals)
+            .number(number)
+    }
+
+    #[test]
+    fn fraction_glyphs() {
+        let f = Formatter::new().with_fraction_glyphs(true);
+        let n = Number::Fraction {
+            whole: 1,
+            num: 1,
+            den: 2,
+            err: 0.0,
+        };
+        assert_eq!(f.number(n), "1½");
+        assert_eq!(f.number(Number::Regular(0.5)), "0.5");
+    }
+
+    #[test_case(UnitStyle::Original, 2.0, "tablespoon" => "2 tablespoon")]
+    #[test_case(UnitStyle::Symbol, 2.0, "tablespoon" => "2 tbsp")]
+    #[test_case(UnitStyle::Name, 2.0, "tbsp" => "2 tablespoons")]
+    #[test_case(UnitStyle::Name, 1.0, "tbsp" => "1 tablespoon")]
+    #[test_case(UnitStyle::Name, 2.0, "bag" => "2 bag"; "unknown unit")]
+    fn unit_style(style: UnitStyle, value: f64, unit: &str) -> String {
+        let converter = Converter::bundled();
+        let q = ScaledQuantity::new(Value::from(value), Some(unit.to_string()));
+        Formatter::new()
+            .with_unit_style(style)
+            .quantity(&q, &converter)
+    }
+}
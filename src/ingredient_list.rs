@@ -112,7 +112,7 @@ impl ScaledRecipe {
     /// references grouped.
     ///
     /// Order is the recipe order.
-    pub fn group_cookware(&self) -> Vec<GroupedCookware> {
+    pub fn group_cookware(&self) -> Vec<GroupedCookware<'_>> {
         let mut list = Vec::new();
         for (index, cookware) in self.cookware.iter().enumerate() {
             if !cookware.relation.is_definition() {
@@ -56,22 +56,37 @@ pub mod _extensions {
 
 #[cfg(doc)]
 pub mod _features {
-    //! This lib has 2 features, both enabled by default:
+    //! This lib has 3 features, all enabled by default:
     //! - `bundled_units`. Includes a units file with the most common units for
     //!   recipes in English. These units are available to load when you want
     //!   without the need to read a file. The default
     //!   [`Converter`](crate::convert::Converter) use them if this feature is
     //!   enabled. [This is the bundled file](https://github.com/cooklang/cooklang-rs/blob/main/units.toml)
     //!
+    //! - `units_loader`. Enables the
+    //!   [`UnitsLoader`](crate::convert::UnitsLoader), that loads units files
+    //!   in TOML reporting errors with their location.
+    //!
     //! - `aisle`. Enables the [`aisle`](crate::aisle) module.
+    //!
+    //! And some optional features to bundle localized layers over the bundled
+    //! units. Load them with
+    //! [`ConverterBuilder::with_bundled_locale_units`](crate::convert::ConverterBuilder::with_bundled_locale_units).
+    //! - `bundled_units_es`. Spanish.
+    //! - `bundled_units_de`. German.
+    //! - `bundled_units_fr`. French.
+    //! - `bundled_units_it`. Italian.
+    //! - `bundled_units_ja`. Japanese.
 }
 
 #[cfg(feature = "aisle")]
 pub mod aisle;
 pub mod analysis;
 pub mod ast;
+pub mod bakers_percentages;
 pub mod convert;
 pub mod error;
+pub mod format;
 pub mod ingredient_list;
 pub mod located;
 pub mod metadata;
@@ -131,6 +146,29 @@ bitflags! {
         const INTERMEDIATE_PREPARATIONS = 1 << 11 | Self::COMPONENT_MODIFIERS.bits();
         /// Enables special metadata key parsing
         const SPECIAL_METADATA = 1 << 12;
+        /// Quantities with many values and units of the same physical quantity
+        /// like `@meat{1 lb 4 oz}` or `~{1 h 30 min}`
+        const COMPOSITE_QUANTITIES = 1 << 13;
+        /// Numbers with unicode fractions like `@igr{½}` or `@igr{1½}` and
+        /// decimal commas like `@igr{1,5}`
+        const EXTENDED_NUMBERS = 1 << 14;
+        /// Numbers written with words like `@eggs{two}` or `@eggs{half a dozen}`
+        /// and indefinite amounts like `@salt{a pinch}`. The words are
+        /// configured in the [`Converter`].
+        const NUMBER_WORDS = 1 << 15;
+        /// Timers written as ISO 8601 durations like `~{PT1H30M}` or as clock
+        /// time like `~{1:30:00}`
+        const DURATIONS = 1 << 16;
+        /// Scaling laws after the auto scale marker like `~{45*sqrt%min}` or
+        /// `#pan{1*ceil}`, and defaults for timers and cookware with
+        /// [`Self::MODES`]
+        const SCALING_LAWS = 1 << 17;
+        /// Cookware quantities with a length unit are pan sizes, like
+        /// `#cake pan{20%cm}` or `#baking pan{23x33%cm}`
+        const PAN_SIZES = 1 << 18;
+        /// Quantities in the text of a step between `{}`, like
+        /// `divide into {4*%portions}`. They can be scaled like ingredients.
+        const INLINE_QUANTITIES = 1 << 19;
 
         /// Enables a subset of extensions to maximize compatibility with other
         /// cooklang parsers.
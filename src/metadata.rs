@@ -20,8 +20,12 @@ macro_rules! regex {
 pub(crate) use regex;
 
 use crate::{
-    convert::{ConvertError, ConvertTo, ConvertUnit, ConvertValue, PhysicalQuantity, UnknownUnit},
-    Converter,
+    convert::{
+        ConvertError, ConvertTo, ConvertUnit, ConvertValue, Duration, PhysicalQuantity, UnknownUnit,
+    },
+    parser::parse_number_and_unit,
+    quantity::{Number, ScaledQuantity, Value},
+    Converter, Extensions, Quantity,
 };
 
 /// Metadata of a recipe
@@ -33,6 +37,9 @@ use crate::{
 pub struct Metadata {
     /// All the raw key/value pairs from the recipe
     pub map: serde_yaml::Mapping,
+    /// The `yield` key, parsed with the extensions of the parser
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) recipe_yield: Option<ScaledQuantity>,
 }
 
 #[derive(
@@ -63,6 +70,7 @@ pub(crate) enum StdKey {
     #[strum(serialize = "cook_time", to_string = "cook time")]
     CookTime,
     Servings,
+    Yield,
 }
 
 impl Metadata {
@@ -161,6 +169,14 @@ impl Metadata {
         self.get(StdKey::Servings.as_ref())
             .and_then(|v| v.as_servings())
     }
+
+    /// What the recipe makes, like `2 loaves` or `1 L`
+    ///
+    /// The `yield` key [`as_yield`](CooklangValueExt::as_yield), parsed once
+    /// when the recipe is parsed, with the extensions of the parser.
+    pub fn recipe_yield(&self) -> Option<&ScaledQuantity> {
+        self.recipe_yield.as_ref()
+    }
 }
 
 pub trait CooklangValueExt: private::Sealed {
@@ -194,6 +210,8 @@ pub trait CooklangValueExt: private::Sealed {
     /// - `30` 30 minutes
     /// - `1h` 60 minutes
     /// - `1h 30min` 90 minutes
+    /// - `PT1H30M` 90 minutes, an ISO 8601 duration
+    /// - `1:30` 90 minutes, or `1:30:00` with seconds
     fn as_minutes(&self, converter: &Converter) -> Option<u32>;
 
     /// Get a [`RecipeTime`]
@@ -202,6 +220,22 @@ pub trait CooklangValueExt: private::Sealed {
     /// of `prep_time` and `cook_time` where each of them is a number or string.
     fn as_time(&self, converter: &Converter) -> Option<RecipeTime>;
 
+    /// Get a quantity with a value and an optional unit
+    ///
+    /// It can be a number or a string with a number and a unit. The number
+    /// is parsed like in a quantity with the given extensions, so it can be a
+    /// decimal, a fraction, a range or, with the extensions, written with
+    /// words.
+    ///
+    /// Examples:
+    /// - `12`
+    /// - `2 loaves`
+    /// - `1.5 L`
+    /// - `1/2 cup`
+    /// - `½ loaf`
+    /// - `two loaves`
+    fn as_yield(&self, extensions: Extensions, converter: &Converter) -> Option<ScaledQuantity>;
+
     /// Like [`serde_yaml::Value::as_u64`] but ensuring the value fits in a u32
     fn as_u32(&self) -> Option<u32>;
 }
@@ -245,6 +279,10 @@ impl CooklangValueExt for serde_yaml::Value {
         value_as_time(self, converter).ok()
     }
 
+    fn as_yield(&self, extensions: Extensions, converter: &Converter) -> Option<ScaledQuantity> {
+        value_as_yield(self, extensions, converter).ok()
+    }
+
     #[inline]
     fn as_u32(&self) -> Option<u32> {
         self.as_u64()?.try_into().ok()
@@ -269,7 +307,7 @@ fn value_as_tags(val: &serde_yaml::Value) -> Result<Vec<&str>, MetadataError> {
     let entries = if let Some(s) = val.as_str() {
         s.split(',').map(|e| e.trim()).collect()
     } else if let Some(seq) = val.as_sequence() {
-        seq.into_iter()
+        seq.iter()
             .map(|val| val.as_str())
             .collect::<Option<Vec<&str>>>()
             .ok_or(MetadataError::UnexpectedType)?
@@ -316,6 +354,59 @@ fn value_as_servings(val: &serde_yaml::Value) -> Result<Vec<u32>, MetadataError>
     Ok(servings)
 }
 
+fn value_as_yield(
+    val: &serde_yaml::Value,
+    extensions: Extensions,
+    converter: &Converter,
+) -> Result<ScaledQuantity, MetadataError> {
+    if let Some(n) = val.as_f64() {
+        return Ok(Quantity::new(Value::from(n), None));
+    }
+    let s = val.as_str().ok_or(MetadataError::UnexpectedType)?.trim();
+    let (value, unit) = parse_number_and_unit(s, extensions)
+        .or_else(|| {
+            extensions
+                .contains(Extensions::NUMBER_WORDS)
+                .then(|| number_words_and_unit(s, converter))
+                .flatten()
+        })
+        .ok_or(MetadataError::InvalidYield)?;
+    // simplify fractions like `3/2` to `1 1/2`
+    let value = match value {
+        Value::Number(n @ Number::Fraction { .. }) => {
+            Value::Number(n.to_rational().map_or(n, Number::from_rational))
+        }
+        v => v,
+    };
+    Ok(Quantity::new_and_parse(
+        value,
+        unit.map(str::to_string),
+        converter,
+    ))
+}
+
+/// Splits a text that starts with a number written with words, like
+/// `half a dozen rolls`, using the most words possible for the number
+fn number_words_and_unit<'a>(
+    s: &'a str,
+    converter: &Converter,
+) -> Option<(Value, Option<&'a str>)> {
+    let ends = s
+        .char_indices()
+        .filter(|(_, c)| c.is_whitespace())
+        .map(|(i, _)| i)
+        .chain(std::iter::once(s.len()))
+        .collect::<Vec<_>>();
+    ends.into_iter().rev().find_map(|end| {
+        let value = converter.parse_number_words(&s[..end])?;
+        if !matches!(value, Value::Number(_)) {
+            return None;
+        }
+        let unit = s[end..].trim();
+        Some((value, Some(unit).filter(|u| !u.is_empty())))
+    })
+}
+
 fn value_as_minutes(val: &serde_yaml::Value, converter: &Converter) -> Result<u32, MetadataError> {
     if let Some(s) = val.as_str() {
         let t = parse_time(s, converter)?;
@@ -353,17 +444,28 @@ fn value_as_time(
     }
 }
 
+/// Parsed value of a standard key that is kept in the recipe
+pub(crate) enum StdValue {
+    Servings(crate::scale::Servings),
+    Yield(ScaledQuantity),
+}
+
 pub(crate) fn check_std_entry(
     key: StdKey,
     value: &serde_yaml::Value,
+    extensions: Extensions,
     converter: &Converter,
-) -> Result<Option<crate::scale::Servings>, MetadataError> {
+) -> Result<Option<StdValue>, MetadataError> {
     match key {
         StdKey::Tags => value_as_tags(value).map(|_| None),
         StdKey::Emoji => value_as_emoji(value).map(|_| None),
         StdKey::Time => value_as_time(value, converter).map(|_| None),
         StdKey::PrepTime | StdKey::CookTime => value_as_minutes(value, converter).map(|_| None),
-        StdKey::Servings => value_as_servings(value).map(|s| Some(crate::scale::Servings(Some(s)))),
+        StdKey::Servings => value_as_servings(value)
+            .map(|s| Some(StdValue::Servings(crate::scale::Servings(Some(s))))),
+        StdKey::Yield => {
+            value_as_yield(value, extensions, converter).map(|q| Some(StdValue::Yield(q)))
+        }
         _ => Ok(None),
     }
 }
@@ -402,6 +504,9 @@ fn parse_time(s: &str, converter: &Converter) -> Result<u32, ParseTimeError> {
     if s.is_empty() {
         return Err(ParseTimeError::Empty);
     }
+    if let Some(duration) = Duration::parse(s) {
+        return Ok(duration.minutes().round() as u32);
+    }
     let r = parse_time_with_units(s, converter);
     // if any error, try to fall back to a full float parse
     if r.is_err() {
@@ -572,6 +677,10 @@ pub(crate) enum MetadataError {
     DuplicateServings { servings: Vec<u32> },
     #[error(transparent)]
     ParseTimeError(#[from] ParseTimeError),
+    #[error(transparent)]
+    ParseFloatError(#[from] ParseFloatError),
+    #[error("Expected a number and an optional unit")]
+    InvalidYield,
 }
 
 #[cfg(test)]
@@ -599,6 +708,58 @@ mod tests {
         assert_eq!(t("   0  hours 90min 59 sec "), Some(91));
     }
 
+    #[test]
+    fn parse_yield() {
+        let converter = Converter::bundled();
+        let t = |v: serde_yaml::Value| {
+            value_as_yield(&v, Extensions::all(), &converter)
+                .ok()
+                .map(|q| q.to_string())
+        };
+        assert_eq!(t(12.into()), Some("12".into()));
+        assert_eq!(t("2 loaves".into()), Some("2 loaves".into()));
+        assert_eq!(t("1.5L".into()), Some("1.5 L".into()));
+        assert_eq!(t("1/2 cup".into()), Some("1/2 cup".into()));
+        assert_eq!(t("3/2 cups".into()), Some("1 1/2 cups".into()));
+        assert_eq!(t("1/0 cup".into()), None);
+        assert_eq!(t("some bread".into()), None);
+        assert_eq!(t(true.into()), None);
+        assert_eq!(t("1,5 l".into()), Some("1.5 l".into()));
+        assert_eq!(t("1,000 g".into()), None);
+        assert_eq!(t("½ loaf".into()), Some("1/2 loaf".into()));
+        assert_eq!(t("two loaves".into()), Some("2 loaves".into()));
+        assert_eq!(t("half a dozen rolls".into()), Some("6 rolls".into()));
+        assert_eq!(t("a pinch".into()), None);
+    }
+
+    #[test]
+    fn parse_yield_extensions() {
+        let converter = Converter::bundled();
+        let t = |v: &str| {
+            value_as_yield(&v.into(), Extensions::empty(), &converter)
+                .ok()
+                .map(|q| q.to_string())
+        };
+        assert_eq!(t("2 loaves"), Some("2 loaves".into()));
+        assert_eq!(t("1,5 l"), None);
+        assert_eq!(t("½ loaf"), None);
+        assert_eq!(t("two loaves"), None);
+    }
+
+    #[test]
+    fn test_parse_time_durations() {
+        let converter = Converter::bundled();
+        let t = |s: &str| parse_time(s, &converter).ok();
+        assert_eq!(t("PT1H30M"), Some(90));
+        assert_eq!(t("PT45S"), Some(1)); // round up
+        assert_eq!(t("P1DT1H"), Some(1500));
+        assert_eq!(t("1:30"), Some(90));
+        assert_eq!(t("1:30:29"), Some(90));
+        assert_eq!(t("0:05:30"), Some(6)); // round up
+        assert_eq!(t("PT1Y"), None);
+        assert_eq!(parse_time("1:30", &Converter::empty()).ok(), Some(90));
+    }
+
     #[test]
     fn special_keys() {
         let t = |s: &str, key: StdKey| {
@@ -622,6 +783,7 @@ mod tests {
         t("cook time", StdKey::CookTime);
         t_alias("cook_time", StdKey::CookTime);
         t("servings", StdKey::Servings);
+        t("yield", StdKey::Yield);
     }
 
     #[test]
@@ -8,8 +8,11 @@ use crate::{
     convert::Converter,
     metadata::Metadata,
     parser::Modifiers,
-    quantity::{GroupedValue, Quantity, QuantityValue, ScalableValue, ScaledQuantity},
-    GroupedQuantity, Value,
+    quantity::{
+        GroupedValue, Quantity, QuantityAddError, QuantityValue, ScalableValue, ScaledQuantity,
+        TextValueError,
+    },
+    GroupedQuantity, Span, Value,
 };
 
 /// A complete recipe
@@ -39,7 +42,14 @@ pub struct Recipe<D, V: QuantityValue> {
     /// All the timers
     pub timers: Vec<Timer<V>>,
     /// All the inline quantities
-    pub inline_quantities: Vec<ScaledQuantity>,
+    ///
+    /// These are the temperatures found in the text and the quantities
+    /// written with the
+    /// [`INLINE_QUANTITIES`](crate::Extensions::INLINE_QUANTITIES) extension.
+    pub inline_quantities: Vec<Quantity<V>>,
+    /// Locations of the components in the source code
+    #[serde(default)]
+    pub source_map: SourceMap,
     pub(crate) data: D,
 }
 
@@ -52,9 +62,27 @@ pub type ScalableRecipe = Recipe<crate::scale::Servings, ScalableValue>;
 /// A recipe after being scaled
 ///
 /// Note that this doesn't implement [`Recipe::scale`]. A recipe can only be
-/// scaled once.
+/// scaled once, use [`Recipe::scale_ref`] to scale the same recipe many times.
 pub type ScaledRecipe = Recipe<crate::scale::Scaled, Value>;
 
+/// Locations in the source code of the components of a [`Recipe`]
+///
+/// Each list has a span for every component of the same kind in the recipe.
+/// The span is the quantity of the component, or the whole component if it
+/// has no quantity. This is used to locate the errors from scaling and
+/// converting the recipe.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SourceMap {
+    /// Spans of the ingredients
+    pub ingredients: Vec<Span>,
+    /// Spans of the cookware
+    pub cookware: Vec<Span>,
+    /// Spans of the timers
+    pub timers: Vec<Span>,
+    /// Spans of the inline quantities
+    pub inline_quantities: Vec<Span>,
+}
+
 /// A section holding steps
 #[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Section {
@@ -183,7 +211,7 @@ pub struct Ingredient<V: QuantityValue = Value> {
 
 impl<V: QuantityValue> Ingredient<V> {
     /// Gets the name the ingredient should be displayed with
-    pub fn display_name(&self) -> Cow<str> {
+    pub fn display_name(&self) -> Cow<'_, str> {
         let mut name = Cow::from(&self.name);
         if self.modifiers.contains(Modifiers::RECIPE) {
             if let Some(recipe_name) = std::path::Path::new(&self.name)
@@ -252,7 +280,7 @@ impl Ingredient<Value> {
     pub fn all_quantities<'a>(
         &'a self,
         all_ingredients: &'a [Self],
-    ) -> impl Iterator<Item = &ScaledQuantity> {
+    ) -> impl Iterator<Item = &'a ScaledQuantity> {
         std::iter::once(self.quantity.as_ref())
             .chain(
                 self.relation
@@ -276,6 +304,9 @@ pub struct Cookware<V: QuantityValue = Value> {
     ///
     /// Note that this is a value, not a quantity, so it doesn't have units.
     pub quantity: Option<V>,
+    /// Size of a pan, with the [`PAN_SIZES`](crate::Extensions::PAN_SIZES)
+    /// extension
+    pub size: Option<PanSize>,
     /// Note
     pub note: Option<String>,
     /// How the cookware is related to others
@@ -283,6 +314,118 @@ pub struct Cookware<V: QuantityValue = Value> {
     pub(crate) modifiers: Modifiers,
 }
 
+/// Size of a pan
+///
+/// Written in the cookware quantity with a length unit, like
+/// `#cake pan{20%cm}` or `#baking pan{23x33%cm}`. It can also be parsed from
+/// the same text, like `"23x33 cm".parse::<PanSize>()`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "shape", rename_all = "camelCase")]
+pub enum PanSize {
+    Round {
+        diameter: ScaledQuantity,
+    },
+    Rectangular {
+        width: ScaledQuantity,
+        length: ScaledQuantity,
+    },
+}
+
+impl PanSize {
+    /// Creates a pan size from a value and the unit of its lengths
+    ///
+    /// A number is the diameter of a round pan and a text like `23x33` or
+    /// `23 × 33` are the sides of a rectangular pan.
+    pub(crate) fn from_value(value: &Value, unit: &str) -> Option<Self> {
+        let length = |n: f64| {
+            (n > 0.0 && n.is_finite())
+                .then(|| Quantity::new(Value::from(n), Some(unit.to_string())))
+        };
+        match value {
+            Value::Number(n) => Some(Self::Round {
+                diameter: length(n.value())?,
+            }),
+            Value::Text(t) => {
+                let (width, length_t) = t.split_once(['x', 'X', '×'])?;
+                let parse = |s: &str| s.trim().replace(',', ".").parse::<f64>().ok();
+                Some(Self::Rectangular {
+                    width: length(parse(width)?)?,
+                    length: length(parse(length_t)?)?,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Area of the pan divided by the area of another pan
+    ///
+    /// The lengths are converted if they are in different units. Lengths
+    /// that are not a number, like a range, are an error.
+    ///
+    /// ```
+    /// # use cooklang::{Converter, model::PanSize};
+    /// let converter = Converter::bundled();
+    /// let big: PanSize = "23x33 cm".parse().unwrap();
+    /// let round: PanSize = "20 cm".parse().unwrap();
+    /// let ratio = big.area_ratio(&round, &converter).unwrap();
+    /// assert!((ratio - 2.416).abs() < 0.001);
+    /// ```
+    pub fn area_ratio(
+        &self,
+        rhs: &PanSize,
+        converter: &Converter,
+    ) -> Result<f64, QuantityAddError> {
+        // use the first length of `rhs` as the unit for both areas
+        let unit = match rhs {
+            PanSize::Round { diameter } => diameter,
+            PanSize::Rectangular { width, .. } => width,
+        };
+        let len = |q: &ScaledQuantity| -> Result<f64, QuantityAddError> {
+            match q.ratio(unit, converter)? {
+                Value::Number(n) => Ok(n.value()),
+                value @ Value::Range { .. } => Err(QuantityAddError::RangeValue(value)),
+                value => Err(QuantityAddError::TextValue(TextValueError(value))),
+            }
+        };
+        let area = |pan: &PanSize| -> Result<f64, QuantityAddError> {
+            Ok(match pan {
+                PanSize::Round { diameter } => {
+                    let r = len(diameter)? / 2.0;
+                    std::f64::consts::PI * r * r
+                }
+                PanSize::Rectangular { width, length } => len(width)? * len(length)?,
+            })
+        };
+        Ok(area(self)? / area(rhs)?)
+    }
+}
+
+impl std::str::FromStr for PanSize {
+    type Err = ParsePanSizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .rfind(|c: char| c.is_ascii_digit() || c == '.')
+            .ok_or(ParsePanSizeError)?;
+        let (value, unit) = s.split_at(split + 1);
+        let unit = unit.trim();
+        if unit.is_empty() {
+            return Err(ParsePanSizeError);
+        }
+        let value = match value.trim().replace(',', ".").parse::<f64>() {
+            Ok(n) => Value::from(n),
+            Err(_) => Value::Text(value.to_string()),
+        };
+        Self::from_value(&value, unit).ok_or(ParsePanSizeError)
+    }
+}
+
+/// Error parsing a [`PanSize`]
+#[derive(Debug, thiserror::Error, Clone, PartialEq)]
+#[error("Invalid pan size, expected something like '20 cm' or '23x33 cm'")]
+pub struct ParsePanSizeError;
+
 impl<V: QuantityValue> Cookware<V> {
     /// Gets the name the cookware item should be displayed with
     pub fn display_name(&self) -> &str {
@@ -332,7 +475,7 @@ impl Cookware<Value> {
     }
 
     /// Gets an iterator over all quantities of this ingredient and its references.
-    pub fn all_amounts<'a>(&'a self, all_cookware: &'a [Self]) -> impl Iterator<Item = &Value> {
+    pub fn all_amounts<'a>(&'a self, all_cookware: &'a [Self]) -> impl Iterator<Item = &'a Value> {
         std::iter::once(self.quantity.as_ref())
             .chain(
                 self.relation
@@ -533,9 +676,9 @@ pub struct Timer<V: QuantityValue = Value> {
     /// If created from parsing the following applies:
     ///
     /// - If the [`ADVANCED_UNITS`](crate::Extensions::ADVANCED_UNITS) extension
-    /// is enabled, this is guaranteed to have a time unit and a non text value.
+    ///   is enabled, this is guaranteed to have a time unit and a non text value.
     ///
     /// - If the [`TIMER_REQUIRES_TIME`](crate::Extensions::TIMER_REQUIRES_TIME)
-    /// extension is enabled, this is guaranteed to be [`Some`].
+    ///   extension is enabled, this is guaranteed to be [`Some`].
     pub quantity: Option<Quantity<V>>,
 }
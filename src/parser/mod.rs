@@ -35,7 +35,8 @@
 //! unit       = (!CloseBrace ANY)*
 //!
 //! val_sep    = Whitespace Or Whitespace
-//! auto_scale = Whitespace Star Whitespace
+//! auto_scale = Whitespace Star Whitespace scaling_law?
+//! scaling_law = Word Whitespace
 //! unit_sep   = Whitespace Percent Whitespace
 //!
 //! val        = num_val | text_val
@@ -79,6 +80,7 @@ use crate::{
 };
 
 pub(crate) use block_parser::BlockParser;
+pub(crate) use quantity::parse_number_and_unit;
 use token_stream::{Token, TokenStream};
 
 /// Events generated by [`PullParser`]
@@ -111,6 +113,9 @@ pub enum Event<'i> {
     Cookware(Located<Cookware<'i>>),
     /// Timer item
     Timer(Located<Timer<'i>>),
+    /// Quantity in the text, with the
+    /// [`INLINE_QUANTITIES`](crate::Extensions::INLINE_QUANTITIES) extension
+    InlineQuantity(Located<Quantity<'i>>),
 
     /// Parser error
     ///
@@ -367,7 +372,7 @@ fn parse_block(block: &mut BlockParser, old_style_metadata: bool) {
                 let key_t = key.text_outer_trimmed();
                 let is_config_key = key_t.starts_with('[') && key_t.ends_with(']');
                 let modes_active = bp.extension(Extensions::MODES);
-                return (is_config_key && modes_active) || old_style_metadata;
+                (is_config_key && modes_active) || old_style_metadata
             })
         }),
         T![=] => block.with_recover(section),
@@ -1,4 +1,6 @@
-use crate::{error::Recover, located::Located, quantity::Value, span::Span, text::Text};
+use crate::{
+    error::Recover, located::Located, quantity::Value, scale::ScalingLaw, span::Span, text::Text,
+};
 
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
@@ -35,6 +37,7 @@ pub enum Item<'a> {
     Ingredient(Box<Located<Ingredient<'a>>>),
     Cookware(Box<Located<Cookware<'a>>>),
     Timer(Box<Located<Timer<'a>>>),
+    InlineQuantity(Box<Located<Quantity<'a>>>),
 }
 
 impl Item<'_> {
@@ -45,6 +48,7 @@ impl Item<'_> {
             Item::Ingredient(c) => c.span(),
             Item::Cookware(c) => c.span(),
             Item::Timer(c) => c.span(),
+            Item::InlineQuantity(q) => q.span(),
         }
     }
 }
@@ -80,6 +84,10 @@ pub struct Cookware<'a> {
     /// This it's just a [`QuantityValue`], because cookware cannot not have
     /// a unit.
     pub quantity: Option<Located<QuantityValue>>,
+    /// The quantity when it has a unit, with the
+    /// [`PAN_SIZES`](crate::Extensions::PAN_SIZES) extension. Then,
+    /// [`Self::quantity`] is [`None`].
+    pub size: Option<Located<Quantity<'a>>>,
     pub note: Option<Text<'a>>,
 }
 
@@ -103,6 +111,22 @@ pub struct Quantity<'a> {
     ///
     /// It's just the text, no checks
     pub unit: Option<Text<'a>>,
+    /// Rest of the parts of a composite quantity like `1 lb 4 oz`
+    ///
+    /// This is only filled with the
+    /// [`COMPOSITE_QUANTITIES`](crate::Extensions::COMPOSITE_QUANTITIES)
+    /// extension. When it is not empty, [`Self::value`] and [`Self::unit`] are
+    /// the first part, and the unit is guaranteed to be [`Some`].
+    pub composite: Vec<CompositePart<'a>>,
+}
+
+/// A `value unit` pair of a composite [`Quantity`]
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CompositePart<'a> {
+    /// Numeric value
+    pub value: Located<Value>,
+    /// Unit text
+    pub unit: Text<'a>,
 }
 
 /// Quantity value(s)
@@ -113,6 +137,9 @@ pub enum QuantityValue {
         value: Located<Value>,
         /// [`Some`] if the auto scale marker (`*`) is present
         auto_scale: Option<Span>,
+        /// [`Some`] if the auto scale marker has a [`ScalingLaw`] after it,
+        /// like `*sqrt`
+        scaling_law: Option<Located<ScalingLaw>>,
     },
     /// Many values
     ///
@@ -125,11 +152,18 @@ impl QuantityValue {
     /// Calculates the span of the value or values
     pub fn span(&self) -> Span {
         match self {
-            QuantityValue::Single { value, auto_scale } => {
+            QuantityValue::Single {
+                value,
+                auto_scale,
+                scaling_law,
+            } => {
                 let s = value.span();
                 if let Some(marker) = auto_scale {
                     assert_eq!(s.end(), marker.start());
-                    Span::new(s.start(), marker.end())
+                    let end = scaling_law
+                        .as_ref()
+                        .map_or(marker.end(), |law| law.span().end());
+                    Span::new(s.start(), end)
                 } else {
                     s
                 }
@@ -155,6 +189,7 @@ impl Recover for Quantity<'_> {
         Self {
             value: Recover::recover(),
             unit: Recover::recover(),
+            composite: Vec::new(),
         }
     }
 }
@@ -164,6 +199,7 @@ impl Recover for QuantityValue {
         Self::Single {
             value: Recover::recover(),
             auto_scale: None,
+            scaling_law: None,
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use smallvec::SmallVec;
 
 use crate::{
@@ -5,12 +7,19 @@ use crate::{
     error::{label, SourceDiag},
     lexer::T,
     located::Located,
-    quantity::{Number, Value},
+    quantity::{Number, Value, VULGAR_FRACTIONS},
+    scale::ScalingLaw,
     span::Span,
     Extensions,
 };
 
-use super::{error, model::*, mt, token_stream::Token, tokens_span, warning, BlockParser};
+use super::{
+    error,
+    model::*,
+    mt,
+    token_stream::{Token, TokenStream},
+    tokens_span, warning, BlockParser,
+};
 
 pub struct ParsedQuantity<'a> {
     pub quantity: Located<Quantity<'a>>,
@@ -28,6 +37,14 @@ pub(crate) fn parse_quantity<'i>(
     // create an insolated sub-block for the quantity tokens
     let mut bp2 = BlockParser::new(tokens, bp.input, bp.events, bp.extensions);
 
+    let composite = bp2
+        .extension(Extensions::COMPOSITE_QUANTITIES)
+        .then(|| bp2.with_recover(parse_composite_quantity))
+        .flatten();
+    if let Some(composite) = composite {
+        return composite;
+    }
+
     let advanced = bp2
         .extension(Extensions::ADVANCED_UNITS)
         .then(|| bp2.with_recover(parse_advanced_quantity))
@@ -55,6 +72,7 @@ fn parse_regular_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> ParsedQuantity<'i
             value = QuantityValue::Single {
                 value: Located::new(text_val, text.span()),
                 auto_scale: None,
+                scaling_law: None,
             };
 
             if let Some(sep) = bp.consume(T![%]) {
@@ -81,7 +99,14 @@ fn parse_regular_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> ParsedQuantity<'i
     }
 
     ParsedQuantity {
-        quantity: Located::new(Quantity { value, unit }, tokens_span(bp.tokens())),
+        quantity: Located::new(
+            Quantity {
+                value,
+                unit,
+                composite: Vec::new(),
+            },
+            tokens_span(bp.tokens()),
+        ),
         unit_separator,
     }
 }
@@ -96,7 +121,7 @@ fn parse_advanced_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<ParsedQua
     }
 
     bp.ws_comments();
-    let value_tokens = bp.consume_while(|t| !matches!(t, T![word]));
+    let value_tokens = consume_value_tokens(bp);
 
     if value_tokens.is_empty() || value_tokens.last().unwrap().kind != T![ws] {
         return None;
@@ -138,8 +163,84 @@ fn parse_advanced_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<ParsedQua
                 value: QuantityValue::Single {
                     value,
                     auto_scale: None,
+                    scaling_law: None,
                 },
                 unit: Some(unit),
+                composite: Vec::new(),
+            },
+            tokens_span(bp.tokens()),
+        ),
+        unit_separator: None,
+    })
+}
+
+/// Parses `value unit value unit [...]`. This never emits errors, if anything
+/// is wrong it just returns [`None`], so other kind of quantity is tried.
+fn parse_composite_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<ParsedQuantity<'i>> {
+    if bp
+        .tokens()
+        .iter()
+        .any(|t| matches!(t.kind, T![|] | T![*] | T![%]))
+    {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    bp.ws_comments();
+    while !bp.at(T![eof]) {
+        // the value is everything until the first word, and like in advanced
+        // units, it has to be separated from the unit
+        let value_tokens = consume_value_tokens(bp);
+        if value_tokens.is_empty() || value_tokens.last().unwrap().kind != T![ws] {
+            return None;
+        }
+        let value_tokens = trim_tokens(value_tokens);
+        if value_tokens.is_empty() {
+            return None;
+        }
+        let value = match numeric_value(value_tokens, bp)? {
+            Ok(value @ Value::Number(_)) => value,
+            _ => return None,
+        };
+        let value = Located::new(value, tokens_span(value_tokens));
+
+        // the unit ends when a new value starts after a whitespace
+        let rest = bp.rest();
+        let unit_len = (0..rest.len())
+            .find(|&i| {
+                rest[i].kind == T![ws]
+                    && rest
+                        .get(i + 1)
+                        .is_some_and(|&t| t.kind == T![int] || vulgar_fraction(t, bp).is_some())
+            })
+            .unwrap_or(rest.len());
+        let unit_tokens = &rest[..unit_len];
+        bp.current += unit_len;
+        bp.ws_comments();
+
+        let unit = bp.text(unit_tokens.first()?.span.start(), unit_tokens);
+        if unit.is_text_empty() {
+            return None;
+        }
+        parts.push(CompositePart { value, unit });
+    }
+
+    if parts.len() < 2 {
+        return None;
+    }
+
+    let mut parts = parts.into_iter();
+    let first = parts.next().unwrap();
+    Some(ParsedQuantity {
+        quantity: Located::new(
+            Quantity {
+                value: QuantityValue::Single {
+                    value: first.value,
+                    auto_scale: None,
+                    scaling_law: None,
+                },
+                unit: Some(first.unit),
+                composite: parts.collect(),
             },
             tokens_span(bp.tokens()),
         ),
@@ -147,9 +248,49 @@ fn parse_advanced_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<ParsedQua
     })
 }
 
+/// Parses a text with a number and an optional unit after it, like the `yield`
+/// in the metadata
+///
+/// The number is everything before the first word, so with `1,5 l` and
+/// without [`EXTENDED_NUMBERS`](Extensions::EXTENDED_NUMBERS) there is no
+/// number. Returns [`None`] if the text does not start with a number.
+pub(crate) fn parse_number_and_unit(
+    input: &str,
+    extensions: Extensions,
+) -> Option<(Value, Option<&str>)> {
+    let tokens = TokenStream::new(input).collect::<Vec<_>>();
+    if tokens.is_empty() {
+        return None;
+    }
+    // no diagnostics, it's just a number or not
+    let mut events = VecDeque::new();
+    let mut bp = BlockParser::new(&tokens, input, &mut events, extensions);
+    let value_tokens = consume_value_tokens(&mut bp);
+    let value = range_value(value_tokens, &bp)
+        .or_else(|| numeric_value(value_tokens, &bp))?
+        .ok()?;
+    let unit_tokens = bp.consume_rest();
+    let unit = bp.slice_str(unit_tokens).trim();
+    bp.finish();
+    Some((value, Some(unit).filter(|u| !u.is_empty())))
+}
+
+/// Consumes the tokens of a value before a unit, everything until the first
+/// word that is not a number
+fn consume_value_tokens<'t>(bp: &mut BlockParser<'t, '_>) -> &'t [Token] {
+    let rest = bp.rest();
+    let len = rest
+        .iter()
+        .position(|&t| t.kind == T![word] && vulgar_fraction(t, bp).is_none())
+        .unwrap_or(rest.len());
+    bp.current += len;
+    &rest[..len]
+}
+
 fn many_values(bp: &mut BlockParser) -> QuantityValue {
     let mut values: Vec<Located<Value>> = vec![];
     let mut auto_scale = None;
+    let mut scaling_law = None;
 
     loop {
         let value_tokens = bp.consume_while(|t| !matches!(t, T![|] | T![*] | T![%]));
@@ -162,6 +303,9 @@ fn many_values(bp: &mut BlockParser) -> QuantityValue {
             T![*] => {
                 let tok = bp.bump_any();
                 auto_scale = Some(tok.span);
+                if bp.extension(Extensions::SCALING_LAWS) {
+                    scaling_law = parse_scaling_law(bp);
+                }
                 break;
             }
             _ => break,
@@ -172,6 +316,7 @@ fn many_values(bp: &mut BlockParser) -> QuantityValue {
         1 => QuantityValue::Single {
             value: values.pop().unwrap(),
             auto_scale,
+            scaling_law,
         },
         2.. => {
             if let Some(span) = auto_scale {
@@ -191,6 +336,34 @@ fn many_values(bp: &mut BlockParser) -> QuantityValue {
     }
 }
 
+/// Scaling law after the auto scale marker, like `*sqrt`
+fn parse_scaling_law(bp: &mut BlockParser) -> Option<Located<ScalingLaw>> {
+    let tokens = bp.consume_while(|t| t != T![%]);
+    if tokens.is_empty() {
+        return None;
+    }
+    let text = bp.text(tokens[0].span.start(), tokens);
+    if text.is_text_empty() {
+        return None;
+    }
+    let span = text.span();
+    match text.text_trimmed().parse::<ScalingLaw>() {
+        Ok(law) => Some(Located::new(law, span)),
+        Err(_) => {
+            bp.error(
+                error!(
+                    format!("Unknown scaling law: {}", text.text_trimmed()),
+                    label!(span, "this law")
+                )
+                .hint(
+                    "Possible scaling laws are 'fixed', 'linear', 'sqrt', 'ceil', 'whole' and 'half'",
+                ),
+            );
+            None
+        }
+    }
+}
+
 fn parse_value(tokens: &[Token], bp: &mut BlockParser) -> Located<Value> {
     let start = tokens
         .first()
@@ -279,6 +452,15 @@ fn numeric_value(tokens: &[Token], bp: &BlockParser) -> Option<Result<Value, Sou
         &[mt![int], mt![.], mt![int | zeroint]] | &[mt![.], mt![int | zeroint]] => {
             Some(float(trimmed_tokens, bp))
         }
+        // `1,000` could be a thousands separator, so it's ambiguous and left
+        // as text
+        &[mt![int], sep @ mt![punctuation], dec @ mt![int | zeroint]]
+            if bp.extension(Extensions::EXTENDED_NUMBERS)
+                && bp.token_str(sep) == ","
+                && bp.token_str(dec).len() != 3 =>
+        {
+            Some(decimal_comma_float(trimmed_tokens, bp))
+        }
         _ => None,
     };
     if r.is_some() {
@@ -299,6 +481,25 @@ fn numeric_value(tokens: &[Token], bp: &BlockParser) -> Option<Result<Value, Sou
         [i @ mt![int], a @ mt![int], mt![/], b @ mt![int]] => mixed_num(i, a, b, bp),
         // frac
         [a @ mt![int], mt![/], b @ mt![int]] => frac(a, b, bp),
+        // unicode fractions
+        [i @ mt![int], f @ mt![word]] if vulgar_fraction(f, bp).is_some() => {
+            let (num, den) = vulgar_fraction(f, bp).unwrap();
+            int(i, bp).map(|whole| Number::Fraction {
+                whole,
+                num,
+                den,
+                err: 0.0,
+            })
+        }
+        [f @ mt![word]] if vulgar_fraction(f, bp).is_some() => {
+            let (num, den) = vulgar_fraction(f, bp).unwrap();
+            Ok(Number::Fraction {
+                whole: 0,
+                num,
+                den,
+                err: 0.0,
+            })
+        }
         // other => not numeric
         _ => return None,
     };
@@ -336,6 +537,23 @@ fn frac(a: Token, b: Token, line: &BlockParser) -> Result<Number, SourceDiag> {
     }
 }
 
+/// Get the numerator and denominator if the token is a unicode fraction
+/// character and the extension is enabled
+fn vulgar_fraction(tok: Token, bp: &BlockParser) -> Option<(u32, u32)> {
+    if tok.kind != T![word] || !bp.extension(Extensions::EXTENDED_NUMBERS) {
+        return None;
+    }
+    let mut chars = bp.token_str(tok).chars();
+    let c = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+    VULGAR_FRACTIONS
+        .iter()
+        .find(|&&(g, _, _)| g == c)
+        .map(|&(_, num, den)| (num, den))
+}
+
 fn int(tok: Token, block: &BlockParser) -> Result<u32, SourceDiag> {
     assert_eq!(tok.kind, T![int]);
     block
@@ -350,6 +568,15 @@ fn float(tokens: &[Token], bp: &BlockParser) -> Result<f64, SourceDiag> {
     })
 }
 
+fn decimal_comma_float(tokens: &[Token], bp: &BlockParser) -> Result<f64, SourceDiag> {
+    bp.slice_str(tokens)
+        .replace(',', ".")
+        .parse::<f64>()
+        .map_err(|e| {
+            error!("Error parsing decimal number", label!(tokens_span(tokens))).set_source(e)
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -400,6 +627,7 @@ mod tests {
             QuantityValue::Single {
                 value: Located::new(num!(100.0), 0..3),
                 auto_scale: None,
+                scaling_law: None,
             }
         );
         assert_eq!(s, Some(Span::new(3, 4)));
@@ -413,7 +641,8 @@ mod tests {
             q.value,
             QuantityValue::Single {
                 value: Located::new(num!(100.0), 0..3),
-                auto_scale: None
+                auto_scale: None,
+                scaling_law: None
             }
         );
         assert_eq!(s, None);
@@ -425,7 +654,8 @@ mod tests {
             q.value,
             QuantityValue::Single {
                 value: Located::new(Value::Text("100 ml".into()), 0..6),
-                auto_scale: None
+                auto_scale: None,
+                scaling_law: None
             }
         );
         assert_eq!(s, None);
@@ -440,7 +670,8 @@ mod tests {
             q.value,
             QuantityValue::Single {
                 value: Located::new(range!(100.0, 200.0), 0..7),
-                auto_scale: None
+                auto_scale: None,
+                scaling_law: None
             }
         );
         assert_eq!(s, None);
@@ -463,7 +694,8 @@ mod tests {
                     },
                     0..11
                 ),
-                auto_scale: None
+                auto_scale: None,
+                scaling_law: None
             }
         );
         assert_eq!(s, None);
@@ -512,6 +744,62 @@ mod tests {
         assert_eq!(ctx.warnings().count(), 0);
     }
 
+    #[test]
+    fn composite_quantity() {
+        let (q, s, ctx) = t!("1 lb 4 oz");
+        assert_eq!(
+            q.value,
+            QuantityValue::Single {
+                value: Located::new(num!(1.0), 0..1),
+                auto_scale: None,
+                scaling_law: None
+            }
+        );
+        assert_eq!(s, None);
+        assert_eq!(q.unit.unwrap().text(), "lb");
+        assert_eq!(q.composite.len(), 1);
+        assert_eq!(q.composite[0].value, Located::new(num!(4.0), 5..6));
+        assert_eq!(q.composite[0].unit.text(), "oz");
+        assert!(ctx.is_empty());
+
+        let (q, _, ctx) = t!("1 h 30 min 15 s");
+        assert_eq!(q.unit.unwrap().text(), "h");
+        assert_eq!(q.composite.len(), 2);
+        assert_eq!(q.composite[0].unit.text_trimmed(), "min");
+        assert_eq!(q.composite[1].unit.text_trimmed(), "s");
+        assert!(ctx.is_empty());
+
+        let (q, _, ctx) = t!("1 1/2 fl oz 2 tbsp");
+        assert_eq!(q.unit.unwrap().text_trimmed(), "fl oz");
+        assert_eq!(q.composite.len(), 1);
+        assert_eq!(q.composite[0].unit.text(), "tbsp");
+        assert!(ctx.is_empty());
+    }
+
+    #[test]
+    fn composite_quantity_fallback() {
+        // a single part is a regular quantity
+        let (q, _, _) = t!("1 h");
+        assert_eq!(q.unit.unwrap().text(), "h");
+        assert!(q.composite.is_empty());
+
+        // not enabled
+        let (q, _, _) = t!(
+            "1 h 30 min",
+            Extensions::all() ^ Extensions::COMPOSITE_QUANTITIES
+        );
+        assert_eq!(q.unit.unwrap().text(), "h 30 min");
+        assert!(q.composite.is_empty());
+
+        // ranges are not allowed
+        let (q, _, _) = t!("1-2 h 30 min");
+        assert!(q.composite.is_empty());
+
+        // separators are not allowed
+        let (q, _, _) = t!("1%h 30 min");
+        assert!(q.composite.is_empty());
+    }
+
     #[test]
     fn range_value() {
         let (q, _, _) = t!("2-3");
@@ -519,7 +807,8 @@ mod tests {
             q.value,
             QuantityValue::Single {
                 value: Located::new(range!(2.0, 3.0), 0..3),
-                auto_scale: None
+                auto_scale: None,
+                scaling_law: None
             }
         );
         assert_eq!(q.unit, None);
@@ -532,7 +821,8 @@ mod tests {
             q.value,
             QuantityValue::Single {
                 value: Located::new(Value::Text("2-3".into()), 0..3),
-                auto_scale: None
+                auto_scale: None,
+                scaling_law: None
             }
         );
         assert_eq!(q.unit, None);
@@ -545,7 +835,8 @@ mod tests {
             q.value,
             QuantityValue::Single {
                 value: Located::new(range!(2.5, 3.0), 0..7),
-                auto_scale: None
+                auto_scale: None,
+                scaling_law: None
             }
         );
         assert_eq!(q.unit, None);
@@ -555,7 +846,8 @@ mod tests {
             q.value,
             QuantityValue::Single {
                 value: Located::new(range!(2.0, 3.5), 0..7),
-                auto_scale: None
+                auto_scale: None,
+                scaling_law: None
             }
         );
         assert_eq!(q.unit, None);
@@ -565,7 +857,8 @@ mod tests {
             q.value,
             QuantityValue::Single {
                 value: Located::new(range!(2.5, 3.5), 0..11),
-                auto_scale: None
+                auto_scale: None,
+                scaling_law: None
             }
         );
         assert_eq!(q.unit, None);
@@ -575,6 +868,11 @@ mod tests {
     #[test_case("0 1/2" => (0, 1, 2); "zero whole")]
     #[test_case("01/2" => panics "not number"; "bad fraction")]
     #[test_case("2 1/2" => (2, 1, 2); "mixed value")]
+    #[test_case("½" => (0, 1, 2); "unicode fraction")]
+    #[test_case("1½" => (1, 1, 2); "unicode mixed value")]
+    #[test_case("2 ⅓" => (2, 1, 3); "unicode mixed value with space")]
+    #[test_case("⅓ cup" => (0, 1, 3); "unicode fraction with unit")]
+    #[test_case("1¾ cups" => (1, 3, 4); "unicode mixed value with unit")]
     fn fractional_val(s: &str) -> (u32, u32, u32) {
         let (q, _, _) = t!(s);
         let QuantityValue::Single { value, .. } = q.value else {
@@ -604,6 +902,8 @@ mod tests {
     #[test_case("10.05" => 10.05)]
     #[test_case("01" => panics "not number")]
     #[test_case("01.0" => panics "not number")]
+    #[test_case("1,5" => 1.5; "decimal comma")]
+    #[test_case("1,05 kg" => 1.05; "decimal comma with unit")]
     fn simple_numbers(s: &str) -> f64 {
         let (q, _, r) = t!(s);
         let QuantityValue::Single { value, .. } = q.value else {
@@ -619,4 +919,74 @@ mod tests {
         assert!(r.is_empty(), "source error");
         n
     }
+
+    #[test_case("½"; "unicode fraction")]
+    #[test_case("1,5"; "decimal comma")]
+    fn extended_numbers_disabled(s: &str) {
+        let (q, _, _) = t!(s, Extensions::all() ^ Extensions::EXTENDED_NUMBERS);
+        let QuantityValue::Single { value, .. } = q.value else {
+            panic!("not single value")
+        };
+        assert!(matches!(value.into_inner(), Value::Text(_)));
+    }
+
+    #[test_case("1,000%g"; "unit separator")]
+    #[test_case("1,000 g"; "advanced unit")]
+    #[test_case("12,500"; "no unit")]
+    fn thousands_separator_is_text(s: &str) {
+        let (q, _, _) = t!(s);
+        let QuantityValue::Single { value, .. } = q.value else {
+            panic!("not single value")
+        };
+        assert!(matches!(value.into_inner(), Value::Text(_)));
+    }
+
+    #[test]
+    fn unicode_composite_quantity() {
+        let (q, _, ctx) = t!("1 cup ½ tbsp");
+        assert_eq!(q.unit.unwrap().text(), "cup");
+        assert_eq!(q.composite.len(), 1);
+        assert_eq!(q.composite[0].unit.text(), "tbsp");
+        assert!(ctx.is_empty());
+    }
+
+    #[test_case("45*sqrt%min" => (Some(ScalingLaw::Sqrt), Some("min".to_string())); "sqrt")]
+    #[test_case("1 * ceil" => (Some(ScalingLaw::Ceil), None); "ceil")]
+    #[test_case("2*fixed%kg" => (Some(ScalingLaw::Fixed), Some("kg".to_string())); "fixed")]
+    #[test_case("2*%kg" => (None, Some("kg".to_string())); "no law")]
+    fn scaling_law(s: &str) -> (Option<ScalingLaw>, Option<String>) {
+        let (q, _, ctx) = t!(s);
+        assert!(ctx.is_empty());
+        let QuantityValue::Single {
+            auto_scale: Some(_),
+            scaling_law,
+            ..
+        } = q.value
+        else {
+            panic!("no auto scale marker")
+        };
+        (
+            scaling_law.map(Located::into_inner),
+            q.unit.map(|u| u.text_trimmed().into_owned()),
+        )
+    }
+
+    #[test]
+    fn unknown_scaling_law() {
+        let (q, _, ctx) = t!("2*cubic%kg");
+        assert!(ctx.has_errors());
+        let QuantityValue::Single { scaling_law, .. } = q.value else {
+            panic!("not single value")
+        };
+        assert!(scaling_law.is_none());
+    }
+
+    #[test]
+    fn scaling_laws_disabled() {
+        let (q, _, _) = t!("2*sqrt%kg", Extensions::all() ^ Extensions::SCALING_LAWS);
+        let QuantityValue::Single { value, .. } = q.value else {
+            panic!("not single value")
+        };
+        assert!(matches!(value.into_inner(), Value::Text(_)));
+    }
 }
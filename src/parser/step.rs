@@ -1,8 +1,10 @@
+use std::collections::VecDeque;
+
 use smallvec::SmallVec;
 
 use crate::{
-    error::label, error::Recover, lexer::T, located::Located, parser::model::*, span::Span,
-    text::Text, Extensions,
+    convert::Duration, error::label, error::Recover, lexer::T, located::Located, parser::model::*,
+    quantity::Value, span::Span, text::Text, Extensions,
 };
 
 use super::{
@@ -13,11 +15,13 @@ use super::{
 pub(crate) fn parse_step(bp: &mut BlockParser<'_, '_>) {
     bp.event(Event::Start(BlockKind::Step));
 
+    let inline_quantities = bp.extension(Extensions::INLINE_QUANTITIES);
     while !bp.rest().is_empty() {
         let component = match bp.peek() {
             T![@] => bp.with_recover(ingredient),
             T![#] => bp.with_recover(cookware),
             T![~] => bp.with_recover(timer),
+            T!['{'] if inline_quantities => bp.with_recover(inline_quantity),
             _ => None,
         };
         if let Some(ev) = component {
@@ -26,7 +30,11 @@ pub(crate) fn parse_step(bp: &mut BlockParser<'_, '_>) {
             let start = bp.current_offset();
             let tokens = bp.capture_slice(|bp| {
                 bp.bump_any(); // consume the first token, this avoids entering an infinite loop
-                bp.consume_while(|t| !matches!(t, T![@] | T![#] | T![~]));
+                bp.consume_while(|t| match t {
+                    T![@] | T![#] | T![~] => false,
+                    T!['{'] => !inline_quantities,
+                    _ => true,
+                });
             });
             let text = bp.text(start, tokens);
             if !text.fragments().is_empty() {
@@ -376,8 +384,13 @@ fn cookware<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
     let (name, alias) = parse_alias(COOKWARE, bp, body.name, name_offset);
     check_empty_name(COOKWARE, bp, &name);
 
-    let quantity = body.quantity.map(|tokens| {
+    let mut size = None;
+    let quantity = body.quantity.and_then(|tokens| {
         let q = parse_quantity(bp, tokens);
+        if q.quantity.unit.is_some() && bp.extension(Extensions::PAN_SIZES) {
+            size = Some(q.quantity);
+            return None;
+        }
         if let Some(unit) = &q.quantity.unit {
             let span = if let Some(sep) = q.unit_separator {
                 Span::new(sep.start(), unit.span().end())
@@ -397,15 +410,17 @@ fn cookware<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
             ..
         } = &q.quantity.value
         {
-            bp.error(
-                error!(
-                    "Invalid cookware quantity: auto scale marker",
-                    label!(auto_scale, "remove this"),
-                )
-                .hint("Cookware items amount can't be auto scaled"),
-            );
+            if !bp.extension(Extensions::SCALING_LAWS) {
+                bp.error(
+                    error!(
+                        "Invalid cookware quantity: auto scale marker",
+                        label!(auto_scale, "remove this"),
+                    )
+                    .hint("Cookware items amount can't be auto scaled"),
+                );
+            }
         }
-        q.quantity.map(|q| q.value)
+        Some(q.quantity.map(|q| q.value))
     });
     let modifiers = parse_modifiers(bp, modifiers_tokens, modifiers_pos);
     let modifiers = check_intermediate_data(bp, modifiers, COOKWARE);
@@ -430,6 +445,7 @@ fn cookware<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
             name,
             alias,
             quantity,
+            size,
             modifiers,
             note,
         },
@@ -437,6 +453,46 @@ fn cookware<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
     )))
 }
 
+fn inline_quantity<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
+    let start = bp.current_offset();
+    bp.consume(T!['{'])?;
+    let tokens = bp.until(|t| t == T!['}'])?;
+    bp.bump(T!['}']);
+    let end = bp.current_offset();
+
+    // `{}` is just text
+    if tokens
+        .iter()
+        .all(|t| matches!(t.kind, T![ws] | T![block comment]))
+    {
+        return None;
+    }
+
+    // the diagnostics are only reported if it is a quantity
+    let mut events = VecDeque::new();
+    let mut sub_bp = BlockParser::new(tokens, bp.input, &mut events, bp.extensions);
+    let quantity = parse_quantity(&mut sub_bp, tokens).quantity.into_inner();
+
+    // braces in the text like `{well}` or `{"a": 1}` are not quantities, it
+    // needs a number with a unit or the auto scale marker
+    let is_quantity = match &quantity.value {
+        QuantityValue::Single {
+            value, auto_scale, ..
+        } => {
+            !matches!(value.value(), Value::Text(_))
+                && (auto_scale.is_some() || quantity.unit.is_some())
+        }
+        QuantityValue::Many(values) => {
+            values.iter().all(|v| !matches!(v.value(), Value::Text(_))) && quantity.unit.is_some()
+        }
+    };
+    if !is_quantity {
+        return None;
+    }
+    bp.events.extend(events);
+    Some(Event::InlineQuantity(Located::new(quantity, start..end)))
+}
+
 fn timer<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
     // Parse
     let start = bp.current_offset();
@@ -460,15 +516,17 @@ fn timer<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
             ..
         } = &q.quantity.value
         {
-            bp.error(
-                error!(
-                    "Invalid timer quantity: auto scale marker",
-                    label!(auto_scale, "remove this"),
-                )
-                .hint("Timers durations cannot be auto scaled"),
-            );
+            if !bp.extension(Extensions::SCALING_LAWS) {
+                bp.error(
+                    error!(
+                        "Invalid timer quantity: auto scale marker",
+                        label!(auto_scale, "remove this"),
+                    )
+                    .hint("Timers durations cannot be auto scaled"),
+                );
+            }
         }
-        if q.quantity.unit.is_none() {
+        if q.quantity.unit.is_none() && !is_duration(bp, &q.quantity) {
             bp.error(
                 error!(
                     "Invalid timer quantity: missing unit",
@@ -517,6 +575,21 @@ fn timer<'i>(bp: &mut BlockParser<'_, 'i>) -> Option<Event<'i>> {
     )))
 }
 
+/// A value without unit like `PT1H30M` or `1:30`, that is converted to a
+/// quantity with unit in the analysis
+fn is_duration(bp: &BlockParser, quantity: &Quantity) -> bool {
+    if !bp.extension(Extensions::DURATIONS) {
+        return false;
+    }
+    match &quantity.value {
+        QuantityValue::Single { value, .. } => match value.value() {
+            crate::quantity::Value::Text(text) => Duration::parse(text).is_some(),
+            _ => false,
+        },
+        QuantityValue::Many(_) => false,
+    }
+}
+
 fn check_modifiers(bp: &mut BlockParser, modifiers_tokens: &[Token], container: &'static str) {
     assert_ne!(container, INGREDIENT);
     assert_ne!(container, COOKWARE);
@@ -609,7 +682,7 @@ mod tests {
     use crate::{error::SourceReport, parser::token_stream::TokenStream};
     use test_case::test_case;
 
-    fn t(input: &str) -> (Vec<Event>, SourceReport) {
+    fn t(input: &str) -> (Vec<Event<'_>>, SourceReport) {
         let mut tokens = TokenStream::new(input).collect::<Vec<_>>();
         // trim trailing newlines, block splitting should make sure this never
         // reaches the step function
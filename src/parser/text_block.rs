@@ -36,7 +36,7 @@ mod tests {
     use indoc::indoc;
     use test_case::test_case;
 
-    fn t(input: &str) -> (Vec<Event>, SourceReport) {
+    fn t(input: &str) -> (Vec<Event<'_>>, SourceReport) {
         let mut tokens = TokenStream::new(input).collect::<Vec<_>>();
         // trim trailing newlines, block splitting should make sure this never
         // reaches the step function
@@ -8,8 +8,9 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    convert::{ConvertError, Converter, PhysicalQuantity, Unit},
+    convert::{ConversionProfile, ConvertError, Converter, PhysicalQuantity, Unit},
     parser,
+    scale::ScalingLaw,
 };
 
 /// A quantity used in components
@@ -33,6 +34,8 @@ pub enum ScalableValue {
     Linear(Value),
     /// Scaling is in defined steps of the number of servings
     ByServings(Vec<Value>),
+    /// Scaling follows a [`ScalingLaw`] other than linear
+    NonLinear { value: Value, law: ScalingLaw },
 }
 
 /// Base value
@@ -47,6 +50,12 @@ pub enum Value {
     ///
     /// It is not possible to operate with this variant.
     Text(String),
+    /// Amount without a number, like `a pinch` or `to taste`
+    ///
+    /// Only with the [`NUMBER_WORDS`](crate::Extensions::NUMBER_WORDS)
+    /// extension. It is not scaled and equal amounts are not repeated in
+    /// ingredient lists.
+    Indefinite(String),
 }
 
 /// A wrapper for different kinds of numbers
@@ -58,6 +67,10 @@ pub enum Value {
 ///
 /// Also, the [`Display`] implementation round `f64` to 3 decimal places.
 ///
+/// A fraction without error and an integer are exact. Adding, multiplying,
+/// scaling and converting exact numbers keeps them exact when possible, see
+/// [`Rational`].
+///
 /// ```
 /// # use cooklang::quantity::Number;
 /// let num = Number::Regular(14.0);
@@ -130,6 +143,13 @@ impl PartialEq for Number {
 pub trait QuantityValue: Display + Clone + sealed::Sealed {
     /// Check if the value is or contains text
     fn is_text(&self) -> bool;
+
+    /// Check if a unit with this value is written in singular
+    ///
+    /// A value is singular if it's `1` or a fraction less than `1`, like
+    /// `1/2 cup`. Ranges are singular if the end is singular, like
+    /// `1/2-1 cup`. Everything else is plural, like `2 cups` or `1.5 cups`.
+    fn is_singular(&self) -> bool;
 }
 
 impl QuantityValue for ScalableValue {
@@ -137,15 +157,37 @@ impl QuantityValue for ScalableValue {
         match self {
             ScalableValue::Fixed(value) => value.is_text(),
             ScalableValue::Linear(value) => value.is_text(),
+            ScalableValue::NonLinear { value, .. } => value.is_text(),
             ScalableValue::ByServings(values) => values.iter().any(Value::is_text),
         }
     }
+
+    fn is_singular(&self) -> bool {
+        match self {
+            ScalableValue::Fixed(value)
+            | ScalableValue::Linear(value)
+            | ScalableValue::NonLinear { value, .. } => value.is_singular(),
+            ScalableValue::ByServings(values) => values.first().is_some_and(Value::is_singular),
+        }
+    }
 }
 
 impl QuantityValue for Value {
     fn is_text(&self) -> bool {
         matches!(self, Value::Text(_))
     }
+
+    fn is_singular(&self) -> bool {
+        let singular = |n: &Number| match *n {
+            Number::Fraction { whole: 0, num, .. } => num > 0,
+            n => n.value() == 1.0,
+        };
+        match self {
+            Value::Number(n) => singular(n),
+            Value::Range { end, .. } => singular(end),
+            Value::Text(_) | Value::Indefinite(_) => false,
+        }
+    }
 }
 
 mod sealed {
@@ -266,6 +308,17 @@ impl<V: QuantityValue> Quantity<V> {
 }
 
 impl ScalableValue {
+    /// Check if the value is or contains an indefinite amount
+    pub(crate) fn is_indefinite(&self) -> bool {
+        let indefinite = |v: &Value| matches!(v, Value::Indefinite(_));
+        match self {
+            ScalableValue::Fixed(value)
+            | ScalableValue::Linear(value)
+            | ScalableValue::NonLinear { value, .. } => indefinite(value),
+            ScalableValue::ByServings(values) => values.iter().any(indefinite),
+        }
+    }
+
     pub(crate) fn from_ast(value: parser::QuantityValue) -> Self {
         match value {
             parser::QuantityValue::Single {
@@ -276,8 +329,15 @@ impl ScalableValue {
             parser::QuantityValue::Single {
                 value,
                 auto_scale: Some(_),
-                ..
-            } => Self::Linear(value.into_inner()),
+                scaling_law,
+            } => match scaling_law.map(crate::located::Located::into_inner) {
+                None | Some(ScalingLaw::Linear) => Self::Linear(value.into_inner()),
+                Some(ScalingLaw::Fixed) => Self::Fixed(value.into_inner()),
+                Some(law) => Self::NonLinear {
+                    value: value.into_inner(),
+                    law,
+                },
+            },
             parser::QuantityValue::Many(v) => Self::ByServings(
                 v.into_iter()
                     .map(crate::located::Located::into_inner)
@@ -292,7 +352,15 @@ impl<V: QuantityValue + Display> Display for Quantity<V> {
         self.value.fmt(f)?;
         if let Some(unit) = &self.unit {
             f.write_str(" ")?;
-            unit.fmt(f)?;
+            // a unit written with a name uses the right form for the value
+            let forms = match unit.info.get() {
+                Some(UnitInfo::Known(u)) => u.forms_of(&unit.text),
+                _ => None,
+            };
+            match forms {
+                Some(forms) => f.write_str(forms.for_value(&self.value))?,
+                None => unit.fmt(f)?,
+            }
         }
         Ok(())
     }
@@ -303,6 +371,7 @@ impl Display for ScalableValue {
         match self {
             Self::Fixed(value) => value.fmt(f),
             Self::Linear(value) => write!(f, "{value}*"),
+            Self::NonLinear { value, law } => write!(f, "{value}*{law}"),
             Self::ByServings(values) => {
                 for value in &values[..values.len() - 1] {
                     write!(f, "{}|", value)?;
@@ -318,7 +387,7 @@ impl Display for Value {
         match self {
             Value::Number(n) => n.fmt(f),
             Value::Range { start, end } => write!(f, "{start}-{end}"),
-            Value::Text(t) => t.fmt(f),
+            Value::Text(t) | Value::Indefinite(t) => t.fmt(f),
         }
     }
 }
@@ -357,6 +426,84 @@ impl Display for Number {
     }
 }
 
+/// Unicode vulgar fraction characters with their numerator and denominator
+pub(crate) const VULGAR_FRACTIONS: &[(char, u32, u32)] = &[
+    ('½', 1, 2),
+    ('⅓', 1, 3),
+    ('⅔', 2, 3),
+    ('¼', 1, 4),
+    ('¾', 3, 4),
+    ('⅕', 1, 5),
+    ('⅖', 2, 5),
+    ('⅗', 3, 5),
+    ('⅘', 4, 5),
+    ('⅙', 1, 6),
+    ('⅚', 5, 6),
+    ('⅐', 1, 7),
+    ('⅛', 1, 8),
+    ('⅜', 3, 8),
+    ('⅝', 5, 8),
+    ('⅞', 7, 8),
+    ('⅑', 1, 9),
+    ('⅒', 1, 10),
+];
+
+impl Number {
+    /// Display the number using unicode fraction characters when possible
+    ///
+    /// Fractions without a unicode character, and regular numbers, are
+    /// displayed as usual.
+    ///
+    /// ```
+    /// # use cooklang::quantity::Number;
+    /// let num = Number::Fraction { whole: 1, num: 1, den: 2, err: 0.0 };
+    /// assert_eq!(num.unicode().to_string(), "1½");
+    /// let num = Number::Fraction { whole: 0, num: 3, den: 4, err: 0.0 };
+    /// assert_eq!(num.unicode().to_string(), "¾");
+    /// let num = Number::Fraction { whole: 0, num: 3, den: 7, err: 0.0 };
+    /// assert_eq!(num.unicode().to_string(), "3/7");
+    /// ```
+    pub fn unicode(self) -> UnicodeNumber {
+        UnicodeNumber(self)
+    }
+}
+
+/// Display a [`Number`] with unicode fraction characters
+///
+/// Created from [`Number::unicode`].
+#[derive(Debug, Clone, Copy)]
+pub struct UnicodeNumber(Number);
+
+impl Display for UnicodeNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Number::Fraction {
+            whole,
+            num,
+            den,
+            err,
+        } = self.0
+        else {
+            return self.0.fmt(f);
+        };
+        let glyph = VULGAR_FRACTIONS
+            .iter()
+            .find(|&&(_, n, d)| n == num && d == den)
+            .map(|&(c, _, _)| c);
+        let Some(glyph) = glyph else {
+            return self.0.fmt(f);
+        };
+
+        if whole > 0 {
+            write!(f, "{whole}")?;
+        }
+        write!(f, "{glyph}")?;
+        if f.alternate() && err.abs() > 0.001 {
+            write!(f, " ({:+})", round_float(err))?;
+        }
+        Ok(())
+    }
+}
+
 impl Display for QuantityUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.text)
@@ -375,7 +522,7 @@ impl From<String> for Value {
     }
 }
 
-/// Error during adding of quantities
+/// Error during adding of quantities or other operations with them
 #[derive(Debug, Error)]
 pub enum QuantityAddError {
     #[error(transparent)]
@@ -386,6 +533,12 @@ pub enum QuantityAddError {
 
     #[error(transparent)]
     Convert(#[from] ConvertError),
+
+    #[error("Division by zero")]
+    DivisionByZero,
+
+    #[error("Cannot operate on a range value")]
+    RangeValue(Value),
 }
 
 /// Error that makes quantity units incompatible to be added
@@ -484,6 +637,110 @@ impl ScaledQuantity {
 
         Ok(qty)
     }
+
+    /// Try subtracting a quantity
+    ///
+    /// `rhs` is converted to the unit of `self`. The result can be negative.
+    ///
+    /// Ranges are subtracted so the result has every possible value, so
+    /// `500 g - 100-200 g` is `300-400 g`.
+    pub fn try_sub(&self, rhs: &Self, converter: &Converter) -> Result<Self, QuantityAddError> {
+        let rhs = self.same_unit(rhs, converter)?;
+        let value = self.value.try_sub(&rhs.value)?;
+        Ok(Quantity {
+            value,
+            unit: self.unit.clone(),
+        })
+    }
+
+    /// Try multiplying the value by a number, keeping the unit
+    ///
+    /// Both ends of a range are multiplied. The result of a negative factor
+    /// is still a range from the lowest to the highest value.
+    pub fn try_mul(&self, factor: impl Into<Number>) -> Result<Self, QuantityAddError> {
+        let factor = factor.into();
+        let value = self.value.map_numbers(|n| n * factor)?;
+        Ok(Quantity {
+            value,
+            unit: self.unit.clone(),
+        })
+    }
+
+    /// Try dividing the value by a number, keeping the unit
+    ///
+    /// Like [`Self::try_mul`], but it's an error to divide by 0.
+    pub fn try_div(&self, divisor: impl Into<Number>) -> Result<Self, QuantityAddError> {
+        let divisor = divisor.into();
+        if divisor.value() == 0.0 {
+            return Err(QuantityAddError::DivisionByZero);
+        }
+        let value = self.value.map_numbers(|n| n / divisor)?;
+        Ok(Quantity {
+            value,
+            unit: self.unit.clone(),
+        })
+    }
+
+    /// Ratio between two compatible quantities
+    ///
+    /// `rhs` is converted to the unit of `self`, so `1 cup` and `250 ml` can
+    /// be compared. If any of the values is a range, the result is a range
+    /// with every possible ratio.
+    ///
+    /// It's an error if `rhs` is or contains 0.
+    pub fn ratio(&self, rhs: &Self, converter: &Converter) -> Result<Value, QuantityAddError> {
+        let rhs = self.same_unit(rhs, converter)?;
+        let (a, b) = self.value.bounds()?;
+        let (c, d) = rhs.value.bounds()?;
+        if c.value() <= 0.0 && d.value() >= 0.0 {
+            return Err(QuantityAddError::DivisionByZero);
+        }
+        if matches!(
+            (&self.value, &rhs.value),
+            (Value::Number(_), Value::Number(_))
+        ) {
+            return Ok(Value::Number(a / c));
+        }
+        Ok(Value::range_of([a / c, a / d, b / c, b / d]))
+    }
+
+    /// Compare two compatible quantities
+    ///
+    /// `rhs` is converted to the unit of `self`, so `1 kg` is greater than
+    /// `500 g`.
+    ///
+    /// A range is less than another value only if all of its values are.
+    /// Ranges that overlap can't be compared, except when they are equal.
+    ///
+    /// Returns [`None`] if the quantities are not compatible or can't be
+    /// compared.
+    pub fn partial_cmp(&self, rhs: &Self, converter: &Converter) -> Option<std::cmp::Ordering> {
+        use std::cmp::Ordering;
+
+        let rhs = self.same_unit(rhs, converter).ok()?;
+        let (a, b) = self.value.bounds().ok()?;
+        let (c, d) = rhs.value.bounds().ok()?;
+        let (a, b, c, d) = (a.value(), b.value(), c.value(), d.value());
+        if a == c && b == d {
+            Some(Ordering::Equal)
+        } else if b < c {
+            Some(Ordering::Less)
+        } else if a > d {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+
+    /// `rhs` in the same unit as `self`, if they are compatible
+    fn same_unit(&self, rhs: &Self, converter: &Converter) -> Result<Self, QuantityAddError> {
+        let convert_to = self.compatible_unit(rhs, converter)?;
+        let mut rhs = rhs.clone();
+        if let Some(to) = convert_to {
+            rhs.convert(&to, converter)?;
+        };
+        Ok(rhs)
+    }
 }
 
 pub trait TryAdd: Sized {
@@ -493,7 +750,7 @@ pub trait TryAdd: Sized {
 }
 
 /// Error when try to operate on a text value
-#[derive(Debug, Error, Clone)]
+#[derive(Debug, Error, Clone, Serialize)]
 #[error("Cannot operate on a text value")]
 pub struct TextValueError(pub Value);
 
@@ -502,19 +759,20 @@ impl TryAdd for Value {
 
     fn try_add(&self, rhs: &Self) -> Result<Value, TextValueError> {
         let val = match (self, rhs) {
-            (Value::Number(a), Value::Number(b)) => Value::Number((a.value() + b.value()).into()),
+            (Value::Number(a), Value::Number(b)) => Value::Number(*a + *b),
             (Value::Number(n), Value::Range { start, end })
             | (Value::Range { start, end }, Value::Number(n)) => Value::Range {
-                start: (start.value() + n.value()).into(),
-                end: (end.value() + n.value()).into(),
+                start: *start + *n,
+                end: *end + *n,
             },
             (Value::Range { start: s1, end: e1 }, Value::Range { start: s2, end: e2 }) => {
                 Value::Range {
-                    start: (s1.value() + s2.value()).into(),
-                    end: (e1.value() + e2.value()).into(),
+                    start: *s1 + *s2,
+                    end: *e1 + *e2,
                 }
             }
-            (t @ Value::Text(_), _) | (_, t @ Value::Text(_)) => {
+            (t @ (Value::Text(_) | Value::Indefinite(_)), _)
+            | (_, t @ (Value::Text(_) | Value::Indefinite(_))) => {
                 return Err(TextValueError(t.to_owned()));
             }
         };
@@ -523,6 +781,66 @@ impl TryAdd for Value {
     }
 }
 
+pub trait TrySub: Sized {
+    type Err;
+
+    fn try_sub(&self, rhs: &Self) -> Result<Self, Self::Err>;
+}
+
+impl TrySub for Value {
+    type Err = TextValueError;
+
+    /// Subtracts the values
+    ///
+    /// When there are ranges, the result is a range with every possible
+    /// value, so `5 - 1-2` is `3-4`.
+    fn try_sub(&self, rhs: &Self) -> Result<Value, TextValueError> {
+        let (a, b) = self.bounds()?;
+        let (c, d) = rhs.bounds()?;
+        let val = match (self, rhs) {
+            (Value::Number(_), Value::Number(_)) => Value::Number(a - c),
+            _ => Value::Range {
+                start: a - d,
+                end: b - c,
+            },
+        };
+        Ok(val)
+    }
+}
+
+impl Value {
+    /// Start and end of a range, or the number twice
+    fn bounds(&self) -> Result<(Number, Number), TextValueError> {
+        match self {
+            Value::Number(n) => Ok((*n, *n)),
+            Value::Range { start, end } => Ok((*start, *end)),
+            Value::Text(_) | Value::Indefinite(_) => Err(TextValueError(self.clone())),
+        }
+    }
+
+    /// Applies an operation to the numbers, keeping ranges in order
+    fn map_numbers(&self, f: impl Fn(Number) -> Number) -> Result<Value, TextValueError> {
+        match self {
+            Value::Number(n) => Ok(Value::Number(f(*n))),
+            Value::Range { start, end } => Ok(Value::range_of([f(*start), f(*end)])),
+            Value::Text(_) | Value::Indefinite(_) => Err(TextValueError(self.clone())),
+        }
+    }
+
+    /// Range from the lowest to the highest of some numbers
+    fn range_of<const N: usize>(numbers: [Number; N]) -> Value {
+        let start = numbers
+            .into_iter()
+            .min_by(|a, b| a.value().total_cmp(&b.value()))
+            .unwrap();
+        let end = numbers
+            .into_iter()
+            .max_by(|a, b| a.value().total_cmp(&b.value()))
+            .unwrap();
+        Value::Range { start, end }
+    }
+}
+
 /// Group of quantities
 ///
 /// This support efficient adding of new quantities, merging other groups..
@@ -571,6 +889,13 @@ impl GroupedQuantity {
             self.other.push(q.clone());
             return;
         }
+        if let Value::Indefinite(_) = q.value {
+            // "a pinch" and "a pinch" is still "a pinch"
+            if !self.other.contains(q) {
+                self.other.push(q.clone());
+            }
+            return;
+        }
         if q.unit.is_none() {
             if let Some(stored) = &mut self.no_unit {
                 add!(stored, q, converter, self.other);
@@ -622,6 +947,20 @@ impl GroupedQuantity {
         Ok(())
     }
 
+    /// Same as [`Self::fit`] but converting following a [`ConversionProfile`]
+    ///
+    /// Quantities the profile keeps as is are not fitted.
+    pub fn fit_with_profile(
+        &mut self,
+        profile: &ConversionProfile,
+        converter: &Converter,
+    ) -> Result<(), ConvertError> {
+        for q in self.known.values_mut().filter_map(|q| q.as_mut()) {
+            q.convert(profile, converter)?;
+        }
+        Ok(())
+    }
+
     pub fn is_empty(&self) -> bool {
         self.iter().next().is_none()
     }
@@ -651,8 +990,8 @@ impl GroupedQuantity {
             .into_values()
             .flatten()
             .chain(self.unknown.into_values())
-            .chain(self.other.into_iter())
-            .chain(self.no_unit.into_iter())
+            .chain(self.other)
+            .chain(self.no_unit)
         {
             v.push(q)
         }
@@ -667,6 +1006,56 @@ impl Display for GroupedQuantity {
     }
 }
 
+/// A quantity split into many units, like `1 h 30 min`
+///
+/// Created from [`ScaledQuantity::to_composite`] or
+/// [`ScaledQuantity::fit_split`]. The parts are sorted from the biggest to the
+/// smallest unit.
+///
+/// The display impl is a space separated list of all the parts. If a part that
+/// is not the last one is not a whole number, they are separated with ` + `,
+/// like `1/4 cup + 1 tbsp`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CompositeQuantity(Vec<ScaledQuantity>);
+
+impl CompositeQuantity {
+    pub(crate) fn new(parts: Vec<ScaledQuantity>) -> Self {
+        debug_assert!(!parts.is_empty(), "empty composite quantity");
+        Self(parts)
+    }
+
+    /// Get all the parts
+    pub fn parts(&self) -> &[ScaledQuantity] {
+        &self.0
+    }
+
+    /// Turn the composite into a single vec
+    pub fn into_vec(self) -> Vec<ScaledQuantity> {
+        self.0
+    }
+}
+
+impl Display for CompositeQuantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut iter = self.0.iter();
+        let Some(mut prev) = iter.next() else {
+            return Ok(());
+        };
+        write!(f, "{prev}")?;
+        for q in iter {
+            let whole = match prev.value {
+                Value::Number(Number::Regular(v)) => v.fract() == 0.0,
+                Value::Number(Number::Fraction { num, .. }) => num == 0,
+                _ => false,
+            };
+            let sep = if whole { " " } else { " + " };
+            write!(f, "{sep}{q}")?;
+            prev = q;
+        }
+        Ok(())
+    }
+}
+
 /// Same as [`GroupedQuantity`] but for [`Value`]
 #[derive(Default, Debug, Clone, Serialize)]
 pub struct GroupedValue(Vec<Value>);
@@ -684,9 +1073,13 @@ impl GroupedValue {
             return;
         }
 
-        if value.is_text() {
+        if let Value::Indefinite(_) = value {
+            if !self.0.contains(value) {
+                self.0.push(value.clone());
+            }
+        } else if value.is_text() {
             self.0.push(value.clone());
-        } else if self.0[0].is_text() {
+        } else if matches!(self.0[0], Value::Text(_) | Value::Indefinite(_)) {
             self.0.insert(0, value.clone());
         } else {
             self.0[0] = self.0[0]
@@ -897,6 +1290,15 @@ impl Number {
     /// Tries to approximate the number to a fraction if possible and not an
     /// integer
     pub fn try_approx(&mut self, accuracy: f32, max_den: u8, max_whole: u32) -> bool {
+        // an exact fraction that already fits is kept as is
+        if let Some(exact @ Number::Fraction { whole, den, .. }) =
+            self.to_rational().map(Number::from_rational)
+        {
+            if den <= max_den as u32 && whole <= max_whole {
+                *self = exact;
+                return true;
+            }
+        }
         match Self::new_approx(self.value(), accuracy, max_den, max_whole) {
             Some(f) => {
                 *self = f;
@@ -907,6 +1309,277 @@ impl Number {
     }
 }
 
+/// An exact rational number
+///
+/// This is used to operate with [`Number`]s without losing precision. A
+/// [`Number::Fraction`] without error and an integer [`Number::Regular`] are
+/// exact, so adding `1/3` three times is exactly `1`.
+///
+/// It's always reduced and the denominator is never 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational {
+    num: i64,
+    den: u64,
+}
+
+impl Rational {
+    /// Creates a new rational number
+    ///
+    /// Returns [`None`] if the denominator is 0.
+    pub fn new(num: i64, den: u64) -> Option<Self> {
+        if den == 0 {
+            return None;
+        }
+        let g = gcd(num.unsigned_abs(), den);
+        Some(Self {
+            num: num / g as i64,
+            den: den / g,
+        })
+    }
+
+    /// Numerator, with the sign of the number
+    pub fn num(self) -> i64 {
+        self.num
+    }
+
+    /// Denominator
+    pub fn den(self) -> u64 {
+        self.den
+    }
+
+    /// Get the value as a float
+    pub fn value(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    /// Converts an integer float to a rational
+    ///
+    /// Returns [`None`] if the value is not an integer or is too big to be
+    /// exact.
+    pub fn from_integer(value: f64) -> Option<Self> {
+        const MAX_EXACT: f64 = (1u64 << 53) as f64;
+        if value.fract() != 0.0 || value.abs() > MAX_EXACT {
+            return None;
+        }
+        Self::new(value as i64, 1)
+    }
+
+    /// Finds a rational number close to the value
+    ///
+    /// It uses the continued fraction expansion of the value and returns the
+    /// first one with a relative error less than `max_err` for which `accept`
+    /// returns true with the denominator.
+    pub(crate) fn approx(value: f64, max_err: f64, accept: impl Fn(u64) -> bool) -> Option<Self> {
+        if !value.is_finite() || value <= 0.0 {
+            return None;
+        }
+        // convergents h/k
+        let (mut h0, mut h1) = (0u64, 1u64);
+        let (mut k0, mut k1) = (1u64, 0u64);
+        let mut x = value;
+        for _ in 0..32 {
+            let a = x.floor();
+            if a > u32::MAX as f64 {
+                return None;
+            }
+            let a = a as u64;
+            let h = a.checked_mul(h1)?.checked_add(h0)?;
+            let k = a.checked_mul(k1)?.checked_add(k0)?;
+            (h0, h1) = (h1, h);
+            (k0, k1) = (k1, k);
+
+            let approx = h as f64 / k as f64;
+            if ((approx - value) / value).abs() <= max_err && accept(k) {
+                return Self::new(i64::try_from(h).ok()?, k);
+            }
+            let rest = x - a as f64;
+            if rest < 1e-12 {
+                return None;
+            }
+            x = 1.0 / rest;
+        }
+        None
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let den = lcm(self.den, rhs.den)?;
+        let a = self.num.checked_mul(i64::try_from(den / self.den).ok()?)?;
+        let b = rhs.num.checked_mul(i64::try_from(den / rhs.den).ok()?)?;
+        Self::new(a.checked_add(b)?, den)
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // reduce first to avoid overflows
+        let g1 = gcd(self.num.unsigned_abs(), rhs.den);
+        let g2 = gcd(rhs.num.unsigned_abs(), self.den);
+        let num = (self.num / g1 as i64).checked_mul(rhs.num / g2 as i64)?;
+        let den = (self.den / g2).checked_mul(rhs.den / g1)?;
+        Self::new(num, den)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(Self {
+            num: rhs.num.checked_neg()?,
+            den: rhs.den,
+        })
+    }
+
+    /// Returns [`None`] if `rhs` is 0 or on overflow
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.num == 0 {
+            return None;
+        }
+        let den = i64::try_from(rhs.den).ok()?;
+        let recip = Self {
+            num: den.checked_mul(rhs.num.signum())?,
+            den: rhs.num.unsigned_abs(),
+        };
+        self.checked_mul(recip)
+    }
+}
+
+pub(crate) fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+pub(crate) fn lcm(a: u64, b: u64) -> Option<u64> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+impl Number {
+    /// Get the exact value of the number, if possible
+    ///
+    /// Only integers and fractions without error are exact.
+    pub fn to_rational(self) -> Option<Rational> {
+        match self {
+            Number::Regular(v) => Rational::from_integer(v),
+            Number::Fraction {
+                whole,
+                num,
+                den,
+                err,
+            } => {
+                if err != 0.0 {
+                    return None;
+                }
+                let num = whole as i64 * den as i64 + num as i64;
+                Rational::new(num, den as u64)
+            }
+        }
+    }
+
+    /// Creates a number from an exact rational
+    ///
+    /// If the rational is an integer or it can't be represented as a
+    /// [`Number::Fraction`], the number will be [`Number::Regular`].
+    pub fn from_rational(r: Rational) -> Self {
+        if r.den == 1 || r.num < 0 {
+            return Self::Regular(r.value());
+        }
+        let num = r.num as u64;
+        match (
+            u32::try_from(num / r.den),
+            u32::try_from(num % r.den),
+            u32::try_from(r.den),
+        ) {
+            (Ok(whole), Ok(num), Ok(den)) => Self::Fraction {
+                whole,
+                num,
+                den,
+                err: 0.0,
+            },
+            _ => Self::Regular(r.value()),
+        }
+    }
+
+    /// Multiplies by an exact factor
+    ///
+    /// The result is a fraction only if `self` is a fraction, so a decimal
+    /// number will not become a fraction.
+    pub fn mul_rational(self, factor: Rational) -> Self {
+        self.exact_op(
+            Self::from_rational(factor),
+            self.is_fraction(),
+            Rational::checked_mul,
+            |a, b| a * b,
+        )
+    }
+
+    fn is_fraction(self) -> bool {
+        matches!(self, Self::Fraction { .. })
+    }
+
+    /// Applies an operation exactly if both numbers are exact, or with floats
+    /// otherwise
+    ///
+    /// If not `keep_fraction`, only integer results are kept exact.
+    fn exact_op(
+        self,
+        rhs: Self,
+        keep_fraction: bool,
+        exact: impl FnOnce(Rational, Rational) -> Option<Rational>,
+        float: impl FnOnce(f64, f64) -> f64,
+    ) -> Self {
+        self.to_rational()
+            .zip(rhs.to_rational())
+            .and_then(|(a, b)| exact(a, b))
+            .filter(|r| keep_fraction || r.den() == 1)
+            .map(Self::from_rational)
+            .unwrap_or_else(|| Self::Regular(float(self.value(), rhs.value())))
+    }
+}
+
+impl From<Rational> for Number {
+    fn from(value: Rational) -> Self {
+        Self::from_rational(value)
+    }
+}
+
+impl std::ops::Add for Number {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let keep_fraction = self.is_fraction() || rhs.is_fraction();
+        self.exact_op(rhs, keep_fraction, Rational::checked_add, |a, b| a + b)
+    }
+}
+
+impl std::ops::Mul for Number {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let keep_fraction = self.is_fraction() || rhs.is_fraction();
+        self.exact_op(rhs, keep_fraction, Rational::checked_mul, |a, b| a * b)
+    }
+}
+
+impl std::ops::Sub for Number {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let keep_fraction = self.is_fraction() || rhs.is_fraction();
+        self.exact_op(rhs, keep_fraction, Rational::checked_sub, |a, b| a - b)
+    }
+}
+
+impl std::ops::Div for Number {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let keep_fraction = self.is_fraction() || rhs.is_fraction();
+        self.exact_op(rhs, keep_fraction, Rational::checked_div, |a, b| a / b)
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value().partial_cmp(&other.value())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -945,4 +1618,122 @@ mod tests {
         }
         num
     }
+
+    #[test_case(1, 3, 1, 3 => (2, 3))]
+    #[test_case(1, 3, 2, 3 => (1, 1))]
+    #[test_case(1, 2, 1, 3 => (5, 6))]
+    #[test_case(-1, 2, 1, 4 => (-1, 4))]
+    fn rational_add(a: i64, b: u64, c: i64, d: u64) -> (i64, u64) {
+        let r = Rational::new(a, b)
+            .unwrap()
+            .checked_add(Rational::new(c, d).unwrap())
+            .unwrap();
+        (r.num(), r.den())
+    }
+
+    #[test_case(0.333_333_31 => Some((1, 3)))]
+    #[test_case(16.000_000_1 => Some((16, 1)))]
+    #[test_case(0.001 => Some((1, 1000)))]
+    #[test_case(236.588_236 => None; "not simple")]
+    fn rational_approx(value: f64) -> Option<(i64, u64)> {
+        Rational::approx(value, 1e-6, |den| den <= 16 || den == 1000).map(|r| (r.num(), r.den()))
+    }
+
+    #[test]
+    fn exact_number_ops() {
+        let third = Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 3,
+            err: 0.0,
+        };
+        let sum = third + third + third;
+        assert!(matches!(sum, Number::Regular(v) if v == 1.0));
+        let sum = third + Number::Regular(1.0);
+        assert!(matches!(
+            sum,
+            Number::Fraction {
+                whole: 1,
+                num: 1,
+                den: 3,
+                ..
+            }
+        ));
+        // decimals are not turned into fractions
+        let half = Rational::new(1, 2).unwrap();
+        assert!(matches!(Number::Regular(3.0).mul_rational(half), Number::Regular(v) if v == 1.5));
+        // inexact values fallback to floats
+        let inexact = Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 3,
+            err: 0.01,
+        };
+        assert!((inexact + third).to_rational().is_none());
+    }
+
+    #[test]
+    fn exact_sub_div() {
+        let third = Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 3,
+            err: 0.0,
+        };
+        let r = Number::Regular(1.0) - third;
+        assert!(matches!(
+            r,
+            Number::Fraction {
+                whole: 0,
+                num: 2,
+                den: 3,
+                ..
+            }
+        ));
+        let r = third / Number::Regular(2.0);
+        assert!(matches!(
+            r,
+            Number::Fraction {
+                whole: 0,
+                num: 1,
+                den: 6,
+                ..
+            }
+        ));
+        // decimals are not turned into fractions
+        assert!(
+            matches!(Number::Regular(1.0) / Number::Regular(4.0), Number::Regular(v) if v == 0.25)
+        );
+        // negative results are regular numbers
+        assert!(matches!(third - Number::Regular(1.0), Number::Regular(v) if v < 0.0));
+        assert!(Number::Regular(1.0) > third);
+    }
+
+    #[test_case(1, 2, 1, 3 => Some((1, 6)))]
+    #[test_case(1, 3, 1, 2 => Some((-1, 6)))]
+    fn rational_sub(a: i64, b: u64, c: i64, d: u64) -> Option<(i64, u64)> {
+        let r = Rational::new(a, b)?.checked_sub(Rational::new(c, d)?)?;
+        Some((r.num(), r.den()))
+    }
+
+    #[test_case(1, 2, 1, 3 => Some((3, 2)))]
+    #[test_case(1, 2, -1, 4 => Some((-2, 1)))]
+    #[test_case(1, 2, 0, 1 => None; "by zero")]
+    fn rational_div(a: i64, b: u64, c: i64, d: u64) -> Option<(i64, u64)> {
+        let r = Rational::new(a, b)?.checked_div(Rational::new(c, d)?)?;
+        Some((r.num(), r.den()))
+    }
+
+    #[test_case(Value::from(1.0) => true; "one")]
+    #[test_case(Value::from(2.0) => false; "two")]
+    #[test_case(Value::from(0.5) => false; "decimal")]
+    #[test_case(Value::from(0.0) => false; "zero")]
+    #[test_case(Value::Number(Number::Fraction { whole: 0, num: 1, den: 2, err: 0.0 }) => true; "fraction")]
+    #[test_case(Value::Number(Number::Fraction { whole: 1, num: 1, den: 2, err: 0.0 }) => false; "mixed fraction")]
+    #[test_case(Value::Range { start: 1.0.into(), end: 2.0.into() } => false; "range")]
+    #[test_case(Value::Range { start: 0.5.into(), end: 1.0.into() } => true; "range to one")]
+    #[test_case(Value::Text("some".into()) => false; "text")]
+    fn singular(value: Value) -> bool {
+        value.is_singular()
+    }
 }
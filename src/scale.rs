@@ -2,19 +2,28 @@
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use unicase::UniCase;
 
 use crate::{
     convert::Converter,
-    quantity::{ScalableQuantity, ScalableValue, ScaledQuantity, TextValueError, Value},
-    Cookware, Ingredient, Quantity, ScalableRecipe, ScaledRecipe, Timer,
+    error::{label, Severity, SourceDiag, SourceReport, Stage},
+    quantity::{
+        gcd, lcm, Number, QuantityAddError, Rational, ScalableQuantity, ScalableValue,
+        ScaledQuantity, TextValueError, Value,
+    },
+    Cookware, Ingredient, IngredientReferenceTarget, PanSize, Quantity, ScalableRecipe,
+    ScaledRecipe, Span, Timer,
 };
 
 /// Configures the scaling target
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct ScaleTarget {
     base: u32,
-    target: u32,
+    target: f64,
     index: Option<usize>,
+    /// Exact factor, if known
+    #[serde(skip)]
+    exact: Option<Rational>,
 }
 
 impl ScaleTarget {
@@ -31,14 +40,55 @@ impl ScaleTarget {
     fn new(base: u32, target: u32, declared_servings: &[u32]) -> Self {
         ScaleTarget {
             base,
-            target,
+            target: target as f64,
             index: declared_servings.iter().position(|&s| s == target),
+            exact: Rational::new(target as i64, base as u64),
+        }
+    }
+
+    /// Creates a new [`ScaleTarget`] from target servings that can have
+    /// decimals.
+    fn from_servings(base: u32, target: f64, declared_servings: &[u32]) -> Self {
+        if target.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&target) {
+            return Self::new(base, target as u32, declared_servings);
+        }
+        ScaleTarget {
+            base,
+            target,
+            index: None,
+            exact: None,
+        }
+    }
+
+    /// Creates a new [`ScaleTarget`] from a factor instead of the target
+    /// servings. The target servings can have decimals.
+    fn from_factor(base: u32, factor: Number, declared_servings: &[u32]) -> Self {
+        let exact = factor.to_rational();
+        let target = match exact.and_then(|f| f.checked_mul(Rational::new(base as i64, 1)?)) {
+            Some(target) => target.value(),
+            None => base as f64 * factor.value(),
+        };
+        ScaleTarget {
+            base,
+            target,
+            index: declared_servings
+                .iter()
+                .position(|&s| (s as f64 - target).abs() < 1e-9),
+            exact,
         }
     }
 
     /// Get the calculated scaling factor
     pub fn factor(&self) -> f64 {
-        self.target as f64 / self.base as f64
+        match self.exact {
+            Some(exact) => exact.value(),
+            None => self.target / self.base as f64,
+        }
+    }
+
+    /// Same as [`Self::factor`] but without losing precision
+    fn exact_factor(&self) -> Option<Rational> {
+        self.exact
     }
 
     /// Get the index into a [`ScalableValue::ByServings`]
@@ -46,17 +96,25 @@ impl ScaleTarget {
         self.index
     }
 
+    /// Get the base servings
+    pub fn base_servings(&self) -> u32 {
+        self.base
+    }
+
     /// Get the target servings
-    pub fn target_servings(&self) -> u32 {
+    ///
+    /// It can have decimals when the recipe was scaled by a factor, an
+    /// ingredient or a yield.
+    pub fn target_servings(&self) -> f64 {
         self.target
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Servings(pub(crate) Option<Vec<u32>>);
 
 /// Possible scaled states of a recipe
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Scaled {
     /// The recipe was scaled to its based servings
@@ -69,7 +127,7 @@ pub enum Scaled {
 }
 
 /// Data from scaling a recipe
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScaledData {
     /// What the target was
     pub target: ScaleTarget,
@@ -79,6 +137,10 @@ pub struct ScaledData {
     pub cookware: Vec<ScaleOutcome>,
     /// Outcome of scaling the timers. Use the same index as in the recipe.
     pub timers: Vec<ScaleOutcome>,
+    /// Outcome of scaling the inline quantities. Use the same index as in the
+    /// recipe.
+    #[serde(default)]
+    pub inline_quantities: Vec<ScaleOutcome>,
 }
 
 /// Possible outcomes from scaling a component
@@ -91,12 +153,198 @@ pub enum ScaleOutcome {
     Fixed,
     /// It has no quantity, so it can't be scaled
     NoQuantity,
+    /// Success, but the value was rounded with the converter
+    /// [`Rounding`](crate::convert::units_file::Rounding) configuration
+    Rounded {
+        /// The value before the last rounding, in the unit of the quantity
+        unrounded: Value,
+        /// The [`ScalingLaw`] used, if it's not linear
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        law: Option<ScalingLaw>,
+    },
+    /// Success, scaled with a [`ScalingLaw`] that is not linear
+    NonLinear {
+        /// The law used
+        law: ScalingLaw,
+    },
     /// Error scaling
-    Error(#[serde(skip)] ScaleError),
+    ///
+    /// The details of the error are serialized, but not deserialized, see
+    /// [`ScaleError::UndefinedError`].
+    Error(#[serde(deserialize_with = "deserialize_scale_error")] ScaleError),
+}
+
+impl ScaleOutcome {
+    /// If the value changed when scaling
+    pub(crate) fn is_scaled(&self) -> bool {
+        matches!(
+            self,
+            Self::Scaled | Self::NonLinear { .. } | Self::Rounded { .. }
+        )
+    }
+
+    /// Rounds the quantity of a scaled component and records it in the outcome
+    ///
+    /// Only quantities that were scaled are rounded, and the law of a non
+    /// linear outcome is kept. Values of discrete laws are already rounded
+    /// to their steps, so they are not changed.
+    pub(crate) fn round(&mut self, quantity: &mut ScaledQuantity, converter: &Converter) {
+        let law = match *self {
+            Self::Scaled => None,
+            Self::NonLinear { law } => Some(law),
+            Self::Rounded { law, .. } if law.is_some_and(|l| l.steps_per_unit().is_some()) => {
+                return
+            }
+            Self::Rounded { law, .. } => law,
+            _ => return,
+        };
+        let unrounded = quantity.value.clone();
+        if quantity.round(converter) {
+            *self = Self::Rounded { unrounded, law };
+        }
+    }
+}
+
+/// How a value changes with the scaling factor
+///
+/// Used with the [`SCALING_LAWS`](crate::Extensions::SCALING_LAWS) extension.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    strum::Display,
+    strum::EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ScalingLaw {
+    /// Not changed
+    Fixed,
+    /// Multiplied by the factor
+    Linear,
+    /// Multiplied by the square root of the factor, like baking times
+    Sqrt,
+    /// Multiplied by the factor and rounded up to a whole number, like the
+    /// number of pans
+    Ceil,
+    /// Multiplied by the factor and rounded to the nearest whole number, but
+    /// at least 1, like eggs
+    Whole,
+    /// Multiplied by the factor and rounded to the nearest half, but at least
+    /// 1/2, like lemons
+    Half,
+}
+
+impl ScalingLaw {
+    /// Scales a number with this law
+    fn scale(self, n: Number, target: ScaleTarget) -> Number {
+        match self {
+            Self::Fixed => n,
+            Self::Linear => linear_scale_number(n, target),
+            Self::Sqrt => (n.value() * target.factor().sqrt()).into(),
+            Self::Ceil => {
+                let v = linear_scale_number(n, target).value();
+                // remove floating point noise before rounding up
+                let v = (v * 1e6).round() / 1e6;
+                v.ceil().into()
+            }
+            Self::Whole | Self::Half => {
+                let steps = self.steps_per_unit().unwrap();
+                let v = linear_scale_number(n, target).value() * steps as f64;
+                if v <= 0.0 {
+                    return n;
+                }
+                let v = v.round().max(1.0) as i64;
+                Rational::new(v, steps).map_or((v as f64).into(), Number::from_rational)
+            }
+        }
+    }
+
+    /// How many parts a unit is divided into for laws that only allow
+    /// discrete values, like 2 with [`Self::Half`]
+    fn steps_per_unit(self) -> Option<u64> {
+        match self {
+            Self::Whole => Some(1),
+            Self::Half => Some(2),
+            _ => None,
+        }
+    }
+}
+
+/// Ingredient to scale a recipe by, used in [`ScalableRecipe::scale_by_ingredient`]
+#[derive(Debug, Clone, Copy)]
+pub enum IngredientRef<'a> {
+    /// Index in [`Recipe::ingredients`](crate::Recipe::ingredients)
+    ///
+    /// If it's a reference, the ingredient it references is used.
+    Index(usize),
+    /// Name or alias of an ingredient definition, ignoring the case
+    Name(&'a str),
+}
+
+/// Errors scaling a recipe by an ingredient
+#[derive(Debug, Error)]
+pub enum ScaleByIngredientError {
+    #[error("Ingredient not found: {0}")]
+    NotFound(String),
+
+    #[error("The ingredient has no quantity")]
+    NoQuantity,
+
+    #[error("The ingredient quantity is not scalable")]
+    NotScalable,
+
+    #[error("The available quantity is not enough")]
+    NotEnough,
+
+    #[error(transparent)]
+    Quantity(#[from] QuantityAddError),
+}
+
+/// The factor or servings to scale a recipe are not a number greater than 0
+#[derive(Debug, Error, Clone, Copy, PartialEq)]
+#[error("Invalid scale factor {0}, it has to be a number greater than 0")]
+pub struct InvalidScaleFactor(pub f64);
+
+/// Errors scaling a recipe to a pan
+#[derive(Debug, Error)]
+pub enum ScaleToPanError {
+    #[error("The recipe has no cookware with a pan size")]
+    NoPan,
+
+    #[error(transparent)]
+    Quantity(#[from] QuantityAddError),
+}
+
+/// Errors scaling a recipe to a yield
+#[derive(Debug, Error)]
+pub enum ScaleToYieldError {
+    #[error("The recipe has no valid yield")]
+    NoYield,
+
+    #[error("The target yield has to be greater than 0")]
+    InvalidTarget,
+
+    #[error(transparent)]
+    Quantity(#[from] QuantityAddError),
+}
+
+fn deserialize_scale_error<'de, D>(deserializer: D) -> Result<ScaleError, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    serde::de::IgnoredAny::deserialize(deserializer)?;
+    Ok(ScaleError::UndefinedError)
 }
 
 /// Possible errors during scaling process
-#[derive(Debug, Error, Clone, Default)]
+#[derive(Debug, Error, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum ScaleError {
     #[error(transparent)]
     TextValueError(#[from] TextValueError),
@@ -115,8 +363,8 @@ pub enum ScaleError {
 
     /// There has been an error but it can't be determined
     ///
-    /// This is used when deserializing, because serializing the [`ScaleOutcome`]
-    /// skips the error.
+    /// This is used when deserializing, because deserializing the
+    /// [`ScaleOutcome`] skips the error.
     #[default]
     #[error("Undefined scale error")]
     UndefinedError,
@@ -126,60 +374,444 @@ impl ScalableRecipe {
     /// Scale a recipe
     ///
     /// Note that this returns a [`ScaledRecipe`] wich doesn't implement this
-    /// method. A recipe can only be scaled once.
+    /// method. A recipe can only be scaled once, use [`Self::scale_ref`] to
+    /// keep the original.
     pub fn scale(self, target: u32, converter: &Converter) -> ScaledRecipe {
-        let target = if let Servings(Some(servings)) = &self.data {
-            let base = servings.first().copied().unwrap_or(1);
-            ScaleTarget::new(base, target, &servings)
+        let components = self.servings_components(target, converter);
+        self.into_scaled(components)
+    }
+
+    /// Scale a recipe without consuming it
+    ///
+    /// The result is the same as [`Self::scale`], so the original recipe can
+    /// be scaled again to other servings. Only the scaled ingredients,
+    /// cookware, timers and inline quantities are built, the rest of the
+    /// recipe is cloned. The other scaling methods take the recipe by value,
+    /// but [`ScalableRecipe`] can be cloned to keep it.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, Extensions};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse(">> servings: 2\n@flour{200*%g}")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let four = recipe.scale_ref(4, parser.converter());
+    /// let six = recipe.scale_ref(6, parser.converter());
+    /// assert_eq!(four.ingredients[0].quantity.as_ref().unwrap().to_string(), "400 g");
+    /// assert_eq!(six.ingredients[0].quantity.as_ref().unwrap().to_string(), "600 g");
+    /// ```
+    pub fn scale_ref(&self, target: u32, converter: &Converter) -> ScaledRecipe {
+        let components = self.servings_components(target, converter);
+        self.to_scaled(components)
+    }
+
+    /// Scaled components for the target servings
+    fn servings_components(&self, target: u32, converter: &Converter) -> ScaledComponents {
+        let (base, declared) = self.base_servings();
+        let target = ScaleTarget::new(base, target, declared);
+
+        if target.index() == Some(0) {
+            return self.default_components();
+        }
+
+        self.scale_components(target, converter)
+    }
+
+    /// Scale a recipe by a factor
+    ///
+    /// The factor can have decimals, so `0.5` makes half of the recipe. The
+    /// target servings are the base servings multiplied by the factor. If
+    /// they are one of the declared servings, values scaled by servings
+    /// (`@flour{100|200%g}`) use its value, otherwise they can't be scaled.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, Extensions};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse(">> servings: 4\n@flour{500*%g}")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let scaled = recipe.scale_by_factor(0.5, parser.converter()).unwrap();
+    /// let flour = scaled.ingredients[0].quantity.as_ref().unwrap();
+    /// assert_eq!(flour.to_string(), "250 g");
+    /// assert_eq!(scaled.scaled_data().unwrap().target.target_servings(), 2.0);
+    /// ```
+    pub fn scale_by_factor(
+        self,
+        factor: f64,
+        converter: &Converter,
+    ) -> Result<ScaledRecipe, InvalidScaleFactor> {
+        if factor <= 0.0 || !factor.is_finite() {
+            return Err(InvalidScaleFactor(factor));
+        }
+        let (base, declared) = self.base_servings();
+        let target = ScaleTarget::from_factor(base, Number::Regular(factor), declared);
+        Ok(self.scale_to(target, converter))
+    }
+
+    /// Scale a recipe to servings that can have decimals
+    ///
+    /// Same as [`Self::scale`] but the target can be any number greater than
+    /// 0, like `1.5`. Values scaled by servings (`@flour{100|200%g}`) can only
+    /// be scaled to one of the declared servings.
+    pub fn scale_to_servings(
+        self,
+        target: f64,
+        converter: &Converter,
+    ) -> Result<ScaledRecipe, InvalidScaleFactor> {
+        if target <= 0.0 || !target.is_finite() {
+            return Err(InvalidScaleFactor(target));
+        }
+        let (base, declared) = self.base_servings();
+        let target = ScaleTarget::from_servings(base, target, declared);
+        if target.index() == Some(0) {
+            return Ok(self.default_scale());
+        }
+        Ok(self.scale_to(target, converter))
+    }
+
+    /// Scale a recipe so it uses the available quantity of an ingredient
+    ///
+    /// The factor is the available quantity divided by the quantity of the
+    /// ingredient and its references. The quantities are converted if they
+    /// are in different units.
+    ///
+    /// - Fixed quantities of the ingredient are subtracted from the available
+    ///   quantity before dividing.
+    /// - Scaling defined by servings (`@flour{100|200%g}`) uses the quantity
+    ///   for the base servings.
+    /// - When there are ranges, the factor is the lowest possible one, so the
+    ///   available quantity is always enough.
+    ///
+    /// The resulting servings, which can have decimals, are in the
+    /// [`ScaleTarget`] of the [`ScaledData`].
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, Extensions, Quantity, Value};
+    /// # use cooklang::scale::IngredientRef;
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse(">> servings: 4\n@flour{500*%g} @eggs{2*}")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let available = Quantity::new(Value::from(1.0), Some("kg".to_string()));
+    /// let scaled = recipe
+    ///     .scale_by_ingredient(IngredientRef::Name("flour"), &available, parser.converter())
+    ///     .unwrap();
+    /// assert_eq!(scaled.scaled_data().unwrap().target.target_servings(), 8.0);
+    /// assert_eq!(scaled.ingredients[1].quantity.as_ref().unwrap().to_string(), "4");
+    /// ```
+    pub fn scale_by_ingredient(
+        self,
+        ingredient: IngredientRef,
+        available: &ScaledQuantity,
+        converter: &Converter,
+    ) -> Result<ScaledRecipe, ScaleByIngredientError> {
+        let factor = self.ingredient_factor(ingredient, available, converter)?;
+        let (base, declared) = self.base_servings();
+        let target = ScaleTarget::from_factor(base, factor, declared);
+        Ok(self.scale_to(target, converter))
+    }
+
+    /// Scale a recipe to make a target yield
+    ///
+    /// The factor is the target divided by the `yield` of the
+    /// [`Metadata`](crate::Metadata::recipe_yield), converting the target if
+    /// it's in another unit. The factor can have decimals, so `1 L` to
+    /// `750 ml` is `0.75`.
+    ///
+    /// The target servings are the base servings multiplied by the factor. If
+    /// they are one of the declared servings, values scaled by servings
+    /// (`@flour{100|200%g}`) use its value, otherwise they can't be scaled.
+    ///
+    /// If the target is a range, the lowest factor is used.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, Extensions, Quantity, Value};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse(">> yield: 2 loaves\n@flour{1*%kg} @water{700*%ml}")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let target = Quantity::new(Value::from(3.0), Some("loaves".to_string()));
+    /// let scaled = recipe.scale_to_yield(&target, parser.converter()).unwrap();
+    /// let water = scaled.ingredients[1].quantity.as_ref().unwrap();
+    /// assert_eq!(water.to_string(), "1.05 l");
+    /// ```
+    pub fn scale_to_yield(
+        self,
+        target: &ScaledQuantity,
+        converter: &Converter,
+    ) -> Result<ScaledRecipe, ScaleToYieldError> {
+        let recipe_yield = self
+            .metadata
+            .recipe_yield()
+            .ok_or(ScaleToYieldError::NoYield)?;
+        let factor = match target.ratio(recipe_yield, converter)? {
+            Value::Number(n) => n,
+            Value::Range { start, .. } => start,
+            _ => unreachable!("ratio is a number"),
+        };
+        if factor.value() <= 0.0 || !factor.value().is_finite() {
+            return Err(ScaleToYieldError::InvalidTarget);
+        }
+        let (base, declared) = self.base_servings();
+        let target = ScaleTarget::from_factor(base, factor, declared);
+        Ok(self.scale_to(target, converter))
+    }
+
+    /// Scale a recipe to be made in a pan of another size
+    ///
+    /// The factor is the area of `pan` divided by the area of the first
+    /// cookware item of the recipe with a [`PanSize`]. Round and rectangular
+    /// pans can be mixed and the lengths are converted if they are in
+    /// different units.
+    ///
+    /// The target servings are the base servings multiplied by the factor,
+    /// like in [`Self::scale_by_factor`].
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, Extensions};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse("Mix @flour{200*%g} and bake in a #cake pan{20%cm}.")
+    ///     .into_output()
+    ///     .unwrap();
+    /// let pan = "23x33 cm".parse().unwrap();
+    /// let scaled = recipe.scale_to_pan(&pan, parser.converter()).unwrap();
+    /// let flour = scaled.ingredients[0].quantity.as_ref().unwrap();
+    /// assert_eq!(flour.to_string(), "483.194 g");
+    /// ```
+    pub fn scale_to_pan(
+        self,
+        pan: &PanSize,
+        converter: &Converter,
+    ) -> Result<ScaledRecipe, ScaleToPanError> {
+        let recipe_pan = self
+            .cookware
+            .iter()
+            .find_map(|c| c.size.as_ref())
+            .ok_or(ScaleToPanError::NoPan)?;
+        let factor = pan.area_ratio(recipe_pan, converter)?;
+        let (base, declared) = self.base_servings();
+        let target = ScaleTarget::from_factor(base, Number::Regular(factor), declared);
+        Ok(self.scale_to(target, converter))
+    }
+
+    /// Nearest servings to a target that don't need rounding
+    ///
+    /// Ingredients with the `whole` or `half` [`ScalingLaw`] are rounded when
+    /// scaled. These are the servings closest to `target` that keep all of
+    /// them exact, so `@eggs{3*whole}` for 4 servings can be scaled to 4 or 8
+    /// servings but not 5 or 6. If both sides are at the same distance, the
+    /// biggest servings are returned.
+    ///
+    /// Returns [`None`] if the servings are too big.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, Extensions};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse(">> servings: 4\n@eggs{3*whole} @flour{500*%g}")
+    ///     .into_output()
+    ///     .unwrap();
+    /// assert_eq!(recipe.discrete_servings(5), Some(4));
+    /// assert_eq!(recipe.discrete_servings(7), Some(8));
+    /// ```
+    pub fn discrete_servings(&self, target: u32) -> Option<u32> {
+        let (base, _) = self.base_servings();
+        // servings have to be a multiple of this
+        let mut step: u64 = 1;
+        for igr in &self.ingredients {
+            let Some(ScalableValue::NonLinear { value, law }) =
+                igr.quantity.as_ref().map(|q| &q.value)
+            else {
+                continue;
+            };
+            let Some(parts) = law.steps_per_unit() else {
+                continue;
+            };
+            let numbers = match value {
+                Value::Number(n) => [*n, *n],
+                Value::Range { start, end } => [*start, *end],
+                _ => continue,
+            };
+            for n in numbers {
+                let r = n
+                    .to_rational()
+                    .or_else(|| Rational::approx(n.value(), 1e-9, |_| true))?;
+                // `r * servings / base * parts` is an integer when servings is a
+                // multiple of `den / gcd(num, den)`
+                let num = r.num().unsigned_abs().checked_mul(parts)?;
+                let den = r.den().checked_mul(base as u64)?;
+                step = lcm(step, den / gcd(num, den))?;
+            }
+        }
+
+        let step = u32::try_from(step).ok()?;
+        let below = target / step * step;
+        if below == target && below != 0 {
+            return Some(target);
+        }
+        let above = below.checked_add(step)?;
+        if below == 0 || above - target <= target - below {
+            Some(above)
         } else {
-            ScaleTarget::new(1, target, &[])
+            Some(below)
+        }
+    }
+
+    /// Base servings and all the declared servings
+    fn base_servings(&self) -> (u32, &[u32]) {
+        match &self.data {
+            Servings(Some(servings)) => (servings.first().copied().unwrap_or(1), servings),
+            Servings(None) => (1, &[]),
+        }
+    }
+
+    /// Factor to scale the recipe to use the available quantity of an
+    /// ingredient
+    fn ingredient_factor(
+        &self,
+        ingredient: IngredientRef,
+        available: &ScaledQuantity,
+        converter: &Converter,
+    ) -> Result<Number, ScaleByIngredientError> {
+        let index = match ingredient {
+            IngredientRef::Index(index) => {
+                let igr = self
+                    .ingredients
+                    .get(index)
+                    .ok_or_else(|| ScaleByIngredientError::NotFound(index.to_string()))?;
+                match igr.relation.references_to() {
+                    Some((def, IngredientReferenceTarget::Ingredient)) => def,
+                    _ => index,
+                }
+            }
+            IngredientRef::Name(name) => {
+                let name = UniCase::new(name.trim());
+                self.ingredients
+                    .iter()
+                    .position(|igr| {
+                        igr.relation.is_definition()
+                            && (UniCase::new(igr.name.as_str()) == name
+                                || igr.alias.as_deref().map(UniCase::new) == Some(name))
+                    })
+                    .ok_or_else(|| ScaleByIngredientError::NotFound(name.to_string()))?
+            }
         };
 
-        if target.index() == Some(0) {
-            return self.default_scale();
+        let definition = &self.ingredients[index];
+        let all =
+            std::iter::once(index).chain(definition.relation.referenced_from().iter().copied());
+        let mut linear: Option<ScaledQuantity> = None;
+        let mut fixed: Option<ScaledQuantity> = None;
+        for i in all {
+            let Some(q) = &self.ingredients[i].quantity else {
+                continue;
+            };
+            let (sum, value) = match &q.value {
+                ScalableValue::Linear(v) => (&mut linear, v),
+                ScalableValue::ByServings(v) => (&mut linear, &v[0]),
+                // approximated as linear, the factor would not be exact anyway
+                ScalableValue::NonLinear { value, .. } => (&mut linear, value),
+                ScalableValue::Fixed(v) => (&mut fixed, v),
+            };
+            let q = Quantity {
+                value: value.clone(),
+                unit: q.unit.clone(),
+            };
+            *sum = Some(match sum.take() {
+                Some(s) => s.try_add(&q, converter)?,
+                None => q,
+            });
         }
+        let linear = match (linear, &definition.quantity) {
+            (Some(linear), _) => linear,
+            (None, None) => return Err(ScaleByIngredientError::NoQuantity),
+            (None, Some(_)) => return Err(ScaleByIngredientError::NotScalable),
+        };
 
+        let available = match fixed {
+            Some(fixed) => available.try_sub(&fixed, converter)?,
+            None => available.clone(),
+        };
+        let factor = match available.ratio(&linear, converter)? {
+            Value::Number(n) => n,
+            // lowest factor so the available quantity is always enough
+            Value::Range { start, .. } => start,
+            _ => unreachable!("ratio is a number"),
+        };
+        if factor.value() <= 0.0 || !factor.value().is_finite() {
+            return Err(ScaleByIngredientError::NotEnough);
+        }
+        Ok(factor)
+    }
+
+    /// Scale a recipe to a target
+    fn scale_to(self, target: ScaleTarget, converter: &Converter) -> ScaledRecipe {
+        let components = self.scale_components(target, converter);
+        self.into_scaled(components)
+    }
+
+    /// Scale the ingredients, cookware, timers and inline quantities
+    fn scale_components(&self, target: ScaleTarget, converter: &Converter) -> ScaledComponents {
         let (ingredients, ingredient_outcomes): (Vec<_>, Vec<_>) = self
             .ingredients
-            .into_iter()
+            .iter()
             .map(|i| i.scale(target))
-            .map(|(mut i, o)| {
+            .map(|(mut i, mut o)| {
                 if let Some(q) = &mut i.quantity {
                     let _ = q.fit(converter);
+                    o.round(q, converter);
                 }
                 (i, o)
             })
             .unzip();
 
         let (cookware, cookware_outcomes): (Vec<_>, Vec<_>) =
-            self.cookware.into_iter().map(|c| c.scale(target)).unzip();
+            self.cookware.iter().map(|c| c.scale(target)).unzip();
 
         let (timers, timer_outcomes): (Vec<_>, Vec<_>) = self
             .timers
-            .into_iter()
+            .iter()
             .map(|c| c.scale(target))
-            .map(|(mut t, o)| {
+            .map(|(mut t, mut o)| {
                 if let Some(q) = &mut t.quantity {
                     let _ = q.fit(converter);
+                    o.round(q, converter);
                 }
                 (t, o)
             })
             .unzip();
 
+        let (inline_quantities, inline_outcomes): (Vec<_>, Vec<_>) = self
+            .inline_quantities
+            .iter()
+            .map(|q| q.scale(target))
+            .map(|(mut q, mut o)| {
+                if matches!(o, ScaleOutcome::Scaled | ScaleOutcome::NonLinear { .. }) {
+                    let _ = q.fit(converter);
+                }
+                o.round(&mut q, converter);
+                (q, o)
+            })
+            .unzip();
+
         let data = ScaledData {
             target,
             ingredients: ingredient_outcomes,
             cookware: cookware_outcomes,
             timers: timer_outcomes,
+            inline_quantities: inline_outcomes,
         };
 
-        ScaledRecipe {
-            metadata: self.metadata,
-            sections: self.sections,
+        ScaledComponents {
             ingredients,
             cookware,
             timers,
-            inline_quantities: self.inline_quantities,
+            inline_quantities,
             data: Scaled::Scaled(data),
         }
     }
@@ -189,48 +821,128 @@ impl ScalableRecipe {
     /// The default values are the ones written in the recipe and the first one
     /// in [`ScalableValue::ByServings`].
     pub fn default_scale(self) -> ScaledRecipe {
-        let ingredients = self
-            .ingredients
-            .into_iter()
-            .map(Scale::default_scale)
-            .collect();
-        let cookware = self
-            .cookware
-            .into_iter()
-            .map(Scale::default_scale)
-            .collect();
-        let timers = self.timers.into_iter().map(Scale::default_scale).collect();
+        let components = self.default_components();
+        self.into_scaled(components)
+    }
+
+    pub(crate) fn default_scale_ref(&self) -> ScaledRecipe {
+        self.to_scaled(self.default_components())
+    }
 
+    fn default_components(&self) -> ScaledComponents {
+        ScaledComponents {
+            ingredients: self.ingredients.iter().map(Scale::default_scale).collect(),
+            cookware: self.cookware.iter().map(Scale::default_scale).collect(),
+            timers: self.timers.iter().map(Scale::default_scale).collect(),
+            inline_quantities: self
+                .inline_quantities
+                .iter()
+                .map(Quantity::default_scale)
+                .collect(),
+            data: Scaled::DefaultScaling,
+        }
+    }
+
+    /// Build the scaled recipe moving the rest of the recipe
+    fn into_scaled(self, components: ScaledComponents) -> ScaledRecipe {
         ScaledRecipe {
             metadata: self.metadata,
             sections: self.sections,
-            ingredients,
-            cookware,
-            timers,
-            inline_quantities: self.inline_quantities,
-            data: Scaled::DefaultScaling,
+            ingredients: components.ingredients,
+            cookware: components.cookware,
+            timers: components.timers,
+            inline_quantities: components.inline_quantities,
+            source_map: self.source_map,
+            data: components.data,
+        }
+    }
+
+    /// Build the scaled recipe cloning the rest of the recipe
+    fn to_scaled(&self, components: ScaledComponents) -> ScaledRecipe {
+        ScaledRecipe {
+            metadata: self.metadata.clone(),
+            sections: self.sections.clone(),
+            ingredients: components.ingredients,
+            cookware: components.cookware,
+            timers: components.timers,
+            inline_quantities: components.inline_quantities,
+            source_map: self.source_map.clone(),
+            data: components.data,
         }
     }
 }
 
-trait Scale: Sized {
+/// Scaled parts of a recipe, the rest is the same as the [`ScalableRecipe`]
+struct ScaledComponents {
+    ingredients: Vec<Ingredient<Value>>,
+    cookware: Vec<Cookware<Value>>,
+    timers: Vec<Timer<Value>>,
+    inline_quantities: Vec<ScaledQuantity>,
+    data: Scaled,
+}
+
+trait Scale {
     type Output;
 
-    fn scale(self, target: ScaleTarget) -> (Self::Output, ScaleOutcome);
-    fn default_scale(self) -> Self::Output;
+    fn scale(&self, target: ScaleTarget) -> (Self::Output, ScaleOutcome);
+    fn default_scale(&self) -> Self::Output;
 }
 
 impl Scale for ScalableValue {
     type Output = Value;
 
-    fn scale(self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
+    fn scale(&self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
         match self {
-            Self::Fixed(value) => (value, ScaleOutcome::Fixed),
-            Self::Linear(value) => match linear_scale(value.clone(), target.factor()) {
+            Self::Fixed(value) | Self::Linear(value @ Value::Indefinite(_)) => {
+                (value.clone(), ScaleOutcome::Fixed)
+            }
+            Self::Linear(value) => match linear_scale(value.clone(), target) {
                 Ok(v) => (v, ScaleOutcome::Scaled),
-                Err(e) => (value, ScaleOutcome::Error(e)),
+                Err(e) => (value.clone(), ScaleOutcome::Error(e)),
+            },
+            &Self::NonLinear { ref value, law } if law.steps_per_unit().is_some() => {
+                // discrete values are linear but rounded
+                let unrounded = match linear_scale(value.clone(), target) {
+                    Ok(v) => v,
+                    Err(e) => return (value.clone(), ScaleOutcome::Error(e)),
+                };
+                let scaled = match *value {
+                    Value::Number(n) => Value::Number(law.scale(n, target)),
+                    Value::Range { start, end } => Value::Range {
+                        start: law.scale(start, target),
+                        end: law.scale(end, target),
+                    },
+                    ref v => v.clone(),
+                };
+                let outcome = if same_value(&scaled, &unrounded) {
+                    ScaleOutcome::Scaled
+                } else {
+                    ScaleOutcome::Rounded {
+                        unrounded,
+                        law: Some(law),
+                    }
+                };
+                (scaled, outcome)
+            }
+            &Self::NonLinear { ref value, law } => match *value {
+                Value::Number(n) => (
+                    Value::Number(law.scale(n, target)),
+                    ScaleOutcome::NonLinear { law },
+                ),
+                Value::Range { start, end } => (
+                    Value::Range {
+                        start: law.scale(start, target),
+                        end: law.scale(end, target),
+                    },
+                    ScaleOutcome::NonLinear { law },
+                ),
+                ref v @ Value::Indefinite(_) => (v.clone(), ScaleOutcome::Fixed),
+                ref v @ Value::Text(_) => (
+                    v.clone(),
+                    ScaleOutcome::Error(TextValueError(v.clone()).into()),
+                ),
             },
-            Self::ByServings(ref values) => {
+            Self::ByServings(values) => {
                 if let Some(index) = target.index {
                     let value = match values.get(index) {
                         Some(v) => v,
@@ -258,10 +970,11 @@ impl Scale for ScalableValue {
         }
     }
 
-    fn default_scale(self) -> Self::Output {
+    fn default_scale(&self) -> Self::Output {
         match self {
-            Self::Fixed(value) => value,
-            Self::Linear(value) => value,
+            Self::Fixed(value) => value.clone(),
+            Self::Linear(value) => value.clone(),
+            Self::NonLinear { value, .. } => value.clone(),
             Self::ByServings(values) => values
                 .first()
                 .expect("scalable value servings list empty")
@@ -270,14 +983,35 @@ impl Scale for ScalableValue {
     }
 }
 
-fn linear_scale(value: Value, factor: f64) -> Result<Value, ScaleError> {
+/// Compares the values of numbers, ignoring floating point noise
+fn same_value(a: &Value, b: &Value) -> bool {
+    let eq = |a: &Number, b: &Number| (a.value() - b.value()).abs() < 1e-6;
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => eq(a, b),
+        (Value::Range { start: s1, end: e1 }, Value::Range { start: s2, end: e2 }) => {
+            eq(s1, s2) && eq(e1, e2)
+        }
+        _ => a == b,
+    }
+}
+
+fn linear_scale_number(n: Number, target: ScaleTarget) -> Number {
+    match target.exact_factor() {
+        Some(factor) => n.mul_rational(factor),
+        None => (n.value() * target.factor()).into(),
+    }
+}
+
+fn linear_scale(value: Value, target: ScaleTarget) -> Result<Value, ScaleError> {
+    let scale = |n: Number| linear_scale_number(n, target);
     match value {
-        Value::Number(n) => Ok(Value::Number((n.value() * factor).into())),
+        Value::Number(n) => Ok(Value::Number(scale(n))),
         Value::Range { start, end } => {
-            let start = (start.value() * factor).into();
-            let end = (end.value() * factor).into();
+            let start = scale(start);
+            let end = scale(end);
             Ok(Value::Range { start, end })
         }
+        v @ Value::Indefinite(_) => Ok(v),
         v @ Value::Text(_) => Err(TextValueError(v).into()),
     }
 }
@@ -285,18 +1019,19 @@ fn linear_scale(value: Value, factor: f64) -> Result<Value, ScaleError> {
 impl Scale for ScalableQuantity {
     type Output = ScaledQuantity;
 
-    fn scale(self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
-        let Self { value, unit } = self;
-        let (value, outcome) = value.scale(target);
-        let scaled = ScaledQuantity { value, unit };
+    fn scale(&self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
+        let (value, outcome) = self.value.scale(target);
+        let scaled = ScaledQuantity {
+            value,
+            unit: self.unit.clone(),
+        };
         (scaled, outcome)
     }
 
-    fn default_scale(self) -> Self::Output {
-        let Self { value, unit } = self;
+    fn default_scale(&self) -> Self::Output {
         Self::Output {
-            value: value.default_scale(),
-            unit,
+            value: self.value.default_scale(),
+            unit: self.unit.clone(),
         }
     }
 }
@@ -304,27 +1039,27 @@ impl Scale for ScalableQuantity {
 impl Scale for Ingredient<ScalableValue> {
     type Output = Ingredient<Value>;
 
-    fn scale(self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
-        let (quantity, outcome) = self.quantity.map(|q| q.scale(target)).unzip();
+    fn scale(&self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
+        let (quantity, outcome) = self.quantity.as_ref().map(|q| q.scale(target)).unzip();
         let outcome = outcome.unwrap_or(ScaleOutcome::NoQuantity);
         let scaled = Ingredient {
-            name: self.name,
-            alias: self.alias,
+            name: self.name.clone(),
+            alias: self.alias.clone(),
             quantity,
-            note: self.note,
-            relation: self.relation,
+            note: self.note.clone(),
+            relation: self.relation.clone(),
             modifiers: self.modifiers,
         };
         (scaled, outcome)
     }
 
-    fn default_scale(self) -> Self::Output {
+    fn default_scale(&self) -> Self::Output {
         Ingredient {
-            name: self.name,
-            alias: self.alias,
-            quantity: self.quantity.map(Quantity::default_scale),
-            note: self.note,
-            relation: self.relation,
+            name: self.name.clone(),
+            alias: self.alias.clone(),
+            quantity: self.quantity.as_ref().map(Quantity::default_scale),
+            note: self.note.clone(),
+            relation: self.relation.clone(),
             modifiers: self.modifiers,
         }
     }
@@ -333,27 +1068,29 @@ impl Scale for Ingredient<ScalableValue> {
 impl Scale for Cookware<ScalableValue> {
     type Output = Cookware<Value>;
 
-    fn scale(self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
-        let (quantity, outcome) = self.quantity.map(|q| q.scale(target)).unzip();
+    fn scale(&self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
+        let (quantity, outcome) = self.quantity.as_ref().map(|q| q.scale(target)).unzip();
         let outcome = outcome.unwrap_or(ScaleOutcome::NoQuantity);
         let scaled = Cookware {
-            name: self.name,
-            alias: self.alias,
+            name: self.name.clone(),
+            alias: self.alias.clone(),
             quantity,
-            note: self.note,
-            relation: self.relation,
+            size: self.size.clone(),
+            note: self.note.clone(),
+            relation: self.relation.clone(),
             modifiers: self.modifiers,
         };
         (scaled, outcome)
     }
 
-    fn default_scale(self) -> Self::Output {
+    fn default_scale(&self) -> Self::Output {
         Cookware {
-            name: self.name,
-            alias: self.alias,
-            quantity: self.quantity.map(ScalableValue::default_scale),
-            note: self.note,
-            relation: self.relation,
+            name: self.name.clone(),
+            alias: self.alias.clone(),
+            quantity: self.quantity.as_ref().map(ScalableValue::default_scale),
+            size: self.size.clone(),
+            note: self.note.clone(),
+            relation: self.relation.clone(),
             modifiers: self.modifiers,
         }
     }
@@ -362,20 +1099,20 @@ impl Scale for Cookware<ScalableValue> {
 impl Scale for Timer<ScalableValue> {
     type Output = Timer<Value>;
 
-    fn scale(self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
-        let (quantity, outcome) = self.quantity.map(|q| q.scale(target)).unzip();
+    fn scale(&self, target: ScaleTarget) -> (Self::Output, ScaleOutcome) {
+        let (quantity, outcome) = self.quantity.as_ref().map(|q| q.scale(target)).unzip();
         let outcome = outcome.unwrap_or(ScaleOutcome::NoQuantity);
         let scaled = Timer {
-            name: self.name,
+            name: self.name.clone(),
             quantity,
         };
         (scaled, outcome)
     }
 
-    fn default_scale(self) -> Self::Output {
+    fn default_scale(&self) -> Self::Output {
         Timer {
-            name: self.name,
-            quantity: self.quantity.map(Quantity::default_scale),
+            name: self.name.clone(),
+            quantity: self.quantity.as_ref().map(Quantity::default_scale),
         }
     }
 }
@@ -422,4 +1159,84 @@ impl ScaledRecipe {
     pub fn is_default_scaled(&self) -> bool {
         matches!(self.data, Scaled::DefaultScaling)
     }
+
+    /// Get the errors from scaling the recipe as a [`SourceReport`]
+    ///
+    /// The errors are located with the [`SourceMap`](crate::SourceMap) of the
+    /// recipe, so the report can be printed with the source code. A recipe
+    /// with the default scaling has no errors.
+    ///
+    /// ```
+    /// # use cooklang::{CooklangParser, Converter, Extensions};
+    /// let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    /// let recipe = parser
+    ///     .parse(">> servings: 2|4\nAdd @flour{100|200%g}.")
+    ///     .unwrap_output();
+    /// let scaled = recipe.scale(3, parser.converter());
+    /// let report = scaled.scale_report();
+    /// let error = report.errors().next().unwrap();
+    /// assert_eq!(error.message, "Can't scale '100 g' of flour");
+    /// assert_eq!(error.labels[0].0.range(), 28..37);
+    /// ```
+    pub fn scale_report(&self) -> SourceReport {
+        let mut report = SourceReport::empty();
+        let Some(data) = self.scaled_data() else {
+            return report;
+        };
+        let source_map = &self.source_map;
+
+        let mut push = |err: &ScaleError, span: Option<&Span>, what: String| {
+            let mut diag =
+                SourceDiag::unlabeled(format!("Can't scale {what}"), Severity::Error, Stage::Scale)
+                    .set_source(err.clone());
+            if let Some(span) = span {
+                diag.add_label(label!(span, "this quantity"));
+            }
+            report.error(diag);
+        };
+
+        for (index, err) in outcome_errors(&data.ingredients) {
+            let igr = &self.ingredients[index];
+            let what = match &igr.quantity {
+                Some(q) => format!("'{q}' of {}", igr.display_name()),
+                None => igr.display_name().into_owned(),
+            };
+            push(err, source_map.ingredients.get(index), what);
+        }
+        for (index, err) in outcome_errors(&data.cookware) {
+            let cw = &self.cookware[index];
+            let what = match &cw.quantity {
+                Some(q) => format!("'{q}' of {}", cw.display_name()),
+                None => cw.display_name().to_string(),
+            };
+            push(err, source_map.cookware.get(index), what);
+        }
+        for (index, err) in outcome_errors(&data.timers) {
+            let timer = &self.timers[index];
+            let what = match (&timer.quantity, &timer.name) {
+                (Some(q), Some(name)) => format!("'{q}' of timer {name}"),
+                (Some(q), None) => format!("timer '{q}'"),
+                (None, Some(name)) => format!("timer {name}"),
+                (None, None) => "timer".to_string(),
+            };
+            push(err, source_map.timers.get(index), what);
+        }
+        for (index, err) in outcome_errors(&data.inline_quantities) {
+            let what = format!("inline quantity '{}'", self.inline_quantities[index]);
+            push(err, source_map.inline_quantities.get(index), what);
+        }
+
+        report
+    }
+}
+
+/// Errors in the scale outcomes with their index
+fn outcome_errors(outcomes: &[ScaleOutcome]) -> impl Iterator<Item = (usize, &ScaleError)> {
+    outcomes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, outcome)| match outcome {
+            ScaleOutcome::Error(err) => Some((index, err)),
+            _ => None,
+        })
 }
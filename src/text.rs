@@ -90,6 +90,27 @@ impl<'a> Text<'a> {
         self.data.span()
     }
 
+    /// Get the offset in the original input of an offset in [`Self::text`]
+    ///
+    /// Offsets inside a soft break are at the start of it.
+    pub(crate) fn source_offset(&self, offset: usize) -> usize {
+        let mut consumed = 0;
+        for f in self.fragments() {
+            let len = match f.kind {
+                TextFragmentKind::Text => f.text.len(),
+                TextFragmentKind::SoftBreak => 1,
+            };
+            if offset < consumed + len {
+                return match f.kind {
+                    TextFragmentKind::Text => f.start() + offset - consumed,
+                    TextFragmentKind::SoftBreak => f.start(),
+                };
+            }
+            consumed += len;
+        }
+        self.span().end()
+    }
+
     /// Get the text of all the fragments concatenated
     ///
     /// A soft break is always rendered as a ascii whitespace.
@@ -145,7 +166,7 @@ impl<'a> Text<'a> {
     }
 
     /// Convenience method to the the text in [`Located`]
-    pub fn located_text_trimmed(&self) -> Located<Cow<str>> {
+    pub fn located_text_trimmed(&self) -> Located<Cow<'_, str>> {
         Located::new(self.text_trimmed(), self.span())
     }
 
@@ -0,0 +1,213 @@
+use cooklang::{
+    bakers_percentages::{BakersPercentagesError, Flour},
+    scale::ScaleOutcome,
+    Converter, CooklangParser, Extensions, Quantity, ScalableRecipe, ScaledRecipe, Value,
+};
+use test_case::test_case;
+
+fn parse(input: &str) -> ScalableRecipe {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    parser.parse(input).unwrap_output()
+}
+
+fn percentages(recipe: &ScaledRecipe, flour: Flour) -> Vec<(String, bool, f64)> {
+    recipe
+        .bakers_percentages(flour, &Converter::bundled())
+        .unwrap()
+        .ingredients
+        .into_iter()
+        .map(|p| {
+            (
+                recipe.ingredients[p.index].name.clone(),
+                p.is_flour,
+                (p.percentage * 100.0).round() / 100.0,
+            )
+        })
+        .collect()
+}
+
+fn q(recipe: &ScaledRecipe, index: usize) -> String {
+    recipe.ingredients[index]
+        .quantity
+        .as_ref()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn percentages_by_name() {
+    let recipe = parse("@Bread Flour{450%g} @whole wheat|ww{50%g} @water{350%g} @yeast{0.01%kg}")
+        .default_scale();
+    assert_eq!(
+        percentages(&recipe, Flour::Names(&["bread flour", "WW"])),
+        [
+            ("Bread Flour".to_string(), true, 90.0),
+            ("whole wheat".to_string(), true, 10.0),
+            ("water".to_string(), false, 70.0),
+            ("yeast".to_string(), false, 2.0),
+        ]
+    );
+}
+
+#[test_case(">> flour: bread flour, rye\n" ; "comma separated")]
+#[test_case("---\nflour:\n  - bread flour\n  - rye\n---\n" ; "yaml sequence")]
+fn percentages_by_metadata(metadata: &str) {
+    let recipe = parse(&format!(
+        "{metadata}@bread flour{{1%lb}} @rye{{1%lb}} @salt{{18.1437%g}}"
+    ))
+    .default_scale();
+    let result = recipe
+        .bakers_percentages(Flour::MetadataKey("flour"), &Converter::bundled())
+        .unwrap();
+    assert_eq!(result.flour.to_string(), "2 lb");
+    assert_eq!(
+        percentages(&recipe, Flour::MetadataKey("flour")),
+        [
+            ("bread flour".to_string(), true, 50.0),
+            ("rye".to_string(), true, 50.0),
+            ("salt".to_string(), false, 2.0),
+        ]
+    );
+}
+
+#[test]
+fn references_grouped() {
+    let recipe =
+        parse("@flour{400%g} @water{300%g}, then @&flour{100%g} and @&water{50%g}").default_scale();
+    let result = recipe
+        .bakers_percentages(Flour::Names(&["flour"]), &Converter::bundled())
+        .unwrap();
+    assert_eq!(result.flour.to_string(), "500 g");
+    assert_eq!(
+        percentages(&recipe, Flour::Names(&["flour"])),
+        [
+            ("flour".to_string(), true, 100.0),
+            ("water".to_string(), false, 70.0),
+        ]
+    );
+}
+
+#[test]
+fn without_mass_skipped() {
+    let recipe = parse("@flour{500%g} @water{350%ml} @eggs{2} @salt{a pinch}").default_scale();
+    assert_eq!(
+        percentages(&recipe, Flour::Names(&["flour"])),
+        [("flour".to_string(), true, 100.0)]
+    );
+}
+
+#[test]
+fn range_lowest_value() {
+    let recipe = parse("@flour{500%g} @water{350-400%g} @salt{10-12%g}").default_scale();
+    assert_eq!(
+        percentages(&recipe, Flour::Names(&["flour"])),
+        [
+            ("flour".to_string(), true, 100.0),
+            ("water".to_string(), false, 70.0),
+            ("salt".to_string(), false, 2.0),
+        ]
+    );
+}
+
+#[test_case("@flour{2%cups} @water{300%g}" ; "no mass")]
+#[test_case("@flour{500%g} @&flour{1%cup} @water{300%g}" ; "part without mass")]
+fn flour_without_mass(input: &str) {
+    let recipe = parse(input).default_scale();
+    let err = recipe
+        .bakers_percentages(Flour::Names(&["flour"]), &Converter::bundled())
+        .unwrap_err();
+    assert!(matches!(err, BakersPercentagesError::WithoutMass(name) if name == "flour"));
+}
+
+#[test_case(Flour::Names(&["flour"]) ; "names")]
+#[test_case(Flour::MetadataKey("flour") ; "metadata key")]
+fn no_flour(flour: Flour) {
+    let recipe = parse("@water{300%g} @salt{5%g}").default_scale();
+    let err = recipe
+        .bakers_percentages(flour, &Converter::bundled())
+        .unwrap_err();
+    assert!(matches!(err, BakersPercentagesError::NoFlour));
+}
+
+#[test]
+fn reverse_same_percentages() {
+    let converter = Converter::bundled();
+    let recipe = parse(">> servings: 2\n@flour{400*%g} @&flour{100*%g} @water{350%g} @eggs{2*}");
+    let percentages = recipe
+        .clone()
+        .default_scale()
+        .bakers_percentages(Flour::Names(&["flour"]), &converter)
+        .unwrap();
+    let flour = Quantity::new(Value::from(1.0), Some("kg".to_string()));
+    let scaled = recipe
+        .scale_to_bakers_percentages(&flour, &percentages, &converter)
+        .unwrap();
+
+    assert_eq!(q(&scaled, 0), "800 g");
+    assert_eq!(q(&scaled, 1), "200 g");
+    // fixed, but changed to keep the percentage
+    assert_eq!(q(&scaled, 2), "700 g");
+    // not a mass, only scaled
+    assert_eq!(q(&scaled, 3), "4");
+
+    let data = scaled.scaled_data().unwrap();
+    assert_eq!(data.target.target_servings(), 4.0);
+    assert!(data
+        .ingredients
+        .iter()
+        .all(|o| matches!(o, ScaleOutcome::Scaled)));
+}
+
+#[test]
+fn reverse_changed_percentages() {
+    let converter = Converter::bundled();
+    let recipe = parse("@flour{500*%g} @water{350*%g} @salt{10*%g}");
+    let mut percentages = recipe
+        .clone()
+        .default_scale()
+        .bakers_percentages(Flour::Names(&["flour"]), &converter)
+        .unwrap();
+    percentages.ingredients[1].percentage = 80.0;
+    percentages.ingredients.remove(2);
+
+    let flour = Quantity::new(Value::from(250.0), Some("g".to_string()));
+    let scaled = recipe
+        .scale_to_bakers_percentages(&flour, &percentages, &converter)
+        .unwrap();
+    assert_eq!(q(&scaled, 0), "250 g");
+    assert_eq!(q(&scaled, 1), "200 g");
+    // not in the percentages, only scaled
+    assert_eq!(q(&scaled, 2), "5 g");
+}
+
+#[test]
+fn reverse_range() {
+    let converter = Converter::bundled();
+    let recipe = parse("@flour{500*%g} @water{350-400%g}");
+    let percentages = recipe
+        .clone()
+        .default_scale()
+        .bakers_percentages(Flour::Names(&["flour"]), &converter)
+        .unwrap();
+    let flour = Quantity::new(Value::from(1.0), Some("kg".to_string()));
+    let scaled = recipe
+        .scale_to_bakers_percentages(&flour, &percentages, &converter)
+        .unwrap();
+    assert_eq!(q(&scaled, 1), "700-800 g");
+}
+
+#[test_case(Quantity::new(Value::from(0.0), Some("g".to_string())) ; "zero")]
+#[test_case(Quantity::new(Value::from(1.0), Some("l".to_string())) ; "not mass")]
+#[test_case(Quantity::new(Value::Text("some".to_string()), None) ; "text")]
+fn reverse_invalid_flour(flour: cooklang::ScaledQuantity) {
+    let converter = Converter::bundled();
+    let recipe = parse("@flour{500*%g} @water{350*%g}");
+    let percentages = recipe
+        .clone()
+        .default_scale()
+        .bakers_percentages(Flour::Names(&["flour"]), &converter)
+        .unwrap();
+    assert!(recipe
+        .scale_to_bakers_percentages(&flour, &percentages, &converter)
+        .is_err());
+}
@@ -171,10 +171,12 @@ impl TestValue {
             ScalableValue::Fixed(value) => match value {
                 Value::Number(num) => TestValue::Number(num.value()),
                 Value::Range { .. } => panic!("unexpected range value"),
+                Value::Indefinite(_) => panic!("unexpected indefinite value"),
                 Value::Text(value) => TestValue::Text(value),
             },
             ScalableValue::Linear(_) => panic!("unexpected linear value"),
             ScalableValue::ByServings(_) => panic!("unexpected value by servings"),
+            ScalableValue::NonLinear { .. } => panic!("unexpected non linear value"),
         }
     }
 }
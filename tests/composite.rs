@@ -0,0 +1,62 @@
+use cooklang::{Converter, CooklangParser, Extensions, Quantity, ScalableValue, Value};
+use test_case::test_case;
+
+#[test_case("@meat{1 lb 4 oz}" => "20 oz")]
+#[test_case("@meat{2 lb 3 oz}" => "35 oz")]
+#[test_case("@meat{1 kg 500 g}" => "1500 g")]
+#[test_case("@milk{1 cup 2 tbsp}" => "18 tbsp")]
+#[test_case("@rice{2 cups 3 tbsp}" => "35 tbsp")]
+#[test_case("@meat{1 lb}" => "1 lb")]
+fn parse_normalized(input: &str) -> String {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser.parse(input).unwrap_output();
+    recipe.ingredients[0].quantity.as_ref().unwrap().to_string()
+}
+
+#[test]
+fn parse_timer() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser.parse("Bake for ~{1 h 30 min}.").unwrap_output();
+    assert_eq!(
+        recipe.timers[0].quantity.as_ref().unwrap().to_string(),
+        "90 min"
+    );
+}
+
+#[test_case("@tomatoes{1 can 14 oz}" => ("1 can 14 oz".to_string(), "unknown unit 'can'".to_string()); "unknown unit")]
+#[test_case("@x{1 large 2 small}" => ("1 large 2 small".to_string(), "unknown unit 'large'".to_string()); "all unknown")]
+#[test_case("@meat{1 lb 4 ml}" => ("1 lb 4 ml".to_string(), "incompatible units 'lb' and 'ml'".to_string()); "mixed quantities")]
+#[test_case("@x{1 lb 4 l}" => ("1 lb 4 l".to_string(), "incompatible units 'lb' and 'l'".to_string()); "mixed symbols")]
+#[test_case("@meat{1 C 4 F}" => ("1 C 4 F".to_string(), "'C' can't be added".to_string()); "temperature")]
+fn parse_fallback(input: &str) -> (String, String) {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let (recipe, report) = parser.parse(input).into_tuple();
+    assert_eq!(report.errors().count(), 0);
+    let warnings = report.warnings().collect::<Vec<_>>();
+    assert_eq!(warnings.len(), 1);
+    let reason = warnings[0]
+        .message
+        .strip_prefix("Not a composite quantity: ")
+        .unwrap()
+        .to_string();
+    let q = recipe.unwrap().ingredients[0].quantity.clone().unwrap();
+    assert_eq!(q.value, ScalableValue::Fixed(Value::from(1.0)));
+    (q.to_string(), reason)
+}
+
+#[test_case(90.0, "min" => Some("1 h 30 min".to_string()))]
+#[test_case(60.0, "min" => Some("1 h".to_string()))]
+#[test_case(45.0, "min" => Some("45 min".to_string()))]
+#[test_case(1.5, "d" => Some("1 d 12 h".to_string()))]
+#[test_case(35.0, "oz" => Some("2 lb 3 oz".to_string()))]
+#[test_case(1.25, "lb" => Some("1 lb 4 oz".to_string()))]
+#[test_case(1.3, "lb" => Some("1 lb 5 oz".to_string()))]
+#[test_case(63.0, "in" => Some("5 ft 3 in".to_string()))]
+#[test_case(1500.0, "g" => None; "disabled in metric")]
+#[test_case(180.0, "F" => None; "not enabled")]
+#[test_case(1.0, "bag" => None; "unknown unit")]
+fn to_composite(value: f64, unit: &str) -> Option<String> {
+    let converter = Converter::bundled();
+    let q = Quantity::new(Value::from(value), Some(unit.to_string()));
+    q.to_composite(&converter).map(|c| c.to_string())
+}
@@ -0,0 +1,99 @@
+use cooklang::{
+    quantity::ScalableValue, scale::ScalingLaw, Converter, CooklangParser, Extensions, UnitInfo,
+    Value,
+};
+use test_case::test_case;
+
+fn parse_timer(input: &str, extensions: Extensions) -> String {
+    let parser = CooklangParser::new(extensions, Converter::bundled());
+    let result = parser.parse(input);
+    assert!(!result.report().has_errors(), "{:?}", result.report());
+    let recipe = result.unwrap_output();
+    recipe.timers[0].quantity.as_ref().unwrap().to_string()
+}
+
+#[test_case("~{PT1H30M}" => "90 min")]
+#[test_case("~{PT2H}" => "2 h")]
+#[test_case("~{pt45s}" => "45 s"; "lowercase")]
+#[test_case("~{P1DT12H}" => "36 h")]
+#[test_case("~{1:30}" => "90 min")]
+#[test_case("~{1:30:15}" => "5415 s")]
+#[test_case("~{0:45:00}" => "45 min")]
+#[test_case("~rest{PT10M}" => "10 min"; "named")]
+#[test_case("~{10%min}" => "10 min"; "regular")]
+fn timer(input: &str) -> String {
+    parse_timer(input, Extensions::all())
+}
+
+#[test]
+fn known_unit() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser.parse("~{PT1H30M}").unwrap_output();
+    let unit = recipe.timers[0].quantity.as_ref().unwrap().unit().unwrap();
+    assert!(matches!(
+        unit.unit_info_or_parse(parser.converter()),
+        UnitInfo::Known(_)
+    ));
+}
+
+#[test]
+fn empty_converter() {
+    let parser = CooklangParser::new(Extensions::DURATIONS, Converter::empty());
+    let recipe = parser.parse("~{PT1H30M}").unwrap_output();
+    let q = recipe.timers[0].quantity.as_ref().unwrap();
+    assert_eq!(q.to_string(), "90 min");
+}
+
+#[test]
+fn disabled() {
+    let parser = CooklangParser::new(
+        Extensions::all() ^ Extensions::DURATIONS,
+        Converter::bundled(),
+    );
+    let result = parser.parse("~{PT1H30M}");
+    assert!(result.report().has_errors());
+}
+
+#[test]
+fn invalid() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let result = parser.parse("~{1:75}");
+    let error = result.report().errors().next().unwrap();
+    assert!(error.message.contains("missing unit"));
+}
+
+#[test]
+fn metadata() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser
+        .parse(">> prep time: PT15M\n>> cook time: 1:05\nStep.")
+        .unwrap_output();
+    let time = recipe.metadata.time(parser.converter()).unwrap();
+    assert_eq!(time.total(), 80);
+}
+
+#[test_case("~{PT10M}" ; "iso")]
+#[test_case("~{0:10}" ; "clock")]
+#[test_case("~{10%min}" ; "regular")]
+fn timer_scaling(input: &str) {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser
+        .parse(&format!(">> [timer scaling]: sqrt\n{input}"))
+        .unwrap_output();
+    let value = &recipe.timers[0].quantity.as_ref().unwrap().value;
+    assert_eq!(
+        value,
+        &ScalableValue::NonLinear {
+            value: Value::from(10.0),
+            law: ScalingLaw::Sqrt
+        }
+    );
+}
+
+#[test]
+fn unknown_unit() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::empty());
+    let result = parser.parse("~{PT1H30M}");
+    let error = result.report().errors().next().unwrap();
+    assert!(error.message.contains("Unknown timer unit"));
+}
@@ -0,0 +1,102 @@
+use cooklang::{
+    convert::{ConverterBuilder, UnitsFile},
+    format::{Formatter, UnitStyle},
+    Converter, CooklangParser, Extensions, Quantity, Value,
+};
+use test_case::test_case;
+
+fn spanish_converter() -> Converter {
+    let spanish: UnitsFile =
+        toml::from_str(include_str!("../units/spanish.toml")).expect("spanish units");
+    ConverterBuilder::new()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(spanish)
+        .unwrap()
+        .finish()
+        .unwrap()
+}
+
+#[test_case(1.5, "kg", UnitStyle::Symbol => "1,5 kg")]
+#[test_case(2.0, "tbsp", UnitStyle::Name => "2 cucharadas")]
+#[test_case(1.0, "cup", UnitStyle::Name => "1 taza")]
+#[test_case(0.25, "l", UnitStyle::Name => "0,25 litros")]
+fn spanish(value: f64, unit: &str, style: UnitStyle) -> String {
+    let converter = spanish_converter();
+    let q = Quantity::new(Value::from(value), Some(unit.to_string()));
+    Formatter::for_locale("es")
+        .with_unit_style(style)
+        .quantity(&q, &converter)
+}
+
+#[test]
+fn grouped() {
+    let converter = Converter::bundled();
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser
+        .parse("@water{1.5%l} @&water{2%tbsp} @&water{1%bag}")
+        .unwrap_output()
+        .default_scale();
+    let grouped = recipe.group_ingredients(&converter);
+    let formatter = Formatter::for_locale("de").with_unit_style(UnitStyle::Name);
+    assert_eq!(
+        formatter.grouped_quantity(&grouped[0].quantity, &converter),
+        "1,53 liters, 1 bag"
+    );
+}
+
+#[test]
+fn step() {
+    let converter = Converter::bundled();
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser
+        .parse("Add @milk{1.5%cup} to the #pot and heat to 80.5 °C for ~{2.5%min}.")
+        .unwrap_output()
+        .default_scale();
+    let step = recipe.sections[0].content[0].unwrap_step();
+    let formatter = Formatter::for_locale("fr").with_unit_style(UnitStyle::Symbol);
+    assert_eq!(
+        formatter.step(step, &recipe, &converter),
+        "Add milk to the pot and heat to 80,5 °C for 2,5 min."
+    );
+}
+
+#[test_case("@milk{2%cup}" => "2 cups")]
+#[test_case("@milk{1%cups}" => "1 cup")]
+#[test_case("@milk{1/2%cups}" => "1/2 cup")]
+#[test_case("@milk{1.5%cup}" => "1.5 cups")]
+#[test_case("@milk{1-2%cup}" => "1-2 cups")]
+#[test_case("@milk{1/2-1%cups}" => "1/2-1 cup")]
+#[test_case("@milk{2%c}" => "2 c"; "symbol")]
+#[test_case("@milk{2%litre}" => "2 litre"; "not a form")]
+#[test_case("@milk{2%bag}" => "2 bag"; "unknown")]
+fn plural_display(input: &str) -> String {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser.parse(input).unwrap_output();
+    recipe.ingredients[0].quantity.as_ref().unwrap().to_string()
+}
+
+#[test_case(None => "2 cups")]
+#[test_case(Some("es") => "2 tazas")]
+#[test_case(Some("es-MX") => "2 tazas"; "language fallback")]
+#[test_case(Some("de") => "2 cups"; "no locale forms")]
+fn locale_forms(locale: Option<&str>) -> String {
+    let units: UnitsFile = toml::from_str(
+        r#"
+        [extend.units]
+        cup = { locales = { es = { singular = "taza", plural = "tazas" } } }
+        "#,
+    )
+    .unwrap();
+    let converter = ConverterBuilder::new()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(units)
+        .unwrap()
+        .finish()
+        .unwrap();
+    let q = Quantity::new(Value::from(2.0), Some("tazas".to_string()));
+    let mut formatter = Formatter::new().with_unit_style(UnitStyle::Name);
+    formatter.locale = locale.map(String::from);
+    formatter.quantity(&q, &converter)
+}
@@ -1,4 +1,8 @@
-use cooklang::{convert::System, Converter, Quantity, Value};
+use cooklang::{
+    convert::{ConverterBuilder, System},
+    quantity::Number,
+    Converter, Quantity, Value,
+};
 use test_case::test_case;
 
 #[test_case(2.0, "tsp" => "2 tsp")]
@@ -15,3 +19,137 @@ fn imperial(value: f64, unit: &str) -> String {
     let _ = q.convert(System::Imperial, &converter);
     q.to_string()
 }
+
+/// Bundled units with the readable fit for volume
+fn readable_converter(split: bool) -> Converter {
+    let readable = toml::from_str(&format!(
+        r#"
+        [[quantity]]
+        quantity = "volume"
+        fit = {{ strategy = "readable", split = {split} }}
+        "#
+    ))
+    .unwrap();
+    ConverterBuilder::new()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(readable)
+        .unwrap()
+        .finish()
+        .unwrap()
+}
+
+#[test_case(16.0, "tsp" => "1/3 c")]
+#[test_case(7.0, "tsp" => "2 1/3 tbsp")]
+#[test_case(0.333, "cup" => "1/3 c")]
+#[test_case(1500.0, "ml" => "1.5 l")]
+#[test_case(250.0, "ml" => "250 ml")]
+fn readable_fit(value: f64, unit: &str) -> String {
+    let converter = readable_converter(false);
+    let mut q = Quantity::new(Value::from(value), Some(unit.to_string()));
+    q.fit(&converter).unwrap();
+    q.to_string()
+}
+
+#[test_case(5.0, "tbsp" => "1/4 c + 1 tbsp")]
+#[test_case(3.0, "tsp" => "1 tbsp")]
+#[test_case(7.0, "tsp" => "2 1/3 tbsp")]
+#[test_case(1.5, "cup" => "1 1/2 c")]
+#[test_case(2.0, "lb" => "2 lb"; "not enabled")]
+fn fit_split(value: f64, unit: &str) -> String {
+    let converter = readable_converter(true);
+    let q = Quantity::new(Value::from(value), Some(unit.to_string()));
+    q.fit_split(&converter).unwrap().to_string()
+}
+
+#[test]
+fn bundled_fit_not_split() {
+    let converter = Converter::bundled();
+    let q = Quantity::new(Value::from(5.0), Some("tbsp".to_string()));
+    assert_eq!(q.fit_split(&converter).unwrap().to_string(), "5 tbsp");
+}
+
+#[test_case("@milk{1/3*%cup}", 3 => "1 c")]
+#[test_case("@milk{2/3*%cup}", 3 => "2 c")]
+#[test_case("@milk{1/3*%cup}", 2 => "2/3 c")]
+#[test_case("@eggs{1*}", 3 => "3")]
+#[test_case("@eggs{1*}", 2 => "2"; "integer")]
+#[test_case("@flour{100*%g}", 3 => "300 g")]
+fn exact_scale(input: &str, target: u32) -> String {
+    let parser = cooklang::CooklangParser::new(cooklang::Extensions::all(), Converter::bundled());
+    let recipe = parser
+        .parse(&format!(">> servings: 1\n{input}"))
+        .unwrap_output()
+        .scale(target, parser.converter());
+    let q = recipe.ingredients[0].quantity.as_ref().unwrap();
+    if let Value::Number(n) = q.value {
+        assert!(n.to_rational().is_some(), "not exact: {n:?}");
+    }
+    q.to_string()
+}
+
+#[test]
+fn exact_scale_decimal_factor() {
+    let parser = cooklang::CooklangParser::new(cooklang::Extensions::all(), Converter::bundled());
+    let recipe = parser
+        .parse(">> servings: 2\n@eggs{1*} @milk{1/2*%cup}")
+        .unwrap_output()
+        .scale(3, parser.converter());
+    let q = |i: usize| recipe.ingredients[i].quantity.as_ref().unwrap().to_string();
+    assert_eq!(q(0), "1.5");
+    assert_eq!(q(1), "3/4 c");
+}
+
+#[test]
+fn exact_add() {
+    let converter = Converter::bundled();
+    let third = Quantity::new(
+        Value::Number(Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 3,
+            err: 0.0,
+        }),
+        Some("cup".to_string()),
+    );
+    let total = third
+        .try_add(&third, &converter)
+        .unwrap()
+        .try_add(&third, &converter)
+        .unwrap();
+    let Value::Number(n) = total.value else {
+        panic!("not a number")
+    };
+    assert_eq!(n.value(), 1.0);
+    assert_eq!(total.to_string(), "1 cup");
+}
+
+#[test_case(1.0, "tbsp", "tsp" => "3 tsp")]
+#[test_case(2.0, "tsp", "tbsp" => "2/3 tbsp")]
+#[test_case(1.5, "lb", "oz" => "24 oz")]
+#[test_case(250.0, "g", "kg" => "0.25 kg")]
+fn exact_convert(value: f64, from: &str, to: &str) -> String {
+    let converter = Converter::bundled();
+    let mut q = Quantity::new(Value::from(value), Some(from.to_string()));
+    q.convert(to, &converter).unwrap();
+    q.to_string()
+}
+
+#[test]
+fn exact_convert_third() {
+    let converter = Converter::bundled();
+    let mut q = Quantity::new(
+        Value::Number(Number::Fraction {
+            whole: 0,
+            num: 1,
+            den: 3,
+            err: 0.0,
+        }),
+        Some("tbsp".to_string()),
+    );
+    q.convert("tsp", &converter).unwrap();
+    let Value::Number(n) = q.value else {
+        panic!("not a number")
+    };
+    assert_eq!(n.value(), 1.0);
+}
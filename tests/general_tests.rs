@@ -1,4 +1,4 @@
-use cooklang::{Content, CooklangParser, Extensions, Item};
+use cooklang::{quantity::ScalableValue, Content, CooklangParser, Extensions, Item};
 use indoc::indoc;
 use test_case::test_case;
 
@@ -203,9 +203,15 @@ fn multiple_temperatures() {
     let parser = CooklangParser::new(Extensions::all(), Default::default());
     let r = parser.parse(input).unwrap_output();
     assert_eq!(r.inline_quantities.len(), 2);
-    assert_eq!(r.inline_quantities[0].value, 2.0.into());
+    assert_eq!(
+        r.inline_quantities[0].value,
+        ScalableValue::Fixed(2.0.into())
+    );
     assert_eq!(r.inline_quantities[0].unit_text(), Some("ºC"));
-    assert_eq!(r.inline_quantities[1].value, 150.0.into());
+    assert_eq!(
+        r.inline_quantities[1].value,
+        ScalableValue::Fixed(150.0.into())
+    );
     assert_eq!(r.inline_quantities[1].unit_text(), Some("F"));
     let Content::Step(first_step) = &r.sections[0].content[0] else {
         panic!()
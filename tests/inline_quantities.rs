@@ -0,0 +1,128 @@
+use cooklang::{
+    convert::System, quantity::ScalableValue, scale::ScaleOutcome, Content, Converter,
+    CooklangParser, Extensions, Item, ScalableRecipe, ScaledRecipe,
+};
+use test_case::test_case;
+
+fn parse(input: &str) -> ScalableRecipe {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    parser.parse(input).unwrap_output()
+}
+
+fn inline(recipe: &ScaledRecipe, index: usize) -> String {
+    recipe.inline_quantities[index].to_string()
+}
+
+#[test]
+fn parsed() {
+    let recipe = parse("Divide into {4*%portions} and bake at 180 °C.");
+    assert_eq!(recipe.inline_quantities.len(), 2);
+    assert!(matches!(
+        recipe.inline_quantities[0].value,
+        ScalableValue::Linear(_)
+    ));
+    assert_eq!(recipe.inline_quantities[0].unit_text(), Some("portions"));
+    assert!(matches!(
+        recipe.inline_quantities[1].value,
+        ScalableValue::Fixed(_)
+    ));
+
+    let Content::Step(step) = &recipe.sections[0].content[0] else {
+        panic!("not a step");
+    };
+    assert_eq!(
+        step.items,
+        [
+            Item::Text {
+                value: "Divide into ".into()
+            },
+            Item::InlineQuantity { index: 0 },
+            Item::Text {
+                value: " and bake at ".into()
+            },
+            Item::InlineQuantity { index: 1 },
+            Item::Text { value: ".".into() },
+        ]
+    );
+}
+
+#[test_case("Use {} here" ; "empty")]
+#[test_case("Not closed { here" ; "not closed")]
+#[test_case("Mix {well} and serve" ; "word")]
+#[test_case(r#"Print {"a": 1} to the console"# ; "json")]
+#[test_case("Add {2} eggs" ; "number without unit")]
+fn text(input: &str) {
+    let recipe = parse(input);
+    assert!(recipe.inline_quantities.is_empty());
+    let Content::Step(step) = &recipe.sections[0].content[0] else {
+        panic!("not a step");
+    };
+    let text = step
+        .items
+        .iter()
+        .map(|item| match item {
+            Item::Text { value } => value.as_str(),
+            _ => panic!("not text"),
+        })
+        .collect::<String>();
+    assert_eq!(text, input);
+}
+
+#[test]
+fn disabled() {
+    let parser = CooklangParser::new(
+        Extensions::all() ^ Extensions::INLINE_QUANTITIES,
+        Converter::bundled(),
+    );
+    let recipe = parser.parse("Divide into {4*%portions}.").unwrap_output();
+    assert!(recipe.inline_quantities.is_empty());
+}
+
+#[test]
+fn scaled() {
+    let recipe = parse(
+        ">> servings: 2\nDivide @dough{500*%g} into {4*%portions} of {125*%g} and bake at 180 °C for {20%min}.",
+    );
+    let recipe = recipe.scale(3, &Converter::bundled());
+    assert_eq!(inline(&recipe, 0), "6 portions");
+    assert_eq!(inline(&recipe, 1), "187.5 g");
+    assert_eq!(inline(&recipe, 2), "180 °C");
+    assert_eq!(inline(&recipe, 3), "20 min");
+
+    let data = recipe.scaled_data().unwrap();
+    assert_eq!(data.inline_quantities.len(), 4);
+    assert!(matches!(data.inline_quantities[0], ScaleOutcome::Scaled));
+    assert!(matches!(data.inline_quantities[1], ScaleOutcome::Scaled));
+    assert!(matches!(data.inline_quantities[2], ScaleOutcome::Fixed));
+    assert!(matches!(data.inline_quantities[3], ScaleOutcome::Fixed));
+}
+
+#[test]
+fn default_scaled() {
+    let recipe = parse(">> servings: 2\nDivide into {4*%portions}.");
+    let recipe = recipe.scale(2, &Converter::bundled());
+    assert!(recipe.scaled_data().is_none());
+    assert_eq!(inline(&recipe, 0), "4 portions");
+}
+
+#[test]
+fn scaling_law() {
+    let recipe = parse(">> servings: 2\nDivide into {1*ceil%trays}.");
+    let recipe = recipe.scale(3, &Converter::bundled());
+    assert_eq!(inline(&recipe, 0), "2 trays");
+    let data = recipe.scaled_data().unwrap();
+    assert!(matches!(
+        data.inline_quantities[0],
+        ScaleOutcome::NonLinear { .. }
+    ));
+}
+
+#[test]
+fn converted() {
+    let converter = Converter::bundled();
+    let recipe = parse(">> servings: 1\nPour {1*%cup} of the sauce.");
+    let mut recipe = recipe.scale(2, &converter);
+    let errors = recipe.convert(System::Metric, &converter);
+    assert!(errors.is_empty());
+    assert_eq!(recipe.inline_quantities[0].unit_text(), Some("ml"));
+}
@@ -0,0 +1,141 @@
+use cooklang::{
+    convert::{ConverterBuilder, UnitsFile},
+    format::{Formatter, UnitStyle},
+    Converter, Quantity, Value,
+};
+use test_case::test_case;
+
+fn converter(layer: &str) -> Converter {
+    let layer: UnitsFile = toml::from_str(layer).expect("valid units file");
+    ConverterBuilder::new()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(layer)
+        .unwrap()
+        .finish()
+        .unwrap()
+}
+
+fn name(converter: &Converter, value: f64, unit: &str) -> String {
+    let q = Quantity::new(Value::from(value), Some(unit.to_string()));
+    Formatter::new()
+        .with_unit_style(UnitStyle::Name)
+        .quantity(&q, converter)
+}
+
+const SPANISH: &str = include_str!("../units/spanish.toml");
+const GERMAN: &str = include_str!("../units/german.toml");
+const FRENCH: &str = include_str!("../units/french.toml");
+const ITALIAN: &str = include_str!("../units/italian.toml");
+const JAPANESE: &str = include_str!("../units/japanese.toml");
+
+#[test_case(SPANISH, 2.0, "tbsp" => "2 cucharadas"; "spanish")]
+#[test_case(SPANISH, 1.0, "kilo" => "1 kilogramo"; "spanish alias")]
+#[test_case(GERMAN, 2.0, "EL" => "2 Esslöffel"; "german symbol")]
+#[test_case(GERMAN, 500.0, "g" => "500 Gramm"; "german")]
+#[test_case(GERMAN, 1.0, "Kilogramm" => "1 Kilogramm"; "german prefix")]
+#[test_case(GERMAN, 2.0, "c" => "2 Tassen"; "german plural")]
+#[test_case(FRENCH, 1.0, "c. à s." => "1 cuillère à soupe"; "french symbol")]
+#[test_case(FRENCH, 2.0, "ml" => "2 millilitres"; "french")]
+#[test_case(ITALIAN, 2.0, "chilo" => "2 chilogrammi"; "italian alias")]
+#[test_case(ITALIAN, 1.0, "cucchiaio" => "1 cucchiaio"; "italian")]
+#[test_case(JAPANESE, 2.0, "大さじ" => "2 大さじ"; "japanese")]
+#[test_case(JAPANESE, 200.0, "ミリリットル" => "200 ミリリットル"; "japanese prefix")]
+fn localized_names(layer: &str, value: f64, unit: &str) -> String {
+    name(&converter(layer), value, unit)
+}
+
+#[test_case(SPANISH; "spanish")]
+#[test_case(GERMAN; "german")]
+#[test_case(FRENCH; "french")]
+#[test_case(ITALIAN; "italian")]
+#[test_case(JAPANESE; "japanese")]
+fn keeps_base_units(layer: &str) {
+    let converter = converter(layer);
+    let q = Quantity::new(Value::from(1.0), Some("tablespoon".to_string()));
+    let mut q = q;
+    q.convert("tsp", &converter).unwrap();
+    assert_eq!(q.value, Value::from(3.0));
+}
+
+#[test]
+fn not_bundled() {
+    let enabled = UnitsFile::bundled_locales().collect::<Vec<_>>();
+    assert!(!enabled.contains(&"xx"));
+    assert!(UnitsFile::bundled_locale("xx").is_none());
+    assert!(ConverterBuilder::new()
+        .with_bundled_locale_units("xx")
+        .is_err());
+}
+
+#[cfg(feature = "bundled_units_es")]
+#[test]
+fn bundled_spanish() {
+    let converter = ConverterBuilder::new()
+        .with_bundled_locale_units("es-MX")
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(name(&converter, 2.0, "tazas"), "2 tazas");
+}
+
+#[cfg(all(feature = "bundled_units_es", feature = "bundled_units_de"))]
+#[test]
+fn bundled_units_added_once() {
+    let converter = ConverterBuilder::new()
+        .with_bundled_units()
+        .unwrap()
+        .with_bundled_locale_units("es")
+        .unwrap()
+        .with_bundled_locale_units("de")
+        .unwrap()
+        .finish()
+        .unwrap();
+    // both layers are known, the last one names the units
+    assert_eq!(name(&converter, 2.0, "tazas"), "2 Tassen");
+    assert_eq!(name(&converter, 2.0, "EL"), "2 Esslöffel");
+}
+
+#[cfg(feature = "bundled_units_de")]
+#[test]
+fn bundled_german() {
+    let converter = ConverterBuilder::new()
+        .with_bundled_locale_units("de")
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(name(&converter, 2.0, "EL"), "2 Esslöffel");
+}
+
+#[cfg(feature = "bundled_units_fr")]
+#[test]
+fn bundled_french() {
+    let converter = ConverterBuilder::new()
+        .with_bundled_locale_units("fr")
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(name(&converter, 2.0, "càc"), "2 cuillères à café");
+}
+
+#[cfg(feature = "bundled_units_it")]
+#[test]
+fn bundled_italian() {
+    let converter = ConverterBuilder::new()
+        .with_bundled_locale_units("it")
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(name(&converter, 2.0, "l"), "2 litri");
+}
+
+#[cfg(feature = "bundled_units_ja")]
+#[test]
+fn bundled_japanese() {
+    let converter = ConverterBuilder::new()
+        .with_bundled_locale_units("ja-JP")
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(name(&converter, 1.0, "小さじ"), "1 小さじ");
+}
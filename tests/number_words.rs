@@ -0,0 +1,102 @@
+use cooklang::{scale::ScaleOutcome, Converter, CooklangParser, Extensions, Value};
+use test_case::test_case;
+
+#[test_case("@eggs{two}" => "2")]
+#[test_case("@eggs{a dozen}" => "12")]
+#[test_case("@eggs{half a dozen}" => "6")]
+#[test_case("@butter{half%cup}" => "1/2 cup")]
+#[test_case("@salt{a pinch}" => "a pinch")]
+#[test_case("@pepper{to taste}" => "to taste")]
+#[test_case("@eggs{some eggs}" => "some eggs"; "not recognized")]
+fn parse(input: &str) -> String {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser.parse(input).unwrap_output();
+    recipe.ingredients[0].quantity.as_ref().unwrap().to_string()
+}
+
+#[test]
+fn parse_kinds() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser
+        .parse("@eggs{two} @salt{a pinch} @cheese{lots}")
+        .unwrap_output()
+        .default_scale();
+    let value = |i: usize| {
+        recipe.ingredients[i]
+            .quantity
+            .as_ref()
+            .unwrap()
+            .value
+            .clone()
+    };
+    assert_eq!(value(0), Value::from(2.0));
+    assert_eq!(value(1), Value::Indefinite("a pinch".into()));
+    assert_eq!(value(2), Value::Text("lots".into()));
+}
+
+#[test]
+fn disabled() {
+    let parser = CooklangParser::new(
+        Extensions::all() ^ Extensions::NUMBER_WORDS,
+        Converter::bundled(),
+    );
+    let recipe = parser
+        .parse("@eggs{two} @salt{a pinch}")
+        .unwrap_output()
+        .default_scale();
+    let value = |i: usize| {
+        recipe.ingredients[i]
+            .quantity
+            .as_ref()
+            .unwrap()
+            .value
+            .clone()
+    };
+    assert_eq!(value(0), Value::Text("two".into()));
+    assert_eq!(value(1), Value::Text("a pinch".into()));
+}
+
+#[test]
+fn scale() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser
+        .parse(">> servings: 2\n@eggs{two*} @salt{a pinch*}")
+        .unwrap_output()
+        .scale(4, parser.converter());
+    let q = |i: usize| recipe.ingredients[i].quantity.as_ref().unwrap().to_string();
+    assert_eq!(q(0), "4");
+    assert_eq!(q(1), "a pinch");
+
+    let outcomes = recipe.scaled_data().unwrap();
+    assert!(matches!(outcomes.ingredients[0], ScaleOutcome::Scaled));
+    assert!(matches!(outcomes.ingredients[1], ScaleOutcome::Fixed));
+}
+
+#[test]
+fn group() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser
+        .parse("@salt{a pinch} @&salt{a pinch} @&salt{5%g} @eggs{two} @&eggs{a dozen}")
+        .unwrap_output()
+        .default_scale();
+    let grouped = recipe.group_ingredients(parser.converter());
+    assert_eq!(grouped[0].quantity.to_string(), "5 g, a pinch");
+    assert_eq!(grouped[1].quantity.to_string(), "14");
+}
+
+#[test]
+fn indefinite_timer() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let result = parser.parse("Rest ~{a few%minutes}.");
+    let error = result.report().errors().next().unwrap();
+    assert_eq!(error.message, "Timer value is indefinite: a few");
+}
+
+#[test]
+fn indefinite_auto_scale() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let result = parser.parse("@basil{a handful*}");
+    assert!(!result.report().has_errors());
+    let warning = result.report().warnings().next().unwrap();
+    assert_eq!(warning.message, "Indefinite amount with auto scale marker");
+}
@@ -0,0 +1,131 @@
+use cooklang::{
+    model::PanSize, quantity::QuantityAddError, scale::ScaleToPanError, Converter, CooklangParser,
+    Extensions, Quantity, ScalableRecipe, Value,
+};
+use test_case::test_case;
+
+fn parse(input: &str) -> ScalableRecipe {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    parser.parse(input).unwrap_output()
+}
+
+fn length(value: f64, unit: &str) -> cooklang::ScaledQuantity {
+    Quantity::new(Value::from(value), Some(unit.to_string()))
+}
+
+#[test_case("#pan{20%cm}" => PanSize::Round { diameter: length(20.0, "cm") }; "round")]
+#[test_case("#pan{20 cm}" => PanSize::Round { diameter: length(20.0, "cm") }; "no separator")]
+#[test_case("#pan{23x33%cm}" => PanSize::Rectangular { width: length(23.0, "cm"), length: length(33.0, "cm") }; "rectangular")]
+#[test_case("#pan{9 × 13%in}" => PanSize::Rectangular { width: length(9.0, "in"), length: length(13.0, "in") }; "times sign")]
+fn cookware_size(input: &str) -> PanSize {
+    let recipe = parse(input);
+    assert_eq!(recipe.cookware[0].quantity, None);
+    recipe.cookware[0].size.clone().unwrap()
+}
+
+#[test_case("#pan{20%g}"; "not length")]
+#[test_case("#pan{20%foo}"; "unknown unit")]
+#[test_case("#pan{big%cm}"; "text")]
+#[test_case("#pan{0x33%cm}"; "zero")]
+fn invalid(input: &str) {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let result = parser.parse(input);
+    assert!(result.report().has_errors());
+}
+
+#[test]
+fn disabled() {
+    let parser = CooklangParser::new(
+        Extensions::all() ^ Extensions::PAN_SIZES,
+        Converter::bundled(),
+    );
+    let result = parser.parse("#pan{20%cm}");
+    assert!(result.report().has_errors());
+}
+
+#[test_case("20 cm" => Ok(PanSize::Round { diameter: length(20.0, "cm") }); "round")]
+#[test_case(" 8.5 in " => Ok(PanSize::Round { diameter: length(8.5, "in") }); "decimal")]
+#[test_case("23x33 cm" => Ok(PanSize::Rectangular { width: length(23.0, "cm"), length: length(33.0, "cm") }); "rectangular")]
+#[test_case("23 X 33cm" => Ok(PanSize::Rectangular { width: length(23.0, "cm"), length: length(33.0, "cm") }); "spaces")]
+#[test_case("20" => Err(()); "no unit")]
+#[test_case("cm" => Err(()); "no value")]
+#[test_case("-2 cm" => Err(()); "negative")]
+fn from_str(s: &str) -> Result<PanSize, ()> {
+    s.parse().map_err(|_| ())
+}
+
+#[test_case("20 cm", "20 cm" => 1.0; "same")]
+#[test_case("40 cm", "20 cm" => 4.0; "double diameter")]
+#[test_case("20x20 cm", "10x10 cm" => 4.0; "rectangular")]
+#[test_case("10x10 cm", "4x4 in" => 0.9688; "converted")]
+fn area_ratio(a: &str, b: &str) -> f64 {
+    let a: PanSize = a.parse().unwrap();
+    let b: PanSize = b.parse().unwrap();
+    let ratio = a.area_ratio(&b, &Converter::bundled()).unwrap();
+    (ratio * 1e4).round() / 1e4
+}
+
+#[test]
+fn scale_to_pan() {
+    let converter = Converter::bundled();
+    let input = ">> servings: 8\nMix @flour{200*%g} and @eggs{2*}. Bake in a #cake pan{20%cm} for ~{30%min}.";
+
+    let pan = "40 cm".parse().unwrap();
+    let recipe = parse(input).scale_to_pan(&pan, &converter).unwrap();
+    let q = |i: usize| recipe.ingredients[i].quantity.as_ref().unwrap().to_string();
+    assert_eq!(q(0), "800 g");
+    assert_eq!(q(1), "8");
+    assert_eq!(
+        recipe.timers[0].quantity.as_ref().unwrap().to_string(),
+        "30 min"
+    );
+    let data = recipe.scaled_data().unwrap();
+    assert_eq!(data.target.target_servings(), 32.0);
+
+    let pan = "10x10 cm".parse().unwrap();
+    let recipe = parse(input).scale_to_pan(&pan, &converter).unwrap();
+    let flour = recipe.ingredients[0].quantity.as_ref().unwrap();
+    let Value::Number(n) = flour.value else {
+        panic!("not a number");
+    };
+    assert!((n.value() - 63.662).abs() < 0.001);
+}
+
+#[test]
+fn no_pan() {
+    let pan = "20 cm".parse().unwrap();
+    let result = parse("@flour{200*%g} #bowl").scale_to_pan(&pan, &Converter::bundled());
+    assert!(matches!(result, Err(ScaleToPanError::NoPan)));
+}
+
+#[test]
+fn area_ratio_range() {
+    let range = PanSize::Round {
+        diameter: Quantity::new(
+            Value::Range {
+                start: 20.0.into(),
+                end: 22.0.into(),
+            },
+            Some("cm".to_string()),
+        ),
+    };
+    let pan: PanSize = "20 cm".parse().unwrap();
+    let converter = Converter::bundled();
+    assert!(matches!(
+        range.area_ratio(&pan, &converter),
+        Err(QuantityAddError::RangeValue(_))
+    ));
+    assert!(matches!(
+        pan.area_ratio(&range, &converter),
+        Err(QuantityAddError::RangeValue(_))
+    ));
+}
+
+#[test]
+fn incompatible() {
+    let pan = PanSize::Round {
+        diameter: length(20.0, "g"),
+    };
+    let result = parse("@flour{200*%g} #pan{20%cm}").scale_to_pan(&pan, &Converter::bundled());
+    assert!(matches!(result, Err(ScaleToPanError::Quantity(_))));
+}
@@ -0,0 +1,113 @@
+use std::error::Error;
+
+use cooklang::{
+    convert::{ConvertError, System},
+    error::Stage,
+    scale::{ScaleError, ScaleOutcome},
+    Converter, CooklangParser, Extensions, ScalableRecipe,
+};
+use test_case::test_case;
+
+fn parse(input: &str) -> ScalableRecipe {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    parser.parse(input).unwrap_output()
+}
+
+#[test]
+fn scale_error_located() {
+    let input = ">> servings: 2|4\nAdd @flour{100|200%g} and ~{10|20%min}.";
+    let recipe = parse(input).scale(3, &Converter::bundled());
+    let report = recipe.scale_report();
+    assert!(report.has_errors());
+
+    let errors = report.errors().collect::<Vec<_>>();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].message, "Can't scale '100 g' of flour");
+    assert_eq!(errors[0].stage, Stage::Scale);
+    assert_eq!(&input[errors[0].labels[0].0.range()], "100|200%g");
+    assert!(matches!(
+        errors[0].source().unwrap().downcast_ref::<ScaleError>(),
+        Some(ScaleError::NotScalable { .. })
+    ));
+    assert_eq!(errors[1].message, "Can't scale timer '10 min'");
+    assert_eq!(&input[errors[1].labels[0].0.range()], "10|20%min");
+}
+
+#[test_case(2 ; "default")]
+#[test_case(4 ; "defined")]
+fn scale_no_errors(target: u32) {
+    let recipe = parse(">> servings: 2|4\n@flour{100|200%g}").scale(target, &Converter::bundled());
+    assert!(recipe.scale_report().is_empty());
+}
+
+#[test]
+fn scale_report_printed() {
+    let input = ">> servings: 2|4\nAdd @flour{100|200%g}.";
+    let recipe = parse(input).scale(3, &Converter::bundled());
+    let mut out = Vec::new();
+    recipe
+        .scale_report()
+        .write("recipe.cook", input, false, &mut out)
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("Can't scale '100 g' of flour"));
+    assert!(out.contains("recipe.cook"));
+    assert!(out.contains("this quantity"));
+}
+
+#[test]
+fn scale_error_serialized() {
+    let recipe = parse(">> servings: 2|4\n@flour{100|200%g}").scale(3, &Converter::bundled());
+    let data = recipe.scaled_data().unwrap();
+    let json = serde_json::to_value(data).unwrap();
+    assert!(json["ingredients"][0]["error"]["notScalable"].is_object());
+
+    let data: cooklang::scale::ScaledData = serde_json::from_value(json).unwrap();
+    assert!(matches!(
+        data.ingredients[0],
+        ScaleOutcome::Error(ScaleError::UndefinedError)
+    ));
+}
+
+#[test]
+fn convert_error_located() {
+    let input = "Add @flour{1%lb} and @water{2%splashes}.";
+    let converter = Converter::bundled();
+    let mut recipe = parse(input).default_scale();
+    let report = recipe.convert(System::Metric, &converter);
+
+    let errors = report.errors().collect::<Vec<_>>();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "Can't convert '2 splashes' of water");
+    assert_eq!(errors[0].stage, Stage::Convert);
+    assert_eq!(&input[errors[0].labels[0].0.range()], "2%splashes");
+    assert!(matches!(
+        errors[0].source().unwrap().downcast_ref::<ConvertError>(),
+        Some(ConvertError::UnknownUnit(_))
+    ));
+    assert_eq!(
+        recipe.ingredients[0].quantity.as_ref().unwrap().to_string(),
+        "453.592 g"
+    );
+}
+
+#[test_case("Bake at 180 °C." ; "single line")]
+#[test_case("Bake\nat 180 °C." ; "soft break")]
+#[test_case("Bake [- at -] at 180 °C." ; "comment")]
+fn temperature_located(input: &str) {
+    let recipe = parse(input);
+    let span = recipe.source_map.inline_quantities[0];
+    assert_eq!(&input[span.range()], "180 °C");
+}
+
+#[test]
+fn source_map() {
+    let input = "Add @flour{1%lb}, @salt and #pan{2} for ~{10%min}. Divide into {4%portions}.";
+    let recipe = parse(input);
+    let map = &recipe.source_map;
+    assert_eq!(&input[map.ingredients[0].range()], "1%lb");
+    assert_eq!(&input[map.ingredients[1].range()], "@salt");
+    assert_eq!(&input[map.cookware[0].range()], "2");
+    assert_eq!(&input[map.timers[0].range()], "10%min");
+    assert_eq!(&input[map.inline_quantities[0].range()], "{4%portions}");
+}
@@ -0,0 +1,96 @@
+use cooklang::{
+    convert::{ConversionProfile, ConvertError, PhysicalQuantity, ProfileTarget, System},
+    quantity::GroupedQuantity,
+    Converter, CooklangParser, Extensions, Quantity, Value,
+};
+use test_case::test_case;
+
+fn profile() -> ConversionProfile {
+    ConversionProfile::new(System::Metric)
+        .with_quantity(PhysicalQuantity::Mass, ProfileTarget::Unit("g".into()))
+        .with_quantity(
+            PhysicalQuantity::Temperature,
+            ProfileTarget::Unit("F".into()),
+        )
+        .with_quantity(
+            PhysicalQuantity::Length,
+            ProfileTarget::System(System::Imperial),
+        )
+        .with_unit("tsp", ProfileTarget::Keep)
+        .with_unit("tbsp", ProfileTarget::Keep)
+}
+
+#[test_case(1.0, "kg" => "1000 g")]
+#[test_case(1.0, "lb" => "453.592 g")]
+#[test_case(180.0, "C" => "356 °F")]
+#[test_case(2.0, "tsp" => "2 tsp"; "kept")]
+#[test_case(1.0, "cup" => "236.588 ml")]
+#[test_case(10.0, "cm" => "4 in")]
+fn convert_quantity(value: f64, unit: &str) -> String {
+    let converter = Converter::bundled();
+    let mut q = Quantity::new(Value::from(value), Some(unit.to_string()));
+    q.convert(&profile(), &converter).unwrap();
+    q.to_string()
+}
+
+#[test]
+fn convert_recipe() {
+    let converter = Converter::bundled();
+    let parser = CooklangParser::new(Extensions::all(), converter.clone());
+    let recipe = parser
+        .parse("@flour{1%lb} @salt{1%tsp} @milk{2%cups} ~{10%min}")
+        .unwrap_output()
+        .default_scale();
+    let mut recipe = recipe;
+    let errors = recipe.convert(&profile(), &converter);
+    assert!(errors.is_empty());
+    let q = |i: usize| recipe.ingredients[i].quantity.as_ref().unwrap().to_string();
+    assert_eq!(q(0), "453.592 g");
+    assert_eq!(q(1), "1 tsp");
+    assert_eq!(q(2), "473.176 ml");
+    assert_eq!(
+        recipe.timers[0].quantity.as_ref().unwrap().to_string(),
+        "10 min"
+    );
+}
+
+#[test]
+fn fit_grouped() {
+    let converter = Converter::bundled();
+    let mut grouped = GroupedQuantity::default();
+    grouped.add(
+        &Quantity::new(Value::from(2.0), Some("lb".to_string())),
+        &converter,
+    );
+    grouped.fit_with_profile(&profile(), &converter).unwrap();
+    assert_eq!(grouped.to_string(), "907.185 g");
+}
+
+#[test]
+fn unknown_target_unit() {
+    let converter = Converter::bundled();
+    let profile = ConversionProfile::default()
+        .with_quantity(PhysicalQuantity::Mass, ProfileTarget::Unit("bag".into()));
+    let mut q = Quantity::new(Value::from(1.0), Some("kg".to_string()));
+    assert!(q.convert(&profile, &converter).is_err());
+    assert_eq!(q.to_string(), "1 kg");
+}
+
+#[test]
+fn same_unit_overrides() {
+    let converter = Converter::bundled();
+    let profile = ConversionProfile::default()
+        .with_unit("tsp", ProfileTarget::Keep)
+        .with_unit("teaspoon", ProfileTarget::Keep);
+    let mut q = Quantity::new(Value::from(2.0), Some("tsp".to_string()));
+    q.convert(&profile, &converter).unwrap();
+    assert_eq!(q.to_string(), "2 tsp");
+
+    let profile = profile.with_unit("teaspoons", ProfileTarget::Unit("ml".into()));
+    let err = q.convert(&profile, &converter).unwrap_err();
+    assert!(matches!(
+        err,
+        ConvertError::ProfileConflict(a, b) if a == "teaspoon" && b == "teaspoons"
+    ));
+    assert_eq!(q.to_string(), "2 tsp");
+}
@@ -0,0 +1,102 @@
+use std::cmp::Ordering;
+
+use cooklang::{
+    quantity::{Number, QuantityAddError},
+    Converter, Quantity, ScaledQuantity, Value,
+};
+use test_case::test_case;
+
+fn q(value: Value, unit: &str) -> ScaledQuantity {
+    let unit = (!unit.is_empty()).then(|| unit.to_string());
+    Quantity::new(value, unit)
+}
+
+fn range_value(start: f64, end: f64) -> Value {
+    Value::Range {
+        start: start.into(),
+        end: end.into(),
+    }
+}
+
+fn frac(num: u32, den: u32) -> Value {
+    Value::Number(Number::Fraction {
+        whole: 0,
+        num,
+        den,
+        err: 0.0,
+    })
+}
+
+#[test_case(q(500.0.into(), "g"), q(200.0.into(), "g") => "300 g"; "same unit")]
+#[test_case(q(1.0.into(), "kg"), q(200.0.into(), "g") => "0.8 kg"; "converted")]
+#[test_case(q(500.0.into(), "g"), q(range_value(100.0, 200.0), "g") => "300-400 g"; "range rhs")]
+#[test_case(q(range_value(3.0, 4.0), "cups"), q(1.0.into(), "cup") => "2-3 cups"; "range lhs")]
+#[test_case(q(range_value(3.0, 5.0), ""), q(range_value(1.0, 2.0), "") => "1-4"; "ranges")]
+#[test_case(q(1.0.into(), "cup"), q(frac(1, 3), "cup") => "2/3 cup"; "fraction")]
+#[test_case(q(100.0.into(), "g"), q(150.0.into(), "g") => "-50 g"; "negative")]
+fn sub(a: ScaledQuantity, b: ScaledQuantity) -> String {
+    let converter = Converter::bundled();
+    a.try_sub(&b, &converter).unwrap().to_string()
+}
+
+#[test]
+fn sub_incompatible() {
+    let converter = Converter::bundled();
+    let r = q(1.0.into(), "kg").try_sub(&q(1.0.into(), "l"), &converter);
+    assert!(matches!(r, Err(QuantityAddError::IncompatibleUnits(_))));
+    let r = q(1.0.into(), "").try_sub(&q(Value::Text("some".into()), ""), &converter);
+    assert!(matches!(r, Err(QuantityAddError::TextValue(_))));
+}
+
+#[test_case(q(300.0.into(), "g"), 2.0 => "600 g")]
+#[test_case(q(range_value(1.0, 2.0), "cups"), 0.5 => "0.5-1 cups"; "range")]
+#[test_case(q(range_value(1.0, 2.0), ""), -1.0 => "-2--1"; "negative range")]
+fn mul(a: ScaledQuantity, factor: f64) -> String {
+    a.try_mul(factor).unwrap().to_string()
+}
+
+#[test_case(q(300.0.into(), "g"), 4.0 => "75 g")]
+#[test_case(q(frac(1, 2), "cup"), 2.0 => "1/4 cup"; "fraction")]
+#[test_case(q(range_value(2.0, 4.0), "tbsp"), 2.0 => "1-2 tbsp"; "range")]
+fn div(a: ScaledQuantity, divisor: f64) -> String {
+    a.try_div(divisor).unwrap().to_string()
+}
+
+#[test]
+fn div_by_zero() {
+    let r = q(1.0.into(), "kg").try_div(0.0);
+    assert!(matches!(r, Err(QuantityAddError::DivisionByZero)));
+}
+
+#[test_case(q(100.0.into(), "g"), q(400.0.into(), "g") => Value::from(0.25))]
+#[test_case(q(1.0.into(), "kg"), q(250.0.into(), "g") => Value::from(4.0); "converted")]
+#[test_case(q(frac(1, 2), "cup"), q(1.0.into(), "cup") => frac(1, 2); "fraction")]
+#[test_case(q(range_value(2.0, 4.0), "cups"), q(2.0.into(), "cups") => range_value(1.0, 2.0); "range")]
+#[test_case(q(4.0.into(), ""), q(range_value(1.0, 2.0), "") => range_value(2.0, 4.0); "range rhs")]
+fn ratio(a: ScaledQuantity, b: ScaledQuantity) -> Value {
+    let converter = Converter::bundled();
+    a.ratio(&b, &converter).unwrap()
+}
+
+#[test]
+fn ratio_by_zero() {
+    let converter = Converter::bundled();
+    let r = q(1.0.into(), "kg").ratio(&q(0.0.into(), "g"), &converter);
+    assert!(matches!(r, Err(QuantityAddError::DivisionByZero)));
+    let r = q(1.0.into(), "").ratio(&q(range_value(0.0, 1.0), ""), &converter);
+    assert!(matches!(r, Err(QuantityAddError::DivisionByZero)));
+}
+
+#[test_case(q(1.0.into(), "kg"), q(500.0.into(), "g") => Some(Ordering::Greater); "greater")]
+#[test_case(q(1.0.into(), "kg"), q(1000.0.into(), "g") => Some(Ordering::Equal); "equal")]
+#[test_case(q(1.0.into(), "tsp"), q(1.0.into(), "tbsp") => Some(Ordering::Less); "less")]
+#[test_case(q(range_value(1.0, 2.0), "cups"), q(3.0.into(), "cups") => Some(Ordering::Less); "range less")]
+#[test_case(q(range_value(1.0, 3.0), "cups"), q(2.0.into(), "cups") => None; "range overlap")]
+#[test_case(q(range_value(1.0, 3.0), "cups"), q(range_value(1.0, 3.0), "cups") => Some(Ordering::Equal); "same range")]
+#[test_case(q(1.0.into(), "kg"), q(1.0.into(), "l") => None; "incompatible")]
+#[test_case(q(1.0.into(), "bunch"), q(2.0.into(), "bunch") => Some(Ordering::Less); "unknown unit")]
+#[test_case(q(Value::Text("some".into()), ""), q(2.0.into(), "") => None; "text")]
+fn partial_cmp(a: ScaledQuantity, b: ScaledQuantity) -> Option<Ordering> {
+    let converter = Converter::bundled();
+    a.partial_cmp(&b, &converter)
+}
@@ -0,0 +1,184 @@
+use cooklang::{
+    convert::{ConverterBuilder, System, UnitsFile},
+    scale::{ScaleOutcome, ScalingLaw},
+    Converter, CooklangParser, Extensions, Value,
+};
+use indoc::indoc;
+
+fn converter(rounding: &str) -> Converter {
+    let file: UnitsFile = toml::from_str(rounding).unwrap();
+    ConverterBuilder::new()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap()
+}
+
+fn rounding_converter() -> Converter {
+    converter(indoc! {r#"
+        [rounding.all]
+        significant_figures = 3
+
+        [rounding.unitless]
+        step = 1
+        direction = "up"
+
+        [rounding.quantity]
+        time = { step = 1 }
+
+        [rounding.unit]
+        g = { step = 5 }
+    "#})
+}
+
+fn scale(input: &str, target: u32, converter: Converter) -> cooklang::ScaledRecipe {
+    let parser = CooklangParser::new(Extensions::all(), converter);
+    let recipe = parser.parse(input).unwrap_output();
+    recipe.scale(target, parser.converter())
+}
+
+#[test]
+fn scaled() {
+    let recipe = scale(
+        ">> servings: 7\n@flour{520*%g} @eggs{3*} @milk{5*%ml} @water{1}",
+        3,
+        rounding_converter(),
+    );
+    let q = |i: usize| recipe.ingredients[i].quantity.as_ref().unwrap().to_string();
+    assert_eq!(q(0), "225 g");
+    assert_eq!(q(1), "2");
+    assert_eq!(q(2), "2.14 ml");
+
+    let data = recipe.scaled_data().unwrap();
+    let ScaleOutcome::Rounded {
+        unrounded: Value::Number(n),
+        law: None,
+    } = &data.ingredients[0]
+    else {
+        panic!("not rounded: {:?}", data.ingredients[0]);
+    };
+    assert!((n.value() - 222.857).abs() < 0.001);
+    assert!(matches!(data.ingredients[1], ScaleOutcome::Rounded { .. }));
+    assert!(matches!(data.ingredients[2], ScaleOutcome::Rounded { .. }));
+}
+
+#[test]
+fn not_rounded() {
+    let recipe = scale(
+        ">> servings: 2\n@flour{100*%g} @eggs{3*}",
+        4,
+        rounding_converter(),
+    );
+    let data = recipe.scaled_data().unwrap();
+    assert!(matches!(data.ingredients[0], ScaleOutcome::Scaled));
+    assert!(matches!(data.ingredients[1], ScaleOutcome::Scaled));
+}
+
+#[test]
+fn no_config() {
+    let recipe = scale(">> servings: 7\n@flour{520*%g}", 3, Converter::bundled());
+    let data = recipe.scaled_data().unwrap();
+    assert!(matches!(data.ingredients[0], ScaleOutcome::Scaled));
+}
+
+#[test]
+fn non_linear_law_kept() {
+    let recipe = scale(">> servings: 1\n~{40*sqrt%min}", 2, rounding_converter());
+    let timer = recipe.timers[0].quantity.as_ref().unwrap();
+    assert_eq!(timer.to_string(), "57 min");
+    let data = recipe.scaled_data().unwrap();
+    let ScaleOutcome::Rounded {
+        unrounded: Value::Number(n),
+        law: Some(ScalingLaw::Sqrt),
+    } = &data.timers[0]
+    else {
+        panic!("not rounded: {:?}", data.timers[0]);
+    };
+    assert!((n.value() - 56.569).abs() < 0.001);
+}
+
+#[test]
+fn discrete_not_rounded() {
+    // the unitless rounding would make it 3
+    let recipe = scale(">> servings: 4\n@lemons{3*half}", 3, rounding_converter());
+    let lemons = recipe.ingredients[0].quantity.as_ref().unwrap();
+    assert_eq!(lemons.to_string(), "2 1/2");
+    let data = recipe.scaled_data().unwrap();
+    let ScaleOutcome::Rounded {
+        unrounded: Value::Number(n),
+        law: Some(ScalingLaw::Half),
+    } = &data.ingredients[0]
+    else {
+        panic!("not rounded: {:?}", data.ingredients[0]);
+    };
+    assert_eq!(n.value(), 2.25);
+}
+
+#[test]
+fn converted() {
+    let converter = rounding_converter();
+    let mut recipe = scale(
+        ">> servings: 2\n@milk{1*%cup} @flour{1*%lb} @water{1%cup}",
+        4,
+        converter.clone(),
+    );
+    let errors = recipe.convert(System::Metric, &converter);
+    assert!(errors.is_empty());
+    let q = |i: usize| recipe.ingredients[i].quantity.as_ref().unwrap().to_string();
+    assert_eq!(q(0), "473 ml");
+    assert_eq!(q(1), "905 g");
+    assert_eq!(q(2), "237 ml");
+
+    let data = recipe.scaled_data().unwrap();
+    assert!(matches!(data.ingredients[0], ScaleOutcome::Rounded { .. }));
+    assert!(matches!(data.ingredients[1], ScaleOutcome::Rounded { .. }));
+    assert!(matches!(data.ingredients[2], ScaleOutcome::Fixed));
+}
+
+#[test]
+fn layers() {
+    let converter = converter(indoc! {r#"
+        [rounding.all]
+        significant_figures = 2
+
+        [rounding.unit]
+        g = { step = 5 }
+    "#});
+    let g = converter.find_unit("g").unwrap();
+    let cfg = converter.rounding_config(Some(&g)).unwrap();
+    assert_eq!(cfg.significant_figures, Some(2));
+    assert_eq!(cfg.step, Some(5.0));
+
+    let kg = converter.find_unit("kg").unwrap();
+    let cfg = converter.rounding_config(Some(&kg)).unwrap();
+    assert_eq!(cfg.step, None);
+
+    assert!(Converter::bundled().rounding_config(Some(&g)).is_none());
+}
+
+#[test]
+fn unknown_unit() {
+    let file: UnitsFile = toml::from_str("[rounding.unit]\nfoo = { step = 1 }").unwrap();
+    let result = ConverterBuilder::new()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(file)
+        .unwrap()
+        .finish();
+    assert!(result.is_err());
+}
+
+#[test]
+fn exported() {
+    let converter = rounding_converter();
+    let toml = toml::to_string(&converter.to_units_file()).unwrap();
+    let file: UnitsFile = toml::from_str(&toml).unwrap();
+    let exported = ConverterBuilder::new()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(exported, converter);
+}
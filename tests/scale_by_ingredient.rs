@@ -0,0 +1,129 @@
+use cooklang::{
+    scale::{IngredientRef, ScaleByIngredientError},
+    Converter, CooklangParser, Extensions, Quantity, ScaledQuantity, ScaledRecipe, Value,
+};
+use test_case::test_case;
+
+fn q(value: f64, unit: &str) -> ScaledQuantity {
+    let unit = (!unit.is_empty()).then(|| unit.to_string());
+    Quantity::new(Value::from(value), unit)
+}
+
+fn scale(
+    input: &str,
+    ingredient: IngredientRef,
+    available: ScaledQuantity,
+) -> Result<ScaledRecipe, ScaleByIngredientError> {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser.parse(input).unwrap_output();
+    recipe.scale_by_ingredient(ingredient, &available, parser.converter())
+}
+
+fn quantity(recipe: &ScaledRecipe, index: usize) -> String {
+    recipe.ingredients[index]
+        .quantity
+        .as_ref()
+        .unwrap()
+        .to_string()
+}
+
+#[test_case(IngredientRef::Name("flour"), q(350.0, "g") => (2.8, "1.4".to_string()); "name")]
+#[test_case(IngredientRef::Name("Flour"), q(1.0, "kg") => (8.0, "4".to_string()); "other unit")]
+#[test_case(IngredientRef::Index(0), q(250.0, "g") => (2.0, "1".to_string()); "index")]
+#[test_case(IngredientRef::Name("yolks"), q(4.0, "") => (8.0, "4".to_string()); "alias")]
+fn servings(ingredient: IngredientRef, available: ScaledQuantity) -> (f64, String) {
+    let recipe = scale(
+        ">> servings: 4\n@flour{500*%g} @eggs|yolks{2*}",
+        ingredient,
+        available,
+    )
+    .unwrap();
+    let data = recipe.scaled_data().unwrap();
+    let servings = (data.target.target_servings() * 1e6).round() / 1e6;
+    let eggs = recipe.ingredients[1].quantity.as_ref().unwrap();
+    let eggs = match eggs.value {
+        Value::Number(n) => (n.value() * 1e6).round() / 1e6,
+        _ => unreachable!(),
+    };
+    (servings, eggs.to_string())
+}
+
+#[test]
+fn no_servings() {
+    let recipe = scale(
+        "@butter{1/2*%cup} @sugar{100*%g}",
+        IngredientRef::Name("butter"),
+        q(1.0, "cup"),
+    )
+    .unwrap();
+    assert_eq!(recipe.scaled_data().unwrap().target.target_servings(), 2.0);
+    assert_eq!(quantity(&recipe, 1), "200 g");
+}
+
+#[test]
+fn references() {
+    // 300 g in total
+    let recipe = scale(
+        "@flour{200*%g} then @&flour{100*%g} and @water{150*%ml}",
+        IngredientRef::Index(1),
+        q(600.0, "g"),
+    )
+    .unwrap();
+    assert_eq!(quantity(&recipe, 2), "300 ml");
+}
+
+#[test]
+fn fixed_part() {
+    // 100 g are always used, 200 g scale
+    let recipe = scale(
+        "@flour{200*%g} then @&flour{100%g} and @water{150*%ml}",
+        IngredientRef::Name("flour"),
+        q(500.0, "g"),
+    )
+    .unwrap();
+    assert_eq!(quantity(&recipe, 0), "400 g");
+    assert_eq!(quantity(&recipe, 2), "300 ml");
+}
+
+#[test]
+fn range() {
+    // the lowest factor, so 400 g are always enough
+    let recipe = scale(
+        "@flour{100-200*%g} @water{100*%ml}",
+        IngredientRef::Name("flour"),
+        q(400.0, "g"),
+    )
+    .unwrap();
+    assert_eq!(quantity(&recipe, 1), "200 ml");
+}
+
+#[test]
+fn by_servings() {
+    let recipe = scale(
+        ">> servings: 2|4\n@flour{100|200%g} @water{100*%ml}",
+        IngredientRef::Name("flour"),
+        q(200.0, "g"),
+    )
+    .unwrap();
+    let data = recipe.scaled_data().unwrap();
+    assert_eq!(data.target.target_servings(), 4.0);
+    assert_eq!(data.target.index(), Some(1));
+    assert_eq!(quantity(&recipe, 0), "200 g");
+}
+
+#[test_case("@flour{500*%g}", IngredientRef::Name("sugar"), q(1.0, "g") => "not found"; "not found")]
+#[test_case("@flour{500*%g}", IngredientRef::Index(4), q(1.0, "g") => "not found"; "bad index")]
+#[test_case("@flour", IngredientRef::Name("flour"), q(1.0, "g") => "no quantity"; "no quantity")]
+#[test_case("@flour{500%g}", IngredientRef::Name("flour"), q(1.0, "g") => "not scalable"; "fixed")]
+#[test_case("@flour{500*%g}", IngredientRef::Name("flour"), q(1.0, "l") => "quantity"; "incompatible")]
+#[test_case("@flour{500*%g} @&flour{100%g}", IngredientRef::Name("flour"), q(50.0, "g") => "not enough"; "not enough")]
+fn errors(input: &str, ingredient: IngredientRef, available: ScaledQuantity) -> &'static str {
+    match scale(input, ingredient, available) {
+        Err(ScaleByIngredientError::NotFound(_)) => "not found",
+        Err(ScaleByIngredientError::NoQuantity) => "no quantity",
+        Err(ScaleByIngredientError::NotScalable) => "not scalable",
+        Err(ScaleByIngredientError::NotEnough) => "not enough",
+        Err(ScaleByIngredientError::Quantity(_)) => "quantity",
+        Ok(_) => "ok",
+    }
+}
@@ -0,0 +1,102 @@
+use cooklang::{
+    scale::{InvalidScaleFactor, ScaleOutcome},
+    Converter, CooklangParser, Extensions, ScalableRecipe, ScaledRecipe,
+};
+use test_case::test_case;
+
+fn parse(input: &str) -> (ScalableRecipe, CooklangParser) {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser.parse(input).unwrap_output();
+    (recipe, parser)
+}
+
+fn quantity(recipe: &ScaledRecipe, index: usize) -> String {
+    recipe.ingredients[index]
+        .quantity
+        .as_ref()
+        .unwrap()
+        .to_string()
+}
+
+#[test_case(0.5 => (2.0, "250 g".to_string()); "half")]
+#[test_case(1.5 => (6.0, "750 g".to_string()); "one and a half")]
+#[test_case(2.0 => (8.0, "1 kg".to_string()); "double")]
+#[test_case(0.3 => (1.2, "150 g".to_string()); "decimal servings")]
+fn by_factor(factor: f64) -> (f64, String) {
+    let (recipe, parser) = parse(">> servings: 4\n@flour{500*%g}");
+    let recipe = recipe.scale_by_factor(factor, parser.converter()).unwrap();
+    let servings = recipe.scaled_data().unwrap().target.target_servings();
+    (servings, quantity(&recipe, 0))
+}
+
+#[test_case(1.5 => "1/2 tbsp"; "fraction")]
+#[test_case(2.0 => "2 tsp"; "integer")]
+fn to_servings(target: f64) -> String {
+    let (recipe, parser) = parse("@salt{1*%tsp}");
+    let recipe = recipe
+        .scale_to_servings(target, parser.converter())
+        .unwrap();
+    quantity(&recipe, 0)
+}
+
+#[test]
+fn by_servings_index() {
+    let input = ">> servings: 2|4\n@flour{200|350%g} @salt{1*%tsp}";
+
+    let (recipe, parser) = parse(input);
+    let recipe = recipe.scale_by_factor(2.0, parser.converter()).unwrap();
+    let data = recipe.scaled_data().unwrap();
+    assert_eq!(data.target.index(), Some(1));
+    assert_eq!(quantity(&recipe, 0), "350 g");
+    assert!(matches!(data.ingredients[0], ScaleOutcome::Scaled));
+
+    let (recipe, parser) = parse(input);
+    let recipe = recipe.scale_to_servings(4.0, parser.converter()).unwrap();
+    assert_eq!(recipe.scaled_data().unwrap().target.index(), Some(1));
+    assert_eq!(quantity(&recipe, 0), "350 g");
+
+    let (recipe, parser) = parse(input);
+    let recipe = recipe.scale_by_factor(0.5, parser.converter()).unwrap();
+    let data = recipe.scaled_data().unwrap();
+    assert_eq!(data.target.index(), None);
+    assert!(matches!(data.ingredients[0], ScaleOutcome::Error(_)));
+    assert_eq!(quantity(&recipe, 1), "1/2 tsp");
+}
+
+#[test]
+fn base_servings() {
+    let (recipe, parser) = parse(">> servings: 2|4\n@flour{200|350%g}");
+    let recipe = recipe.scale_to_servings(2.0, parser.converter()).unwrap();
+    assert!(recipe.is_default_scaled());
+}
+
+#[test_case(0.0; "zero")]
+#[test_case(-1.0; "negative")]
+#[test_case(f64::NAN; "nan")]
+#[test_case(f64::INFINITY; "infinity")]
+fn invalid(factor: f64) {
+    let (recipe, parser) = parse("@flour{500*%g}");
+    let err = recipe
+        .scale_by_factor(factor, parser.converter())
+        .unwrap_err();
+    assert!(matches!(err, InvalidScaleFactor(_)));
+
+    let (recipe, parser) = parse("@flour{500*%g}");
+    assert!(recipe
+        .scale_to_servings(factor, parser.converter())
+        .is_err());
+}
+
+#[test_case(1; "default")]
+#[test_case(2; "declared")]
+#[test_case(3; "linear")]
+fn scale_ref(target: u32) {
+    let input = ">> servings: 1|2\n@flour{100|180%g} @salt{1*%tsp} ~{10%min} #pot";
+    let (recipe, parser) = parse(input);
+    let by_ref = recipe.scale_ref(target, parser.converter());
+    let by_ref_again = recipe.scale_ref(target, parser.converter());
+    let by_value = recipe.scale(target, parser.converter());
+    let json = |r: &ScaledRecipe| serde_json::to_value(r).unwrap();
+    assert_eq!(json(&by_ref), json(&by_value));
+    assert_eq!(json(&by_ref_again), json(&by_value));
+}
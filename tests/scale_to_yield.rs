@@ -0,0 +1,93 @@
+use cooklang::{
+    scale::{ScaleOutcome, ScaleToYieldError},
+    Converter, CooklangParser, Extensions, Quantity, ScaledQuantity, ScaledRecipe, Value,
+};
+use test_case::test_case;
+
+fn q(value: f64, unit: &str) -> ScaledQuantity {
+    let unit = (!unit.is_empty()).then(|| unit.to_string());
+    Quantity::new(Value::from(value), unit)
+}
+
+fn scale(input: &str, target: ScaledQuantity) -> Result<ScaledRecipe, ScaleToYieldError> {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser.parse(input).unwrap_output();
+    recipe.scale_to_yield(&target, parser.converter())
+}
+
+fn quantity(recipe: &ScaledRecipe, index: usize) -> String {
+    recipe.ingredients[index]
+        .quantity
+        .as_ref()
+        .unwrap()
+        .to_string()
+}
+
+#[test_case(q(3.0, "loaves") => (3.0, "750 g".to_string()); "same unit")]
+#[test_case(q(1.0, "loaves") => (1.0, "250 g".to_string()); "fractional")]
+#[test_case(q(5.0, "loaves") => (5.0, "1.25 kg".to_string()); "fitted")]
+fn loaves(target: ScaledQuantity) -> (f64, String) {
+    let recipe = scale(">> servings: 2\n>> yield: 2 loaves\n@flour{500*%g}", target).unwrap();
+    let servings = recipe.scaled_data().unwrap().target.target_servings();
+    (servings, quantity(&recipe, 0))
+}
+
+#[test_case(">> yield: 1 l" , q(750.0, "ml") => "375 g"; "converted")]
+#[test_case(">> yield: 1.5 l", q(3.0, "l") => "1 kg"; "decimal yield")]
+#[test_case(">> yield: 1/2 l", q(1.0, "l") => "1 kg"; "fraction yield")]
+#[test_case(">> yield: 12", q(18.0, "") => "750 g"; "unitless")]
+fn factor(yield_entry: &str, target: ScaledQuantity) -> String {
+    let recipe = scale(&format!("{yield_entry}\n@flour{{500*%g}}"), target).unwrap();
+    quantity(&recipe, 0)
+}
+
+#[test]
+fn by_servings() {
+    let input = ">> servings: 2|4\n>> yield: 1 kg\n@flour{500|1000%g} @salt{1*%tsp}";
+
+    let recipe = scale(input, q(2.0, "kg")).unwrap();
+    let data = recipe.scaled_data().unwrap();
+    assert_eq!(data.target.index(), Some(1));
+    assert_eq!(quantity(&recipe, 0), "1 kg");
+    assert!(matches!(data.ingredients[0], ScaleOutcome::Scaled));
+
+    let recipe = scale(input, q(1.5, "kg")).unwrap();
+    let data = recipe.scaled_data().unwrap();
+    assert_eq!(data.target.index(), None);
+    assert!(matches!(data.ingredients[0], ScaleOutcome::Error(_)));
+    assert_eq!(quantity(&recipe, 1), "1/2 tbsp");
+}
+
+#[test]
+fn no_yield() {
+    let result = scale("@flour{500*%g}", q(1.0, "kg"));
+    assert!(matches!(result, Err(ScaleToYieldError::NoYield)));
+}
+
+#[test]
+fn incompatible() {
+    let result = scale(">> yield: 1 kg\n@flour{500*%g}", q(1.0, "l"));
+    assert!(matches!(result, Err(ScaleToYieldError::Quantity(_))));
+}
+
+#[test]
+fn invalid_target() {
+    let result = scale(">> yield: 1 kg\n@flour{500*%g}", q(0.0, "kg"));
+    assert!(matches!(result, Err(ScaleToYieldError::InvalidTarget)));
+}
+
+#[test_case(">> yield: 1,5 l"; "decimal comma")]
+#[test_case(">> yield: ½ loaf"; "unicode fraction")]
+#[test_case(">> yield: two loaves"; "number words")]
+fn yield_extensions(yield_entry: &str) {
+    let parser = CooklangParser::new(Extensions::empty(), Converter::bundled());
+    let input = format!("{yield_entry}\n@flour{{500%g}}");
+    let recipe = parser.parse(&input).unwrap_output();
+    assert_eq!(recipe.metadata.recipe_yield(), None);
+    let result = recipe.scale_to_yield(&q(1.0, "l"), parser.converter());
+    assert!(matches!(result, Err(ScaleToYieldError::NoYield)));
+
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let recipe = parser.parse(&input).unwrap_output();
+    assert!(recipe.metadata.recipe_yield().is_some());
+}
@@ -0,0 +1,184 @@
+use cooklang::{
+    quantity::ScalableValue,
+    scale::{ScaleOutcome, ScalingLaw},
+    Converter, CooklangParser, Extensions, ScalableRecipe, Value,
+};
+use indoc::indoc;
+use test_case::test_case;
+
+fn parse(input: &str) -> ScalableRecipe {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    parser.parse(input).unwrap_output()
+}
+
+fn timer(input: &str, target: u32) -> (String, ScaleOutcome) {
+    let recipe = parse(input).scale(target, &Converter::bundled());
+    let quantity = recipe.timers[0].quantity.as_ref().unwrap().to_string();
+    let outcome = recipe.scaled_data().unwrap().timers[0].clone();
+    (quantity, outcome)
+}
+
+fn cookware(input: &str, target: u32) -> (String, ScaleOutcome) {
+    let recipe = parse(input).scale(target, &Converter::bundled());
+    let quantity = recipe.cookware[0].quantity.as_ref().unwrap().to_string();
+    let outcome = recipe.scaled_data().unwrap().cookware[0].clone();
+    (quantity, outcome)
+}
+
+#[test_case("~{20*sqrt%min}", 4 => "40 min"; "sqrt")]
+#[test_case("~{40*sqrt%min}", 2 => "56.569 min"; "sqrt decimal")]
+#[test_case("~{20*%min}", 2 => "40 min"; "linear")]
+#[test_case("~{40*fixed%min}", 4 => "40 min"; "fixed")]
+#[test_case("~{40%min}", 4 => "40 min"; "no marker")]
+fn timers(input: &str, target: u32) -> String {
+    timer(input, target).0
+}
+
+#[test_case("#pans{1*ceil}", 3 => "2"; "step")]
+#[test_case("#pans{1*ceil}", 4 => "2"; "whole")]
+#[test_case("#pans{2*ceil}", 5 => "5"; "up")]
+#[test_case("#pans{1*}", 3 => "1.5"; "linear")]
+fn cookware_laws(input: &str, target: u32) -> String {
+    cookware(&format!(">> servings: 2\n{input}"), target).0
+}
+
+#[test]
+fn outcomes() {
+    let (_, outcome) = timer("~{40*sqrt%min}", 4);
+    assert!(matches!(
+        outcome,
+        ScaleOutcome::NonLinear {
+            law: ScalingLaw::Sqrt
+        }
+    ));
+    let (_, outcome) = timer("~{40*%min}", 4);
+    assert!(matches!(outcome, ScaleOutcome::Scaled));
+    let (_, outcome) = timer("~{40*fixed%min}", 4);
+    assert!(matches!(outcome, ScaleOutcome::Fixed));
+    let (_, outcome) = cookware("#pans{1*ceil}", 3);
+    assert!(matches!(
+        outcome,
+        ScaleOutcome::NonLinear {
+            law: ScalingLaw::Ceil
+        }
+    ));
+}
+
+#[test]
+fn range() {
+    let (quantity, _) = cookware(">> servings: 2\n#pans{1-2*ceil}", 3);
+    assert_eq!(quantity, "2-3");
+}
+
+#[test]
+fn modes() {
+    let recipe = parse(indoc! {r#"
+        >> [timer scaling]: sqrt
+        >> [cookware scaling]: ceil
+        Bake in #pans{1} for ~{40%min} and then ~{5*%min} and ~{5*fixed%min}.
+        >> [timer scaling]: default
+        Rest ~{10%min}.
+    "#});
+    let law = |v: &ScalableValue| match v {
+        ScalableValue::NonLinear { law, .. } => Some(*law),
+        ScalableValue::Linear(_) => Some(ScalingLaw::Linear),
+        ScalableValue::Fixed(_) => Some(ScalingLaw::Fixed),
+        ScalableValue::ByServings(_) => None,
+    };
+    let timers: Vec<_> = recipe
+        .timers
+        .iter()
+        .map(|t| law(&t.quantity.as_ref().unwrap().value))
+        .collect();
+    assert_eq!(
+        timers,
+        [
+            Some(ScalingLaw::Sqrt),
+            Some(ScalingLaw::Linear),
+            Some(ScalingLaw::Fixed),
+            Some(ScalingLaw::Fixed),
+        ]
+    );
+    assert_eq!(
+        law(recipe.cookware[0].quantity.as_ref().unwrap()),
+        Some(ScalingLaw::Ceil)
+    );
+}
+
+#[test_case("@salt{1*fixed%tsp}" => ScalableValue::Fixed(Value::from(1.0)); "explicit fixed")]
+#[test_case("@salt{1%tsp}" => ScalableValue::Linear(Value::from(1.0)); "no marker")]
+#[test_case("@salt{1*sqrt%tsp}" => ScalableValue::NonLinear { value: Value::from(1.0), law: ScalingLaw::Sqrt }; "law")]
+fn auto_scale_mode(input: &str) -> ScalableValue {
+    let recipe = parse(&format!(">> [auto scale]: true\n{input}"));
+    recipe.ingredients[0].quantity.clone().unwrap().value
+}
+
+#[test]
+fn invalid_mode() {
+    let parser = CooklangParser::new(Extensions::all(), Converter::bundled());
+    let result = parser.parse(">> [timer scaling]: cubic\n~{10%min}");
+    assert!(result.report().has_errors());
+}
+
+#[test]
+fn disabled() {
+    let parser = CooklangParser::new(
+        Extensions::all() ^ Extensions::SCALING_LAWS,
+        Converter::bundled(),
+    );
+    let result = parser.parse("~{10*%min}");
+    assert!(result.report().has_errors());
+}
+
+#[test]
+fn display() {
+    let recipe = parse("~{40*sqrt%min}");
+    let quantity = recipe.timers[0].quantity.as_ref().unwrap();
+    assert_eq!(quantity.to_string(), "40*sqrt min");
+}
+
+fn ingredient(input: &str, target: u32) -> (String, ScaleOutcome) {
+    let recipe = parse(input).scale(target, &Converter::bundled());
+    let quantity = recipe.ingredients[0].quantity.as_ref().unwrap().to_string();
+    let outcome = recipe.scaled_data().unwrap().ingredients[0].clone();
+    (quantity, outcome)
+}
+
+#[test_case("@eggs{3*whole}", 5 => "4"; "whole")]
+#[test_case("@eggs{3*whole}", 8 => "6"; "exact")]
+#[test_case("@eggs{3*whole}", 1 => "1"; "at least one")]
+#[test_case("@lemons{3*half}", 5 => "4"; "half whole")]
+#[test_case("@lemons{3*half}", 3 => "2 1/2"; "half")]
+#[test_case("@lemons{1*half}", 1 => "1/2"; "at least half")]
+#[test_case("@eggs{2-3*whole}", 6 => "3-5"; "range")]
+fn discrete(input: &str, target: u32) -> String {
+    ingredient(&format!(">> servings: 4\n{input}"), target).0
+}
+
+#[test]
+fn discrete_outcome() {
+    let (_, outcome) = ingredient(">> servings: 4\n@eggs{3*whole}", 5);
+    let ScaleOutcome::Rounded {
+        unrounded: Value::Number(n),
+        law: Some(ScalingLaw::Whole),
+    } = outcome
+    else {
+        panic!("not rounded: {outcome:?}");
+    };
+    assert_eq!(n.value(), 3.75);
+
+    let (_, outcome) = ingredient(">> servings: 4\n@eggs{3*whole}", 8);
+    assert!(matches!(outcome, ScaleOutcome::Scaled));
+}
+
+#[test_case("@eggs{3*whole}", 5 => Some(4); "down")]
+#[test_case("@eggs{3*whole}", 7 => Some(8); "up")]
+#[test_case("@eggs{3*whole}", 6 => Some(8); "tie")]
+#[test_case("@eggs{3*whole}", 1 => Some(4); "not zero")]
+#[test_case("@eggs{3*half}", 5 => Some(6); "half")]
+#[test_case("@eggs{2*whole} @yolks{3*whole}", 3 => Some(4); "many")]
+#[test_case("@eggs{1/2*whole}", 5 => Some(8); "fraction")]
+#[test_case("@eggs{3*ceil}", 5 => Some(5); "not discrete")]
+fn discrete_servings(input: &str, target: u32) -> Option<u32> {
+    parse(&format!(">> servings: 4\n{input}")).discrete_servings(target)
+}
@@ -0,0 +1,66 @@
+use cooklang::{Converter, CooklangParser, Extensions, ParseOptions, UnitInfo};
+use test_case::test_case;
+
+fn parser() -> CooklangParser {
+    CooklangParser::new(Extensions::all(), Converter::bundled())
+}
+
+#[test_case("@flour{200%gramms}" => vec!["Did you mean `grams`?".to_string()])]
+#[test_case("@sugar{2%tbps}" => vec!["Did you mean `tbsp`?".to_string()])]
+#[test_case("@milk{1%Ml}" => vec!["Did you mean `ml`?".to_string()])]
+#[test_case("@sugar{1%tesp}" => vec!["Did you mean `tsp` or `tbsp`?".to_string()]; "many")]
+fn hints(input: &str) -> Vec<String> {
+    let result = parser().parse(input);
+    let warnings = result.report().warnings().collect::<Vec<_>>();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].message.contains("Unknown unit"));
+    warnings[0].hints.iter().map(|h| h.to_string()).collect()
+}
+
+#[test_case("@garlic{2%cloves}"; "not similar")]
+#[test_case("@flour{200%g}"; "known")]
+fn no_hints(input: &str) {
+    let result = parser().parse(input);
+    assert!(result.report().is_empty());
+}
+
+#[test]
+fn disabled() {
+    let parser = CooklangParser::new(
+        Extensions::all() ^ Extensions::ADVANCED_UNITS,
+        Converter::bundled(),
+    );
+    let result = parser.parse("@flour{200%gramms}");
+    assert!(result.report().is_empty());
+}
+
+#[test]
+fn timer() {
+    let result = parser().parse("~{10%minuts}");
+    let error = result.report().errors().next().unwrap();
+    assert!(error.message.contains("Unknown timer unit"));
+    assert!(error.hints.iter().any(|h| h == "Did you mean `minute`?"));
+}
+
+#[test]
+fn resolve() {
+    let options = ParseOptions {
+        resolve_unit_typos: true,
+        ..Default::default()
+    };
+    let result = parser().parse_with_options("@flour{200%gramms} @sugar{1%tesp}", options);
+    let warnings = result.report().warnings().count();
+    let recipe = result.unwrap_output();
+
+    // only one is unambiguous
+    assert_eq!(warnings, 2);
+    let flour = recipe.ingredients[0].quantity.as_ref().unwrap();
+    assert_eq!(flour.unit_text(), Some("grams"));
+    let info = flour
+        .unit()
+        .unwrap()
+        .unit_info_or_parse(parser().converter());
+    assert!(matches!(info, UnitInfo::Known(_)));
+    let sugar = recipe.ingredients[1].quantity.as_ref().unwrap();
+    assert_eq!(sugar.unit_text(), Some("tesp"));
+}
@@ -0,0 +1,56 @@
+use cooklang::{
+    convert::{ConverterBuilder, UnitsFile},
+    Converter, Quantity, Value,
+};
+
+fn round_trip(converter: &Converter) -> Converter {
+    let toml = toml::to_string(&converter.to_units_file()).expect("serialize");
+    let file: UnitsFile = toml::from_str(&toml).expect("deserialize");
+    ConverterBuilder::new()
+        .with_units_file(file)
+        .unwrap()
+        .finish()
+        .unwrap()
+}
+
+#[test]
+fn bundled() {
+    let converter = Converter::bundled();
+    assert_eq!(round_trip(&converter), converter);
+}
+
+#[test]
+fn layered() {
+    let spanish: UnitsFile = toml::from_str(include_str!("../units/spanish.toml")).unwrap();
+    let converter = ConverterBuilder::new()
+        .with_bundled_units()
+        .unwrap()
+        .with_units_file(spanish)
+        .unwrap()
+        .finish()
+        .unwrap();
+    let exported = round_trip(&converter);
+    assert_eq!(exported, converter);
+    assert!(exported.find_unit("cucharada").is_some());
+    assert!(exported.find_unit("kilogram").is_some());
+}
+
+#[test]
+fn flattened() {
+    let file = Converter::bundled().to_units_file();
+    assert!(file.si.is_none());
+    assert!(file.extend.is_none());
+    assert!(file.fractions.is_some());
+}
+
+#[test]
+fn keeps_fractions() {
+    let converter = round_trip(&Converter::bundled());
+    let mut q = Quantity::new(Value::from(0.5), Some("cup".to_string()));
+    q.convert("tsp", &converter).unwrap();
+    assert_eq!(q.to_string(), "24 tsp");
+
+    let mut q = Quantity::new(Value::from(0.125), Some("tbsp".to_string()));
+    q.convert("tsp", &converter).unwrap();
+    assert_eq!(q.to_string(), "3/8 tsp");
+}
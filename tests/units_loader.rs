@@ -0,0 +1,265 @@
+use cooklang::convert::UnitsLoader;
+use indoc::indoc;
+
+const BASE: &str = indoc! {r#"
+    [si.prefixes]
+    kilo = ["kilo"]
+    hecto = ["hecto"]
+    deca = ["deca"]
+    deci = ["deci"]
+    centi = ["centi"]
+    milli = ["milli"]
+
+    [si.symbol_prefixes]
+    kilo = ["k"]
+    hecto = ["h"]
+    deca = ["da"]
+    deci = ["d"]
+    centi = ["c"]
+    milli = ["m"]
+
+    [[quantity]]
+    quantity = "mass"
+    best = ["g", "kg"]
+    [quantity.units]
+    metric = [
+        { names = ["gram", "grams"], symbols = ["g"], ratio = 1, expand_si = true },
+    ]
+
+    [[quantity]]
+    quantity = "volume"
+    best = ["ml", "l"]
+    [quantity.units]
+    metric = [
+        { names = ["liter", "liters"], symbols = ["l"], ratio = 1, expand_si = true },
+    ]
+
+    [[quantity]]
+    quantity = "length"
+    best = ["m"]
+    [quantity.units]
+    metric = [{ names = ["meter"], symbols = ["m"], ratio = 1 }]
+
+    [[quantity]]
+    quantity = "time"
+    best = ["s"]
+    [quantity.units]
+    metric = [{ names = ["second"], symbols = ["s"], ratio = 1 }]
+
+    [[quantity]]
+    quantity = "temperature"
+    best = ["C"]
+    [quantity.units]
+    metric = [{ names = ["celsius"], symbols = ["C"], ratio = 1 }]
+"#};
+
+/// The source of the first label of the first diagnostic of a file
+fn first_label(loader: UnitsLoader, file: usize) -> (String, String) {
+    let loaded = loader.finish();
+    assert!(loaded.converter.is_none());
+    let file = &loaded.files[file];
+    let diag = file.report.iter().next().expect("a diagnostic");
+    let (span, _) = diag.labels.first().expect("a label");
+    (
+        diag.message.to_string(),
+        file.source[span.range()].to_string(),
+    )
+}
+
+#[test]
+fn valid() {
+    let loaded = UnitsLoader::new().with_toml("base.toml", BASE).finish();
+    assert!(!loaded.has_errors());
+    let converter = loaded.converter.expect("converter");
+    assert!(converter.find_unit("kilogram").is_some());
+}
+
+#[test]
+fn bundled() {
+    let mut loader = UnitsLoader::new();
+    loader.add_bundled_units();
+    let loaded = loader.finish();
+    assert!(loaded.files[0].report.is_empty());
+    assert!(loaded.converter.is_some());
+}
+
+#[test]
+fn syntax_error() {
+    let loader = UnitsLoader::new().with_toml("a.toml", "[quantity\nbest = 1");
+    let loaded = loader.finish();
+    assert!(loaded.has_errors());
+    let diag = loaded.files[0].report.iter().next().unwrap();
+    assert!(!diag.labels.is_empty());
+}
+
+#[test]
+fn invalid_field() {
+    let loader = UnitsLoader::new().with_toml("a.toml", "default_system = \"klingon\"");
+    let (_, text) = first_label(loader, 0);
+    assert_eq!(text, "\"klingon\"");
+}
+
+#[test]
+fn duplicate_unit() {
+    let layer = indoc! {r#"
+        [[quantity]]
+        quantity = "mass"
+        units = [{ names = ["gram"], symbols = [], ratio = 1 }]
+    "#};
+    let loader = UnitsLoader::new()
+        .with_toml("base.toml", BASE)
+        .with_toml("layer.toml", layer);
+    let loaded = loader.finish();
+    assert!(loaded.files[0].report.is_empty());
+    let diag = loaded.files[1].report.iter().next().unwrap();
+    let (span, _) = &diag.labels[0];
+    assert_eq!(&layer[span.range()], "\"gram\"");
+    assert!(diag.hints.iter().any(|h| h.contains("base.toml")));
+}
+
+#[test]
+fn unknown_best_unit() {
+    let source = BASE.replace(r#"best = ["m"]"#, r#"best = ["m", "furlong"]"#);
+    let loader = UnitsLoader::new().with_toml("base.toml", source);
+    let (message, text) = first_label(loader, 0);
+    assert!(message.contains("furlong"));
+    assert_eq!(text, "\"furlong\"");
+}
+
+#[test]
+fn unknown_extend_unit() {
+    let layer = indoc! {r#"
+        [extend.units]
+        furlong = { aliases = ["fur"] }
+    "#};
+    let loader = UnitsLoader::new()
+        .with_toml("base.toml", BASE)
+        .with_toml("layer.toml", layer);
+    let (_, text) = first_label(loader, 1);
+    assert_eq!(text, "furlong");
+}
+
+#[test]
+fn missing_si_prefixes() {
+    let source = &BASE[BASE.find("[[quantity]]").unwrap()..];
+    let loader = UnitsLoader::new().with_toml("base.toml", source);
+    let (_, text) = first_label(loader, 0);
+    assert_eq!(text, "expand_si");
+}
+
+#[test]
+fn missing_best_units() {
+    let source = BASE.replace("best = [\"s\"]\n", "");
+    let loader = UnitsLoader::new().with_toml("base.toml", source);
+    let (message, text) = first_label(loader, 0);
+    assert!(message.contains("time"));
+    assert_eq!(text, "\"time\"");
+}
+
+#[test]
+fn empty_best_units() {
+    let source = BASE.replace(r#"best = ["s"]"#, "best = []");
+    let loader = UnitsLoader::new().with_toml("base.toml", source);
+    let (_, text) = first_label(loader, 0);
+    assert_eq!(text, "best");
+}
+
+#[test]
+fn replaced_settings() {
+    let layer = indoc! {r#"
+        default_system = "imperial"
+
+        [[quantity]]
+        quantity = "mass"
+        best = ["kg"]
+    "#};
+    let loaded = UnitsLoader::new()
+        .with_toml("base.toml", BASE)
+        .with_toml("layer.toml", layer)
+        .finish();
+    assert!(loaded.converter.is_some());
+    let report = &loaded.files[1].report;
+    assert!(!report.has_errors());
+    // the default system was never set before
+    let warnings = report.warnings().collect::<Vec<_>>();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].message.contains("best units of mass"));
+    assert!(warnings[0].message.contains("base.toml"));
+    let (span, _) = &warnings[0].labels[0];
+    assert_eq!(&layer[span.range()], "best");
+}
+
+#[test]
+fn changed_units() {
+    let layer = indoc! {r#"
+        [extend]
+        precedence = "override"
+        [extend.units]
+        gram = { ratio = 2 }
+        meter = { names = ["metre"] }
+        s = { aliases = ["sec"] }
+    "#};
+    let loaded = UnitsLoader::new()
+        .with_toml("base.toml", BASE)
+        .with_toml("layer.toml", layer)
+        .finish();
+    assert!(loaded.converter.is_some());
+    let report = &loaded.files[1].report;
+    let warnings = report.warnings().collect::<Vec<_>>();
+    assert_eq!(warnings.len(), 3);
+    let located = |key: &str| {
+        warnings
+            .iter()
+            .find(|w| &layer[w.labels[0].0.range()] == key)
+            .unwrap_or_else(|| panic!("no warning for {key}"))
+    };
+    assert!(located("gram").message.contains("redefines the unit 'gram'"));
+    assert!(located("gram").message.contains("base.toml"));
+    assert!(located("meter").message.contains("replaces the names"));
+    assert!(located("s").message.contains("replaces the names"));
+}
+
+#[test]
+fn changed_units_same_file() {
+    let source = format!("{BASE}\n[extend.units]\ngram = {{ ratio = 2 }}\n");
+    let loaded = UnitsLoader::new().with_toml("base.toml", source).finish();
+    assert!(loaded.files[0].report.is_empty());
+}
+
+#[test]
+fn added_names_not_changed() {
+    let layer = indoc! {r#"
+        [extend.units]
+        gram = { singular = "gramo", plural = "gramos", aliases = ["gr"] }
+    "#};
+    let loaded = UnitsLoader::new()
+        .with_toml("base.toml", BASE)
+        .with_toml("layer.toml", layer)
+        .finish();
+    assert!(loaded.files[1].report.is_empty());
+}
+
+#[test]
+fn duplicate_expanded_unit() {
+    let layer = indoc! {r#"
+        [[quantity]]
+        quantity = "mass"
+        units = [{ names = ["kilogram"], symbols = ["kgm"], ratio = 1000 }]
+    "#};
+    let loader = UnitsLoader::new()
+        .with_toml("base.toml", BASE)
+        .with_toml("layer.toml", layer);
+    let (_, text) = first_label(loader, 1);
+    assert_eq!(text, "\"kilogram\"");
+}
+
+#[test]
+fn write_report() {
+    let loaded = UnitsLoader::new()
+        .with_toml("base.toml", "default_system = 1")
+        .finish();
+    let mut out = Vec::new();
+    loaded.write(false, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("base.toml"));
+}